@@ -13,6 +13,7 @@ const DEFAULT_SPEECH_THRESHOLD: f32 = 0.006; // Amplitude threshold for speech d
 const DEFAULT_SILENCE_DURATION_MS: usize = 1000; // How long silence before we consider speech ended
 const MIN_SPEECH_DURATION_MS: usize = 400; // Minimum speech duration to process
 const MAX_SPEECH_DURATION_MS: usize = 10000; // Max duration before forced processing
+const MAX_PUSH_TO_TALK_MS: usize = 30000; // Audio kept while the push-to-talk shortcut is held
 
 // Streaming mode parameters
 const STREAMING_CHUNK_MS: usize = 600; // Send chunks every 600ms for streaming transcription
@@ -45,6 +46,8 @@ pub enum AudioEvent {
     StreamingChunk(Vec<f32>),
     /// Complete utterance after silence detected
     SpeechEnded(Vec<f32>),
+    /// Audio captured while the push-to-talk shortcut was held (empty if too short)
+    PushToTalkEnded(Vec<f32>),
 }
 
 pub struct AudioCapture {
@@ -57,6 +60,8 @@ pub struct AudioCapture {
     event_sender: Sender<AudioEvent>,
     event_receiver: Receiver<AudioEvent>,
     vad_config: VadConfig,
+    // Set while the push-to-talk shortcut is held (bypasses VAD)
+    push_to_talk: Arc<AtomicBool>,
 }
 
 impl AudioCapture {
@@ -118,9 +123,16 @@ impl AudioCapture {
             event_sender,
             event_receiver,
             vad_config,
+            push_to_talk: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// Share the push-to-talk flag toggled by the global shortcut
+    pub fn with_push_to_talk(mut self, push_to_talk: Arc<AtomicBool>) -> Self {
+        self.push_to_talk = push_to_talk;
+        self
+    }
+
     pub fn device_name(&self) -> Option<String> {
         self.device.name().ok()
     }
@@ -129,6 +141,7 @@ impl AudioCapture {
         let sender = self.audio_sender.clone();
         let event_sender = self.event_sender.clone();
         let is_recording = self.is_recording.clone();
+        let push_to_talk = self.push_to_talk.clone();
         let channels = self.config.channels as usize;
         let native_rate = self.native_sample_rate;
         let resample_ratio = native_rate as f64 / TARGET_SAMPLE_RATE as f64;
@@ -141,6 +154,7 @@ impl AudioCapture {
         let silence_samples = (native_rate as usize * silence_duration_ms) / 1000;
         let min_speech_samples = (native_rate as usize * MIN_SPEECH_DURATION_MS) / 1000;
         let max_speech_samples = (native_rate as usize * MAX_SPEECH_DURATION_MS) / 1000;
+        let max_push_to_talk_samples = (native_rate as usize * MAX_PUSH_TO_TALK_MS) / 1000;
 
         // Streaming mode: send chunks every STREAMING_CHUNK_MS
         let streaming_chunk_samples = (native_rate as usize * STREAMING_CHUNK_MS) / 1000;
@@ -172,6 +186,41 @@ impl AudioCapture {
                         data.to_vec()
                     };
 
+                    // PUSH-TO-TALK: capture everything while the shortcut is held, ignoring VAD
+                    let push_to_talk_held = push_to_talk.load(Ordering::SeqCst);
+                    if push_to_talk_held && !state.push_to_talk_active {
+                        state.reset();
+                        state.push_to_talk_active = true;
+                        state.speech_started = true;
+                    }
+
+                    if state.push_to_talk_active {
+                        if push_to_talk_held {
+                            let samples_added = mono_samples.len();
+                            if state.buffer.len() < max_push_to_talk_samples {
+                                state.buffer.extend(mono_samples);
+                            }
+                            state.samples_since_last_chunk += samples_added;
+
+                            if state.samples_since_last_chunk >= streaming_chunk_samples {
+                                let resampled = resample(&state.buffer, resample_ratio);
+                                let _ = event_sender.try_send(AudioEvent::StreamingChunk(resampled));
+                                state.samples_since_last_chunk = 0;
+                            }
+                        } else {
+                            // Shortcut released: send whatever was captured (nothing for a quick tap,
+                            // so the listening window still gets closed)
+                            let samples = if state.buffer.len() >= min_speech_samples {
+                                resample(&state.buffer, resample_ratio)
+                            } else {
+                                Vec::new()
+                            };
+                            let _ = event_sender.try_send(AudioEvent::PushToTalkEnded(samples));
+                            state.reset();
+                        }
+                        return;
+                    }
+
                     // Calculate RMS amplitude for this chunk
                     let rms = (mono_samples.iter().map(|s| s * s).sum::<f32>() / mono_samples.len() as f32).sqrt();
                     let is_speech = rms > speech_threshold;
@@ -248,6 +297,8 @@ struct VadState {
     silence_counter: usize,
     // For streaming mode: track samples since last streaming chunk
     samples_since_last_chunk: usize,
+    // Capturing a push-to-talk utterance
+    push_to_talk_active: bool,
 }
 
 impl VadState {
@@ -257,6 +308,7 @@ impl VadState {
             speech_started: false,
             silence_counter: 0,
             samples_since_last_chunk: 0,
+            push_to_talk_active: false,
         }
     }
 
@@ -265,6 +317,7 @@ impl VadState {
         self.speech_started = false;
         self.silence_counter = 0;
         self.samples_since_last_chunk = 0;
+        self.push_to_talk_active = false;
     }
 }
 
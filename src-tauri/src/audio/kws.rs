@@ -0,0 +1,442 @@
+use anyhow::{anyhow, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// Feature extraction parameters (audio is 16kHz mono, same as Whisper)
+const SAMPLE_RATE: usize = 16000;
+const FRAME_LEN: usize = 400; // 25ms
+const FRAME_HOP: usize = 160; // 10ms
+const FFT_SIZE: usize = 512;
+const NUM_MEL_FILTERS: usize = 26;
+const NUM_COEFFS: usize = 13; // c0 is dropped, so 12 coefficients per frame
+const PRE_EMPHASIS: f32 = 0.97;
+
+// Detection parameters
+const DETECTION_WINDOW_MS: usize = 2500; // Only look at the most recent audio
+const DEFAULT_THRESHOLD: f32 = 4.0; // Used until at least 2 templates are enrolled
+const THRESHOLD_MARGIN: f32 = 1.25; // Headroom over the worst template-to-template distance
+const MAX_TEMPLATES: usize = 5;
+
+/// MFCC frames of one enrolled sample of the wake word
+type Template = Vec<Vec<f32>>;
+
+#[derive(Serialize, Deserialize, Default)]
+struct TemplateFile {
+    templates: Vec<Template>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct KeywordSpotterStatus {
+    pub template_count: usize,
+    pub enrolling: bool,
+    pub threshold: f32,
+}
+
+/// Cheap keyword spotter (MFCC features + DTW template matching)
+///
+/// Runs before streaming Whisper so full transcription only happens once the
+/// wake word has probably been heard.
+pub struct KeywordSpotter {
+    templates: Vec<Template>,
+    threshold: f32,
+    /// Next complete utterance is captured as a template instead of processed
+    enrolling: bool,
+}
+
+impl KeywordSpotter {
+    /// Load enrolled templates from disk (empty spotter if none)
+    pub fn load() -> Self {
+        let templates = Self::templates_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<TemplateFile>(&content).ok())
+            .map(|file| file.templates)
+            .unwrap_or_default();
+
+        let mut spotter = Self {
+            templates,
+            threshold: DEFAULT_THRESHOLD,
+            enrolling: false,
+        };
+        spotter.calibrate();
+        spotter
+    }
+
+    fn templates_path() -> Option<PathBuf> {
+        ProjectDirs::from("com", "robert", "Robert")
+            .map(|dirs| dirs.data_dir().join("kws_templates.json"))
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::templates_path().ok_or_else(|| anyhow!("Could not determine templates path"))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = TemplateFile {
+            templates: self.templates.clone(),
+        };
+        std::fs::write(&path, serde_json::to_string(&file)?)?;
+        Ok(())
+    }
+
+    pub fn has_templates(&self) -> bool {
+        !self.templates.is_empty()
+    }
+
+    pub fn is_enrolling(&self) -> bool {
+        self.enrolling
+    }
+
+    pub fn start_enrollment(&mut self) {
+        self.enrolling = true;
+    }
+
+    pub fn status(&self) -> KeywordSpotterStatus {
+        KeywordSpotterStatus {
+            template_count: self.templates.len(),
+            enrolling: self.enrolling,
+            threshold: self.threshold,
+        }
+    }
+
+    /// Add a recorded sample of the wake word (16kHz) and persist templates
+    pub fn enroll(&mut self, samples: &[f32]) -> Result<usize> {
+        self.enrolling = false;
+
+        let features = mfcc(trim_silence(samples));
+        if features.len() < 10 {
+            return Err(anyhow!("Sample too short to enroll"));
+        }
+
+        if self.templates.len() >= MAX_TEMPLATES {
+            self.templates.remove(0);
+        }
+        self.templates.push(features);
+        self.calibrate();
+        self.save()?;
+
+        Ok(self.templates.len())
+    }
+
+    /// Remove all enrolled templates
+    pub fn clear(&mut self) -> Result<()> {
+        self.templates.clear();
+        self.enrolling = false;
+        self.calibrate();
+        self.save()
+    }
+
+    /// Check whether the wake word was probably spoken in the given audio (16kHz)
+    pub fn detect(&self, samples: &[f32]) -> bool {
+        if self.templates.is_empty() {
+            return false;
+        }
+
+        let window = (SAMPLE_RATE * DETECTION_WINDOW_MS) / 1000;
+        let start = samples.len().saturating_sub(window);
+        let features = mfcc(&samples[start..]);
+        if features.is_empty() {
+            return false;
+        }
+
+        self.templates
+            .iter()
+            .map(|template| subsequence_dtw(template, &features))
+            .any(|distance| distance <= self.threshold)
+    }
+
+    /// Derive the detection threshold from the spread between enrolled templates
+    fn calibrate(&mut self) {
+        let mut worst: Option<f32> = None;
+        for (i, a) in self.templates.iter().enumerate() {
+            for b in &self.templates[i + 1..] {
+                let distance = dtw(a, b);
+                worst = Some(worst.map_or(distance, |w| w.max(distance)));
+            }
+        }
+
+        self.threshold = worst
+            .map(|w| w * THRESHOLD_MARGIN)
+            .unwrap_or(DEFAULT_THRESHOLD);
+    }
+}
+
+// ============================================================================
+// MFCC features
+// ============================================================================
+
+/// Compute MFCC frames (c1..c12) with cepstral mean normalization
+fn mfcc(samples: &[f32]) -> Vec<Vec<f32>> {
+    if samples.len() < FRAME_LEN {
+        return Vec::new();
+    }
+
+    let window: Vec<f32> = (0..FRAME_LEN)
+        .map(|n| 0.54 - 0.46 * (2.0 * std::f32::consts::PI * n as f32 / (FRAME_LEN - 1) as f32).cos())
+        .collect();
+    let filters = mel_filterbank();
+
+    let mut frames = Vec::new();
+    let mut start = 0;
+    while start + FRAME_LEN <= samples.len() {
+        let frame = &samples[start..start + FRAME_LEN];
+
+        // Pre-emphasis + Hamming window
+        let mut re = vec![0.0f32; FFT_SIZE];
+        let mut im = vec![0.0f32; FFT_SIZE];
+        for n in 0..FRAME_LEN {
+            let prev = if n > 0 { frame[n - 1] } else { 0.0 };
+            re[n] = (frame[n] - PRE_EMPHASIS * prev) * window[n];
+        }
+
+        fft(&mut re, &mut im);
+
+        let power: Vec<f32> = (0..FFT_SIZE / 2 + 1)
+            .map(|k| (re[k] * re[k] + im[k] * im[k]) / FFT_SIZE as f32)
+            .collect();
+
+        let log_energies: Vec<f32> = filters
+            .iter()
+            .map(|filter| {
+                let energy: f32 = filter.iter().zip(&power).map(|(w, p)| w * p).sum();
+                energy.max(1e-10).ln()
+            })
+            .collect();
+
+        // DCT-II, dropping c0 (overall loudness)
+        let coeffs: Vec<f32> = (1..NUM_COEFFS)
+            .map(|k| {
+                log_energies
+                    .iter()
+                    .enumerate()
+                    .map(|(m, e)| {
+                        e * (std::f32::consts::PI * k as f32 * (m as f32 + 0.5) / NUM_MEL_FILTERS as f32).cos()
+                    })
+                    .sum()
+            })
+            .collect();
+
+        frames.push(coeffs);
+        start += FRAME_HOP;
+    }
+
+    // Cepstral mean normalization (removes microphone/channel coloration)
+    let dims = NUM_COEFFS - 1;
+    let mut mean = vec![0.0f32; dims];
+    for frame in &frames {
+        for (m, c) in mean.iter_mut().zip(frame) {
+            *m += c / frames.len() as f32;
+        }
+    }
+    for frame in &mut frames {
+        for (c, m) in frame.iter_mut().zip(&mean) {
+            *c -= m;
+        }
+    }
+
+    frames
+}
+
+/// Triangular filters spaced on the mel scale, over FFT_SIZE / 2 + 1 bins
+fn mel_filterbank() -> Vec<Vec<f32>> {
+    let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+
+    let low = hz_to_mel(80.0);
+    let high = hz_to_mel(SAMPLE_RATE as f32 / 2.0);
+    let bins: Vec<usize> = (0..NUM_MEL_FILTERS + 2)
+        .map(|i| {
+            let mel = low + (high - low) * i as f32 / (NUM_MEL_FILTERS + 1) as f32;
+            ((FFT_SIZE + 1) as f32 * mel_to_hz(mel) / SAMPLE_RATE as f32).floor() as usize
+        })
+        .collect();
+
+    (0..NUM_MEL_FILTERS)
+        .map(|m| {
+            let (left, center, right) = (bins[m], bins[m + 1], bins[m + 2]);
+            let mut filter = vec![0.0f32; FFT_SIZE / 2 + 1];
+            for (k, weight) in filter.iter_mut().enumerate() {
+                if k >= left && k < center && center > left {
+                    *weight = (k - left) as f32 / (center - left) as f32;
+                } else if k >= center && k <= right && right > center {
+                    *weight = (right - k) as f32 / (right - center) as f32;
+                }
+            }
+            filter
+        })
+        .collect()
+}
+
+/// In-place iterative radix-2 FFT (length must be a power of two)
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_re, w_im) = ((angle * k as f32).cos(), (angle * k as f32).sin());
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Drop leading/trailing frames much quieter than the loudest one
+fn trim_silence(samples: &[f32]) -> &[f32] {
+    let energies: Vec<f32> = samples
+        .chunks(FRAME_HOP)
+        .map(|c| c.iter().map(|s| s * s).sum::<f32>() / c.len() as f32)
+        .collect();
+    let max = energies.iter().cloned().fold(0.0f32, f32::max);
+    let floor = max * 0.01;
+
+    let first = energies.iter().position(|e| *e > floor).unwrap_or(0);
+    let last = energies.iter().rposition(|e| *e > floor).unwrap_or(energies.len().saturating_sub(1));
+
+    let start = first * FRAME_HOP;
+    let end = ((last + 1) * FRAME_HOP).min(samples.len());
+    if start < end {
+        &samples[start..end]
+    } else {
+        samples
+    }
+}
+
+// ============================================================================
+// Dynamic time warping
+// ============================================================================
+
+fn frame_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt()
+}
+
+/// DTW distance between two whole sequences, normalized by their combined length
+fn dtw(a: &[Vec<f32>], b: &[Vec<f32>]) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return f32::INFINITY;
+    }
+
+    let mut prev = vec![f32::INFINITY; b.len() + 1];
+    let mut curr = vec![f32::INFINITY; b.len() + 1];
+    prev[0] = 0.0;
+
+    for fa in a {
+        curr[0] = f32::INFINITY;
+        for (j, fb) in b.iter().enumerate() {
+            let best = prev[j].min(prev[j + 1]).min(curr[j]);
+            curr[j + 1] = frame_distance(fa, fb) + best;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()] / (a.len() + b.len()) as f32
+}
+
+/// Best DTW match of `template` anywhere inside `input` (free start and end),
+/// normalized by twice the template length
+fn subsequence_dtw(template: &[Vec<f32>], input: &[Vec<f32>]) -> f32 {
+    if template.is_empty() || input.is_empty() {
+        return f32::INFINITY;
+    }
+
+    // Rows follow the template, columns follow the input; row 0 may start at any column
+    let mut prev: Vec<f32> = input.iter().map(|f| frame_distance(&template[0], f)).collect();
+    let mut curr = vec![f32::INFINITY; input.len()];
+
+    for ft in &template[1..] {
+        for (j, fi) in input.iter().enumerate() {
+            let mut best = prev[j];
+            if j > 0 {
+                best = best.min(prev[j - 1]).min(curr[j - 1]);
+            }
+            curr[j] = frame_distance(ft, fi) + best;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    let best = prev.iter().cloned().fold(f32::INFINITY, f32::min);
+    best / (2 * template.len()) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two-tone test signal whose pitch glides over time, like a spoken word
+    fn glide(start_hz: f32, end_hz: f32, ms: usize) -> Vec<f32> {
+        let n = SAMPLE_RATE * ms / 1000;
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let hz = start_hz + (end_hz - start_hz) * i as f32 / n as f32;
+                0.3 * (2.0 * std::f32::consts::PI * hz * t).sin()
+                    + 0.1 * (2.0 * std::f32::consts::PI * 3.0 * hz * t).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fft_impulse_is_flat() {
+        let mut re = vec![0.0f32; 8];
+        let mut im = vec![0.0f32; 8];
+        re[0] = 1.0;
+        fft(&mut re, &mut im);
+        assert!(re.iter().all(|v| (v - 1.0).abs() < 1e-6));
+        assert!(im.iter().all(|v| v.abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_mfcc_frame_count() {
+        let frames = mfcc(&vec![0.1; SAMPLE_RATE]);
+        assert_eq!(frames.len(), (SAMPLE_RATE - FRAME_LEN) / FRAME_HOP + 1);
+        assert!(frames.iter().all(|f| f.len() == NUM_COEFFS - 1));
+    }
+
+    #[test]
+    fn test_dtw_identical_is_zero() {
+        let a = mfcc(&glide(300.0, 900.0, 600));
+        assert!(dtw(&a, &a) < 1e-4);
+    }
+
+    #[test]
+    fn test_subsequence_dtw_finds_embedded_keyword() {
+        let keyword = glide(300.0, 900.0, 600);
+        let template = mfcc(&keyword);
+
+        // Keyword followed by unrelated audio, as in "OK Robert, start recording"
+        let mut with_keyword = keyword.clone();
+        with_keyword.extend(glide(1500.0, 1200.0, 800));
+        let without_keyword = glide(1500.0, 1200.0, 1400);
+
+        let hit = subsequence_dtw(&template, &mfcc(&with_keyword));
+        let miss = subsequence_dtw(&template, &mfcc(&without_keyword));
+        assert!(hit < miss, "hit={} miss={}", hit, miss);
+    }
+}
@@ -1,3 +1,5 @@
 mod capture;
+mod kws;
 
 pub use capture::{AudioCapture, AudioEvent, DeviceInfo, VadConfig};
+pub use kws::{KeywordSpotter, KeywordSpotterStatus};
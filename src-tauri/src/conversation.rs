@@ -0,0 +1,114 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::llm::Message;
+use crate::state::{ActiveConversation, AppState};
+use crate::storage::Database;
+use crate::DbState;
+
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Whether a conversation idle since `last_activity` should be closed
+fn is_expired(last_activity: DateTime<Utc>, idle_timeout_minutes: u64, now: DateTime<Utc>) -> bool {
+    idle_timeout_minutes > 0 && now - last_activity > Duration::minutes(idle_timeout_minutes as i64)
+}
+
+/// Conversation for a persona's next command: the active one, or a new one
+/// (empty history) if there is none or it has been idle too long
+pub fn ensure_conversation(state: &mut AppState, db: Option<&DbState>, persona_id: &str, first_command: &str) -> Uuid {
+    let now = Utc::now();
+    let timeout = state.settings.conversation_idle_timeout_minutes;
+
+    if let Some(active) = state.active_conversations.get_mut(persona_id) {
+        if !is_expired(active.last_activity, timeout, now) {
+            active.last_activity = now;
+            return active.id;
+        }
+        println!("[Conversation] Idle timeout, starting a new conversation ({})", persona_id);
+    }
+
+    let title: String = first_command.chars().take(MAX_TITLE_CHARS).collect();
+    let id = db
+        .and_then(|db| db.lock().ok())
+        .and_then(|db| db.create_conversation(persona_id, &title).ok())
+        .map(|c| c.id)
+        // Without database the conversation only lives in memory
+        .unwrap_or_else(Uuid::new_v4);
+
+    state.conversation_histories.insert(persona_id.to_string(), Vec::new());
+    state.conversation_summaries.remove(persona_id);
+    state.active_conversations.insert(persona_id.to_string(), ActiveConversation { id, last_activity: now });
+    id
+}
+
+/// Forget the persona's conversation; the next command starts a new one
+pub fn start_new_conversation(state: &mut AppState, persona_id: &str) {
+    state.active_conversations.remove(persona_id);
+    state.conversation_histories.remove(persona_id);
+    state.conversation_summaries.remove(persona_id);
+}
+
+/// Store new messages of a conversation (tool_use and tool_result blocks included)
+pub fn persist_messages(db: Option<&DbState>, conversation_id: Uuid, messages: &[Message]) {
+    let db = match db.and_then(|db| db.lock().ok()) {
+        Some(db) => db,
+        None => return,
+    };
+
+    for message in messages {
+        let content = match serde_json::to_value(&message.content) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        if let Err(e) = db.add_conversation_message(conversation_id, &message.role, &content) {
+            eprintln!("[Conversation] Failed to store message: {}", e);
+        }
+    }
+}
+
+/// Messages of a stored conversation, as sent to the LLM
+pub fn load_messages(db: &Database, conversation_id: Uuid) -> Result<Vec<Message>> {
+    let messages = db
+        .get_conversation_messages(conversation_id)?
+        .into_iter()
+        .filter_map(|m| {
+            serde_json::from_value(serde_json::json!({ "role": m.role, "content": m.content })).ok()
+        })
+        .collect();
+    Ok(messages)
+}
+
+/// Make a stored conversation the active one of its persona
+pub fn resume_conversation(state: &mut AppState, db: &Database, conversation_id: Uuid) -> Result<String> {
+    let conversation = db
+        .get_conversation(conversation_id)?
+        .ok_or_else(|| anyhow::anyhow!("Conversation not found"))?;
+    let messages = load_messages(db, conversation_id)?;
+
+    // The whole conversation is loaded; the next command compacts it if needed
+    state.conversation_histories.insert(conversation.persona_id.clone(), messages);
+    state.conversation_summaries.remove(&conversation.persona_id);
+    state.active_conversations.insert(
+        conversation.persona_id.clone(),
+        ActiveConversation { id: conversation.id, last_activity: Utc::now() },
+    );
+    Ok(conversation.persona_id)
+}
+
+/// At startup, continue each persona's latest conversation if it is still fresh
+pub fn restore_conversations(state: &mut AppState, db: &Database) {
+    let now = Utc::now();
+    let timeout = state.settings.conversation_idle_timeout_minutes;
+
+    for persona in state.settings.resolved_personas() {
+        let latest = match db.get_latest_conversation(&persona.id) {
+            Ok(Some(c)) if !is_expired(c.updated_at, timeout, now) => c,
+            _ => continue,
+        };
+        match resume_conversation(state, db, latest.id) {
+            Ok(_) => println!("[Conversation] Resumed '{}' ({} messages)", latest.title, latest.message_count),
+            Err(e) => eprintln!("[Conversation] Failed to resume '{}': {}", latest.title, e),
+        }
+    }
+}
@@ -0,0 +1,597 @@
+use chrono::{
+    DateTime, Datelike, Days, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+
+use crate::intent::normalize;
+
+/// Start (inclusive) and end (exclusive) of a time range, each optional
+pub type DateRange = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Words that don't change the meaning of a date expression
+const FILLER_WORDS: &[&str] = &["on", "the", "of", "in", "during", "le", "la", "les", "l", "de", "du", "d", "en", "pendant", "dans"];
+
+const WEEKDAYS: &[(&str, Weekday)] = &[
+    ("monday", Weekday::Mon), ("tuesday", Weekday::Tue), ("wednesday", Weekday::Wed), ("thursday", Weekday::Thu),
+    ("friday", Weekday::Fri), ("saturday", Weekday::Sat), ("sunday", Weekday::Sun),
+    ("lundi", Weekday::Mon), ("mardi", Weekday::Tue), ("mercredi", Weekday::Wed), ("jeudi", Weekday::Thu),
+    ("vendredi", Weekday::Fri), ("samedi", Weekday::Sat), ("dimanche", Weekday::Sun),
+];
+
+const NUMBERS: &[(&str, u64)] = &[
+    ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5), ("six", 6), ("seven", 7), ("ten", 10),
+    ("un", 1), ("deux", 2), ("trois", 3), ("quatre", 4), ("cinq", 5), ("sept", 7), ("dix", 10),
+];
+
+/// Parts of the day, as hours of the day
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PartOfDay {
+    Morning,
+    Afternoon,
+    Evening,
+}
+
+impl PartOfDay {
+    fn from_word(word: &str) -> Option<Self> {
+        match word {
+            "morning" | "matin" | "matinee" => Some(PartOfDay::Morning),
+            "afternoon" => Some(PartOfDay::Afternoon),
+            "evening" | "tonight" | "soir" | "soiree" => Some(PartOfDay::Evening),
+            _ => None,
+        }
+    }
+
+    /// Hour of a reminder set for this part of the day without a time
+    fn default_hour(self) -> u32 {
+        match self {
+            PartOfDay::Morning => 9,
+            PartOfDay::Afternoon => 14,
+            PartOfDay::Evening => 19,
+        }
+    }
+
+    fn hours(self) -> (u32, u32) {
+        match self {
+            PartOfDay::Morning => (6, 12),
+            PartOfDay::Afternoon => (12, 18),
+            PartOfDay::Evening => (18, 24),
+        }
+    }
+}
+
+/// Local calendar days covered by an expression, before the part of the day
+enum Period {
+    /// First day and number of days
+    Span(NaiveDate, u64),
+    /// First day (inclusive) and last day (exclusive) of a month range
+    Months(NaiveDate, NaiveDate),
+}
+
+/// Time range of a date expression, in English or French: "yesterday", "last Tuesday
+/// afternoon", "la semaine dernière", "il y a 3 jours", "the last 7 days", "ce matin",
+/// ISO days ("2026-03-12"), months ("2026-03") and ranges ("2026-03-01..2026-03-15").
+/// Days are calendar days in the time zone of `now`.
+pub fn parse_when<Tz: TimeZone>(expr: &str, now: &DateTime<Tz>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    if let Some(range) = parse_iso(expr.trim()) {
+        return Some(to_range(range, None, now));
+    }
+
+    let mut words: Vec<String> = day_words(expr)
+        .split_whitespace()
+        .filter(|w| !FILLER_WORDS.contains(w))
+        .map(str::to_string)
+        .collect();
+
+    // "afternoon", "après-midi", "ce soir"...
+    let mut part = None;
+    words.retain(|w| {
+        let found = PartOfDay::from_word(w);
+        part = part.or(found);
+        found.is_none()
+    });
+
+    let today = now.date_naive();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let days = match words.as_slice() {
+        [] | ["today"] | ["aujourd", "hui"] | ["this"] | ["ce"] | ["cet"] | ["cette"] => Period::Span(today, 1),
+        ["yesterday"] | ["hier"] => Period::Span(today - Days::new(1), 1),
+        ["day", "before", "yesterday"] | ["avant", "hier"] => Period::Span(today - Days::new(2), 1),
+        ["tomorrow"] | ["demain"] => Period::Span(today + Days::new(1), 1),
+        ["day", "after", "tomorrow"] | ["apres", "demain"] => Period::Span(today + Days::new(2), 1),
+        ["this", "week"] | ["cette", "semaine"] => Period::Span(week_start(today), 7),
+        ["last", "week"] | ["semaine", "derniere"] => Period::Span(week_start(today) - Days::new(7), 7),
+        ["this", "month"] | ["ce", "mois"] | ["ce", "mois", "ci"] => {
+            let start = today.with_day(1)?;
+            Period::Months(start, start + Months::new(1))
+        }
+        ["last", "month"] | ["mois", "dernier"] => {
+            let end = today.with_day(1)?;
+            Period::Months(end - Months::new(1), end)
+        }
+        ["this", "year"] | ["cette", "annee"] => {
+            let start = NaiveDate::from_ymd_opt(today.year(), 1, 1)?;
+            Period::Months(start, start + Months::new(12))
+        }
+        ["last", "year"] | ["annee", "derniere"] => {
+            let end = NaiveDate::from_ymd_opt(today.year(), 1, 1)?;
+            Period::Months(end - Months::new(12), end)
+        }
+        // "3 days ago", "il y a 3 jours"
+        [n, "days" | "day", "ago"] | ["il", "y", "a", n, "jours" | "jour"] => {
+            Period::Span(today - Days::new(number(n)?), 1)
+        }
+        // "last 7 days", "les 7 derniers jours" (today included)
+        ["last" | "past", n, "days"] | [n, "derniers", "jours"] => {
+            let n = number(n)?;
+            Period::Span(today - Days::new(n.checked_sub(1)?), n)
+        }
+        // "last Tuesday", "mardi dernier": the one before today
+        ["last", day] | [day, "dernier"] => {
+            let weekday = weekday(day)?;
+            let back = match (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7 {
+                0 => 7,
+                n => n,
+            };
+            Period::Span(today - Days::new(back as u64), 1)
+        }
+        // "Tuesday", "this Tuesday", "mardi": the latest one, today included
+        [day] | ["this" | "ce", day] => {
+            let weekday = weekday(day)?;
+            let back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+            Period::Span(today - Days::new(back as u64), 1)
+        }
+        _ => return None,
+    };
+
+    let (first, end) = match days {
+        Period::Span(first, n) => (first, first + Days::new(n)),
+        Period::Months(first, end) => (first, end),
+    };
+    // A part of the day only narrows a single day
+    let part = part.filter(|_| end == first + Days::new(1));
+    Some(to_range((first, end), part, now))
+}
+
+/// Normalized expression, "après-midi" as one word so "après-demain" isn't an afternoon
+fn day_words(expr: &str) -> String {
+    normalize(expr).replace("apres midi", "afternoon")
+}
+
+/// "2026-03-12", "2026-03", "2026-03-01..2026-03-15" (also with " to ", " au " or "/")
+fn parse_iso(expr: &str) -> Option<(NaiveDate, NaiveDate)> {
+    for separator in ["..", " to ", " au ", "/"] {
+        if let Some((from, to)) = expr.split_once(separator) {
+            let (first, _) = parse_iso(from.trim())?;
+            let (_, end) = parse_iso(to.trim())?;
+            return Some((first, end));
+        }
+    }
+
+    if let Ok(day) = NaiveDate::parse_from_str(expr, "%Y-%m-%d") {
+        return Some((day, day + Days::new(1)));
+    }
+    let month = NaiveDate::parse_from_str(&format!("{}-01", expr), "%Y-%m-%d").ok()?;
+    Some((month, month + Months::new(1)))
+}
+
+fn number(word: &str) -> Option<u64> {
+    word.parse()
+        .ok()
+        .or_else(|| NUMBERS.iter().find(|(name, _)| *name == word).map(|(_, n)| *n))
+        .filter(|n| (1..=366).contains(n))
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    WEEKDAYS.iter().find(|(name, _)| *name == word).map(|(_, day)| *day)
+}
+
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Days::new(day.weekday().num_days_from_monday() as u64)
+}
+
+/// Days `first` (inclusive) to `end` (exclusive), narrowed to a part of the day
+fn to_range<Tz: TimeZone>(
+    (first, end): (NaiveDate, NaiveDate),
+    part: Option<PartOfDay>,
+    now: &DateTime<Tz>,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    match part {
+        Some(part) => {
+            let (from, to) = part.hours();
+            let start = local_time(first, from, now);
+            (start, start + chrono::Duration::hours((to - from) as i64))
+        }
+        None => (local_time(first, 0, now), local_time(end, 0, now)),
+    }
+}
+
+fn local_time<Tz: TimeZone>(day: NaiveDate, hour: u32, now: &DateTime<Tz>) -> DateTime<Utc> {
+    local_datetime(day.and_time(NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or_default()), now)
+}
+
+fn local_datetime<Tz: TimeZone>(time: NaiveDateTime, now: &DateTime<Tz>) -> DateTime<Utc> {
+    now.timezone()
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&time))
+}
+
+/// Words skipped in durations and due times
+const DUE_FILLER_WORDS: &[&str] = &["in", "dans", "for", "pendant", "of", "d", "de", "du", "on", "the", "le", "la", "l"];
+
+fn unit_seconds(word: &str) -> Option<f64> {
+    match word {
+        "s" | "sec" | "secs" | "second" | "seconds" | "seconde" | "secondes" => Some(1.0),
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(60.0),
+        "h" | "hr" | "hrs" | "hour" | "hours" | "heure" | "heures" => Some(3600.0),
+        "day" | "days" | "jour" | "jours" => Some(86400.0),
+        "week" | "weeks" | "semaine" | "semaines" => Some(604800.0),
+        _ => None,
+    }
+}
+
+/// Normalized words with numbers split from their units: "1h30" is "1 h 30", "3pm" is "3 pm"
+fn split_numbers(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        let mut current = String::new();
+        for c in word.chars() {
+            if !current.is_empty() && current.ends_with(|p: char| p.is_ascii_digit()) != c.is_ascii_digit() {
+                words.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        words.push(current);
+    }
+    words
+}
+
+/// Length of a duration in English or French: "20 minutes", "1h30", "2 min 30",
+/// "an hour and a half", "une demi-heure", "trois quarts d'heure"
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let mut seconds = 0.0;
+    // Number waiting for its unit, and the last unit (for "and a half", "1h30")
+    let mut amount: Option<f64> = None;
+    let mut last_unit: Option<f64> = None;
+    let mut and = false;
+
+    for word in split_numbers(&normalize(text)) {
+        let word = word.as_str();
+        if let Some(unit) = unit_seconds(word) {
+            seconds += amount.take().unwrap_or(1.0) * unit;
+            last_unit = Some(unit);
+            and = false;
+            continue;
+        }
+        let fraction = match word {
+            "half" | "demi" | "demie" => Some(0.5),
+            "quarter" | "quarters" | "quart" | "quarts" => Some(0.25),
+            _ => None,
+        };
+        match (fraction, amount, last_unit) {
+            // "one and a half hours", "an hour and a half"
+            (Some(f), Some(n), _) if and => amount = Some(n + f),
+            (Some(f), None, Some(unit)) if and => seconds += f * unit,
+            // "half an hour", "trois quarts d'heure"
+            (Some(f), n, _) => amount = Some(n.unwrap_or(1.0) * f),
+            (None, ..) => match word {
+                "and" | "et" => and = true,
+                "a" | "an" | "one" | "un" | "une" => {
+                    if amount.is_none() && !and {
+                        amount = Some(1.0);
+                    }
+                }
+                _ if DUE_FILLER_WORDS.contains(&word) => {}
+                _ => {
+                    if amount.is_some() {
+                        return None;
+                    }
+                    amount = Some(word.parse::<u32>().ok().map(f64::from).or_else(|| number(word).map(|n| n as f64))?);
+                }
+            },
+        }
+    }
+
+    // "1h30", "2 min 30": a trailing number is in the next smaller unit
+    if let Some(n) = amount {
+        seconds += n * match last_unit.map(|unit| unit as u32) {
+            Some(3600) => 60.0,
+            Some(60) => 1.0,
+            _ => return None,
+        };
+    }
+    // Absurd amounts ("100000000 weeks") are out of range rather than a panic
+    Duration::try_seconds(seconds.round() as i64).filter(|d| *d > Duration::zero())
+}
+
+/// Time of day at the start of `words` ("15 h 30", "15 30" from "15:30", "3 pm", "9 o
+/// clock"): hour, minute, whether am/pm was said, and the number of words read.
+/// A bare number ("9") reads one word.
+fn parse_clock(words: &[&str]) -> Option<(u32, u32, bool, usize)> {
+    let hour: u32 = words.first()?.parse().ok().filter(|h| *h <= 24)?;
+    let mut read = 1;
+    match words.get(1) {
+        Some(&"h") | Some(&"heure") | Some(&"heures") => read = 2,
+        Some(&"o") if words.get(2) == Some(&"clock") => read = 3,
+        _ => {}
+    }
+    let mut minute = 0;
+    if let Some(m) = words.get(read).filter(|m| m.len() == 2).and_then(|m| m.parse::<u32>().ok()).filter(|m| *m < 60) {
+        minute = m;
+        read += 1;
+    }
+    match words.get(read) {
+        Some(&"am") | Some(&"pm") if (1..=12).contains(&hour) => {
+            let pm = words[read] == "pm";
+            Some((hour % 12 + if pm { 12 } else { 0 }, minute, true, read + 1))
+        }
+        _ => Some((hour, minute, false, read)),
+    }
+}
+
+/// When a reminder is due: a delay ("in 20 minutes", "dans 1h30"), or a time and/or day
+/// ("at 15:30", "5pm", "demain à 9h", "next Monday at noon", "tomorrow morning", "ce
+/// soir", "2026-03-12 15:30"). A time without a day that has already passed today is
+/// tomorrow; a day without a time is at 9:00.
+pub fn parse_due<Tz: TimeZone>(expr: &str, now: &DateTime<Tz>) -> Option<DateTime<Utc>> {
+    let expr = expr.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(expr) {
+        return Some(time.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(expr, format) {
+            return Some(local_datetime(time, now));
+        }
+    }
+    // "9h" alone is a time of day, "dans 9h" a delay
+    let words = split_numbers(&normalize(expr));
+    if words.iter().any(|w| w == "in" || w == "dans") || !words.iter().any(|w| w == "h") {
+        if let Some(delay) = parse_duration(expr) {
+            return now.with_timezone(&Utc).checked_add_signed(delay);
+        }
+    }
+
+    // "2026-03-20 at 10"
+    let mut iso_day = None;
+    let expr: String = expr
+        .split_whitespace()
+        .filter(|w| match NaiveDate::parse_from_str(w, "%Y-%m-%d") {
+            Ok(day) => {
+                iso_day = Some(day);
+                false
+            }
+            Err(_) => true,
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let words = split_numbers(&day_words(&expr));
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let mut time: Option<(u32, u32, bool)> = None;
+    let mut part = None;
+    let mut rest: Vec<&str> = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        if time.is_none() {
+            if let Some((hour, minute, meridiem, read)) = parse_clock(&words[i..]) {
+                // A bare number is an hour only after "at" / "à" ("à 9", not "2026 03 12")
+                let after_at = matches!(rest.last(), Some(&"at") | Some(&"a"));
+                if read > 1 || after_at {
+                    if after_at {
+                        rest.pop();
+                    }
+                    time = Some((hour, minute, meridiem));
+                    i += read;
+                    continue;
+                }
+            }
+        }
+        match words[i] {
+            "noon" | "midday" | "midi" => time = Some((12, 0, true)),
+            "midnight" | "minuit" => time = Some((24, 0, true)),
+            word => match PartOfDay::from_word(word) {
+                Some(found) => part = Some(found),
+                None => rest.push(word),
+            },
+        }
+        i += 1;
+    }
+
+    let rest: Vec<&str> = rest
+        .into_iter()
+        .filter(|w| !DUE_FILLER_WORDS.contains(w) && *w != "at" && *w != "a")
+        .collect();
+    let today = now.date_naive();
+    let day = match rest.as_slice() {
+        [] => iso_day,
+        ["today"] | ["aujourd", "hui"] | ["this"] | ["ce"] | ["cet"] | ["cette"] => Some(today),
+        ["tomorrow"] | ["demain"] => Some(today + Days::new(1)),
+        ["day", "after", "tomorrow"] | ["apres", "demain"] => Some(today + Days::new(2)),
+        // "Monday", "next Monday", "lundi prochain": the next one after today
+        [day] | ["next" | "this", day] | [day, "prochain"] => {
+            let weekday = weekday(day)?;
+            let ahead = match (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7 {
+                0 => 7,
+                n => n,
+            };
+            Some(today + Days::new(ahead as u64))
+        }
+        _ => return None,
+    };
+    if time.is_none() && part.is_none() && day.is_none() {
+        return None;
+    }
+
+    let (mut hour, minute) = match (time, part) {
+        (Some((hour, minute, _)), _) => (hour, minute),
+        (None, Some(part)) => (part.default_hour(), 0),
+        (None, None) => (9, 0),
+    };
+    // "ce soir à 8h" is 20:00
+    if let (Some((_, _, false)), Some(PartOfDay::Afternoon | PartOfDay::Evening)) = (time, part) {
+        if hour < 12 {
+            hour += 12;
+        }
+    }
+
+    let at = |date: NaiveDate| {
+        local_time(date, 0, now) + Duration::hours(hour as i64) + Duration::minutes(minute as i64)
+    };
+    let due = at(day.unwrap_or(today));
+    if day.is_none() && due <= now.with_timezone(&Utc) {
+        return Some(at(today + Days::new(1)));
+    }
+    Some(due)
+}
+
+/// Range from a `when` expression and/or `from` and `to` bounds (expressions too, both
+/// inclusive: "to yesterday" ends at midnight tonight), relative to `now`
+pub fn resolve_range_at<Tz: TimeZone>(
+    when: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+    now: &DateTime<Tz>,
+) -> Result<DateRange, String> {
+    let parse = |expr: &str| {
+        parse_when(expr, now).ok_or_else(|| {
+            format!(
+                "Couldn't understand the date '{}' (try 'yesterday', 'last week', 'mardi dernier' or YYYY-MM-DD)",
+                expr
+            )
+        })
+    };
+
+    let (mut since, mut until) = match when.filter(|w| !w.trim().is_empty()) {
+        Some(when) => parse(when).map(|(start, end)| (Some(start), Some(end)))?,
+        None => (None, None),
+    };
+    if let Some(from) = from.filter(|f| !f.trim().is_empty()) {
+        since = Some(parse(from)?.0);
+    }
+    if let Some(to) = to.filter(|t| !t.trim().is_empty()) {
+        until = Some(parse(to)?.1);
+    }
+    Ok((since, until))
+}
+
+/// `resolve_range_at` for the current local time
+pub fn resolve_range(when: Option<&str>, from: Option<&str>, to: Option<&str>) -> Result<DateRange, String> {
+    resolve_range_at(when, from, to, &Local::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    /// Thursday 2026-03-12 15:30, UTC+1
+    fn now() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(3600).unwrap().with_ymd_and_hms(2026, 3, 12, 15, 30, 0).unwrap()
+    }
+
+    /// Local start and end of a parsed range, as "YYYY-MM-DD HH"
+    fn when(expr: &str) -> Option<(String, String)> {
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        parse_when(expr, &now()).map(|(start, end)| {
+            (
+                start.with_timezone(&offset).format("%Y-%m-%d %H").to_string(),
+                end.with_timezone(&offset).format("%Y-%m-%d %H").to_string(),
+            )
+        })
+    }
+
+    fn range(start: &str, end: &str) -> Option<(String, String)> {
+        Some((start.to_string(), end.to_string()))
+    }
+
+    #[test]
+    fn test_relative_days() {
+        assert_eq!(when("today"), range("2026-03-12 00", "2026-03-13 00"));
+        assert_eq!(when("Yesterday"), range("2026-03-11 00", "2026-03-12 00"));
+        assert_eq!(when("hier"), range("2026-03-11 00", "2026-03-12 00"));
+        assert_eq!(when("avant-hier"), range("2026-03-10 00", "2026-03-11 00"));
+        assert_eq!(when("il y a 3 jours"), range("2026-03-09 00", "2026-03-10 00"));
+        assert_eq!(when("two days ago"), range("2026-03-10 00", "2026-03-11 00"));
+        assert_eq!(when("the last 7 days"), range("2026-03-06 00", "2026-03-13 00"));
+        assert_eq!(when("les 3 derniers jours"), range("2026-03-10 00", "2026-03-13 00"));
+    }
+
+    #[test]
+    fn test_weekdays_and_parts_of_day() {
+        assert_eq!(when("last Tuesday afternoon"), range("2026-03-10 12", "2026-03-10 18"));
+        assert_eq!(when("mardi dernier après-midi"), range("2026-03-10 12", "2026-03-10 18"));
+        assert_eq!(when("on Thursday"), range("2026-03-12 00", "2026-03-13 00"));
+        assert_eq!(when("last thursday"), range("2026-03-05 00", "2026-03-06 00"));
+        assert_eq!(when("vendredi"), range("2026-03-06 00", "2026-03-07 00"));
+        assert_eq!(when("this morning"), range("2026-03-12 06", "2026-03-12 12"));
+        assert_eq!(when("hier soir"), range("2026-03-11 18", "2026-03-12 00"));
+    }
+
+    #[test]
+    fn test_weeks_months_and_iso() {
+        assert_eq!(when("this week"), range("2026-03-09 00", "2026-03-16 00"));
+        assert_eq!(when("la semaine dernière"), range("2026-03-02 00", "2026-03-09 00"));
+        assert_eq!(when("le mois dernier"), range("2026-02-01 00", "2026-03-01 00"));
+        assert_eq!(when("this month"), range("2026-03-01 00", "2026-04-01 00"));
+        assert_eq!(when("2026-02-27"), range("2026-02-27 00", "2026-02-28 00"));
+        assert_eq!(when("2026-02"), range("2026-02-01 00", "2026-03-01 00"));
+        assert_eq!(when("2026-03-01..2026-03-04"), range("2026-03-01 00", "2026-03-05 00"));
+        assert_eq!(when("sometime soon"), None);
+        assert_eq!(when("last 0 days"), None);
+    }
+
+    fn due(expr: &str) -> Option<String> {
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        parse_due(expr, &now()).map(|t| t.with_timezone(&offset).format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let minutes = |text: &str| parse_duration(text).map(|d| d.num_seconds() as f64 / 60.0);
+        assert_eq!(minutes("20 minutes"), Some(20.0));
+        assert_eq!(minutes("in 20min"), Some(20.0));
+        assert_eq!(minutes("dans 1h30"), Some(90.0));
+        assert_eq!(minutes("2 min 30"), Some(2.5));
+        assert_eq!(minutes("an hour and a half"), Some(90.0));
+        assert_eq!(minutes("one and a half hours"), Some(90.0));
+        assert_eq!(minutes("une demi-heure"), Some(30.0));
+        assert_eq!(minutes("trois quarts d'heure"), Some(45.0));
+        assert_eq!(minutes("90 secondes"), Some(1.5));
+        assert_eq!(minutes("tomorrow"), None);
+        assert_eq!(minutes("20"), None);
+
+        // Out of range amounts are no duration, not a panic
+        let huge = "4000000000 weeks ".repeat(4);
+        assert_eq!(parse_duration(&huge), None);
+        assert_eq!(due("in 4000000000 weeks"), None);
+    }
+
+    #[test]
+    fn test_parse_due() {
+        assert_eq!(due("in 20 minutes"), Some("2026-03-12 15:50".to_string()));
+        assert_eq!(due("dans 2h"), Some("2026-03-12 17:30".to_string()));
+        assert_eq!(due("at 15:45"), Some("2026-03-12 15:45".to_string()));
+        assert_eq!(due("5pm"), Some("2026-03-12 17:00".to_string()));
+        // Already passed today
+        assert_eq!(due("à 9h"), Some("2026-03-13 09:00".to_string()));
+        assert_eq!(due("demain à 9h30"), Some("2026-03-13 09:30".to_string()));
+        assert_eq!(due("tomorrow morning"), Some("2026-03-13 09:00".to_string()));
+        assert_eq!(due("ce soir à 8h"), Some("2026-03-12 20:00".to_string()));
+        assert_eq!(due("next Monday at noon"), Some("2026-03-16 12:00".to_string()));
+        assert_eq!(due("jeudi 14h"), Some("2026-03-19 14:00".to_string()));
+        assert_eq!(due("après-demain"), Some("2026-03-14 09:00".to_string()));
+        assert_eq!(due("2026-03-20 10:00"), Some("2026-03-20 10:00".to_string()));
+        assert_eq!(due("2026-03-20 at 10"), Some("2026-03-20 10:00".to_string()));
+        assert_eq!(due("today at 9"), Some("2026-03-12 09:00".to_string()));
+        assert_eq!(due("whenever"), None);
+    }
+
+    #[test]
+    fn test_resolve_range() {
+        let (since, until) = resolve_range_at(None, Some("2026-03-01"), Some("yesterday"), &now()).unwrap();
+        assert_eq!(since, parse_when("2026-03-01", &now()).map(|r| r.0));
+        assert_eq!(until, parse_when("today", &now()).map(|r| r.0));
+        assert_eq!(resolve_range_at(None, None, None, &now()).unwrap(), (None, None));
+        assert!(resolve_range_at(Some("whenever"), None, None, &now()).is_err());
+    }
+}
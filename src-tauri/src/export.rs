@@ -0,0 +1,400 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration, Local};
+use directories::{ProjectDirs, UserDirs};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::storage::{AudioSource, Database, MeetingNotes, Recording, Transcription};
+
+/// Markdown transcripts get a heading every this many minutes
+const SECTION_MINUTES: i64 = 5;
+
+/// Subtitle cues are assumed to last this long per word, within the bounds below
+const CUE_MILLIS_PER_WORD: i64 = 400;
+const MIN_CUE_MILLIS: i64 = 1_000;
+const MAX_CUE_MILLIS: i64 = 15_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Headings and timestamps
+    #[serde(alias = "md")]
+    Markdown,
+    /// SubRip subtitles
+    Srt,
+    /// WebVTT subtitles
+    #[serde(alias = "webvtt")]
+    Vtt,
+    /// Recording, segments and meeting notes with all metadata
+    Json,
+    /// The words only
+    #[serde(alias = "txt")]
+    Text,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Srt => "srt",
+            ExportFormat::Vtt => "vtt",
+            ExportFormat::Json => "json",
+            ExportFormat::Text => "txt",
+        }
+    }
+}
+
+/// A transcription segment placed on the recording's timeline
+struct Cue<'a> {
+    start: Duration,
+    end: Duration,
+    segment: &'a Transcription,
+}
+
+/// Segments are stored when speech ends, so a segment ends at its timestamp and starts
+/// an estimated speaking time before (never before the previous one ends)
+fn cues<'a>(recording: &Recording, transcriptions: &'a [Transcription]) -> Vec<Cue<'a>> {
+    let mut cues: Vec<Cue> = Vec::new();
+    for segment in transcriptions.iter().filter(|t| !t.text.trim().is_empty()) {
+        let end = (segment.timestamp - recording.created_at).max(Duration::zero());
+        let words = segment.text.split_whitespace().count() as i64;
+        let spoken = Duration::milliseconds((words * CUE_MILLIS_PER_WORD).clamp(MIN_CUE_MILLIS, MAX_CUE_MILLIS));
+        let previous_end = cues.last().map(|c| c.end).unwrap_or_else(Duration::zero);
+        let start = (end - spoken).max(previous_end).min(end);
+        cues.push(Cue { start, end, segment });
+    }
+    cues
+}
+
+/// "01:02:03" followed by `separator` and milliseconds when given
+fn clock(offset: Duration, separator: Option<char>) -> String {
+    let millis = offset.num_milliseconds().max(0);
+    let hms = format!("{:02}:{:02}:{:02}", millis / 3_600_000, millis / 60_000 % 60, millis / 1000 % 60);
+    match separator {
+        Some(separator) => format!("{}{}{:03}", hms, separator, millis % 1000),
+        None => hms,
+    }
+}
+
+fn duration(recording: &Recording, cues: &[Cue]) -> Duration {
+    match recording.ended_at {
+        Some(ended) => ended - recording.created_at,
+        None => cues.last().map(|c| c.end).unwrap_or_else(Duration::zero),
+    }
+}
+
+/// Text of a recording in `format`
+pub fn render(
+    format: ExportFormat,
+    recording: &Recording,
+    transcriptions: &[Transcription],
+    notes: Option<&MeetingNotes>,
+) -> Result<String> {
+    let cues = cues(recording, transcriptions);
+    let text = match format {
+        ExportFormat::Markdown => to_markdown(recording, &cues),
+        ExportFormat::Srt => to_subtitles(&cues, false),
+        ExportFormat::Vtt => to_subtitles(&cues, true),
+        ExportFormat::Json => serde_json::to_string_pretty(&to_json(recording, &cues, notes))?,
+        ExportFormat::Text => {
+            let mut text: String = cues.iter().map(|c| format!("{}\n", c.segment.text.trim())).collect();
+            if text.is_empty() {
+                text.push('\n');
+            }
+            text
+        }
+    };
+    Ok(text)
+}
+
+fn to_markdown(recording: &Recording, cues: &[Cue]) -> String {
+    let mut out = format!(
+        "# {}\n\n- **Date**: {}\n- **Duration**: {} min\n",
+        recording.name,
+        recording.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+        duration(recording, cues).num_minutes()
+    );
+    out.push_str("\n## Transcript\n");
+    if cues.is_empty() {
+        out.push_str("\n_No transcription._\n");
+    }
+
+    let mut section = None;
+    for cue in cues {
+        let minute = cue.start.num_minutes() / SECTION_MINUTES * SECTION_MINUTES;
+        if section != Some(minute) {
+            section = Some(minute);
+            out.push_str(&format!("\n### {}\n", clock(Duration::minutes(minute), None)));
+        }
+        let source = match cue.segment.source {
+            AudioSource::Microphone => "",
+            AudioSource::System => " _(system audio)_",
+        };
+        out.push_str(&format!("\n**[{}]**{} {}\n", clock(cue.start, None), source, cue.segment.text.trim()));
+    }
+    out
+}
+
+/// SRT, or WebVTT when `vtt`
+fn to_subtitles(cues: &[Cue], vtt: bool) -> String {
+    let separator = if vtt { '.' } else { ',' };
+    let mut out = if vtt { "WEBVTT\n\n".to_string() } else { String::new() };
+    for (i, cue) in cues.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            clock(cue.start, Some(separator)),
+            clock(cue.end, Some(separator)),
+            cue.segment.text.trim()
+        ));
+    }
+    out
+}
+
+fn to_json(recording: &Recording, cues: &[Cue], notes: Option<&MeetingNotes>) -> serde_json::Value {
+    let segments: Vec<serde_json::Value> = cues
+        .iter()
+        .map(|c| {
+            json!({
+                "id": c.segment.id,
+                "timestamp": c.segment.timestamp,
+                "start_seconds": c.start.num_milliseconds() as f64 / 1000.0,
+                "end_seconds": c.end.num_milliseconds() as f64 / 1000.0,
+                "source": c.segment.source.as_str(),
+                "text": c.segment.text.trim(),
+            })
+        })
+        .collect();
+    json!({
+        "recording": recording,
+        "duration_seconds": duration(recording, cues).num_seconds(),
+        "segments": segments,
+        "meeting_notes": notes,
+    })
+}
+
+/// Where exports go when no path is given: ~/Documents/Robert
+pub fn default_export_dir() -> Result<PathBuf> {
+    if let Some(documents) = UserDirs::new().and_then(|dirs| dirs.document_dir().map(Path::to_path_buf)) {
+        return Ok(documents.join("Robert"));
+    }
+    let dirs = ProjectDirs::from("com", "robert", "Robert").ok_or_else(|| anyhow!("Could not find app directories"))?;
+    Ok(dirs.data_dir().join("exports"))
+}
+
+/// `~/...` paths are relative to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), UserDirs::new()) {
+        (Some(rest), Some(dirs)) => dirs.home_dir().join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// File name for a recording, unique among `taken`: its name without characters that
+/// are invalid in paths, then " (2)", " (3)"... on collisions
+fn file_name(recording: &Recording, format: ExportFormat, taken: &mut HashSet<String>) -> String {
+    let stem: String = recording
+        .name
+        .chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    let stem = match stem.trim().trim_start_matches('.') {
+        "" => "recording".to_string(),
+        stem => stem.to_string(),
+    };
+
+    unique_name(&stem, format.extension(), taken)
+}
+
+/// `stem.extension`, or `stem (2).extension`... if that name is taken (case-insensitive)
+fn unique_name(stem: &str, extension: &str, taken: &mut HashSet<String>) -> String {
+    let dot = if extension.is_empty() { "" } else { "." };
+    let mut name = format!("{}{}{}", stem, dot, extension);
+    let mut n = 2;
+    while !taken.insert(name.to_lowercase()) {
+        name = format!("{} ({}){}{}", stem, n, dot, extension);
+        n += 1;
+    }
+    name
+}
+
+fn write_recording(db: &Database, recording: &Recording, format: ExportFormat, path: &Path) -> Result<()> {
+    let transcriptions = db.get_transcriptions(recording.id)?;
+    let notes = db.get_meeting_notes(recording.id)?;
+    let text = render(format, recording, &transcriptions, notes.as_ref())?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).with_context(|| format!("Could not create {}", parent.display()))?;
+    }
+    // Never replace an existing file, even one created since its name was picked
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .with_context(|| format!("Could not create {}", path.display()))?;
+    file.write_all(text.as_bytes())
+        .with_context(|| format!("Could not write {}", path.display()))
+}
+
+/// Export a recording to `path`: a file, or an existing folder to put it in (named after
+/// the recording). An existing file is never overwritten: the export gets a numbered
+/// name next to it instead. Returns the file written.
+pub fn export_recording(db: &Database, recording: &Recording, format: ExportFormat, path: &Path) -> Result<PathBuf> {
+    let path = if path.is_dir() {
+        let mut taken = existing_names(path)?;
+        path.join(file_name(recording, format, &mut taken))
+    } else if path.exists() {
+        let folder = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = path.extension().unwrap_or_default().to_string_lossy();
+        let mut taken = existing_names(folder)?;
+        folder.join(unique_name(&stem, &extension, &mut taken))
+    } else {
+        path.to_path_buf()
+    };
+    write_recording(db, recording, format, &path)?;
+    Ok(path)
+}
+
+/// Export each recording to its own file in `folder` (created if needed). Returns the
+/// files written.
+pub fn export_recordings(
+    db: &Database,
+    recordings: &[Recording],
+    format: ExportFormat,
+    folder: &Path,
+) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(folder).with_context(|| format!("Could not create {}", folder.display()))?;
+    let mut taken = existing_names(folder)?;
+    recordings
+        .iter()
+        .map(|recording| {
+            let path = folder.join(file_name(recording, format, &mut taken));
+            write_recording(db, recording, format, &path)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Lowercased names of the files in `folder`
+fn existing_names(folder: &Path) -> Result<HashSet<String>> {
+    let entries = fs::read_dir(folder).with_context(|| format!("Could not read {}", folder.display()))?;
+    Ok(entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_lowercase())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+    use uuid::Uuid;
+
+    fn recording() -> Recording {
+        let created_at = Utc.with_ymd_and_hms(2026, 3, 12, 9, 0, 0).unwrap();
+        Recording {
+            id: Uuid::new_v4(),
+            name: "Weekly sync: Q3/Q4".to_string(),
+            created_at,
+            ended_at: Some(created_at + Duration::minutes(12)),
+            is_active: false,
+        }
+    }
+
+    fn segment(recording: &Recording, at_seconds: i64, text: &str) -> Transcription {
+        let timestamp: DateTime<Utc> = recording.created_at + Duration::seconds(at_seconds);
+        Transcription {
+            id: Uuid::new_v4(),
+            recording_id: recording.id,
+            text: text.to_string(),
+            timestamp,
+            source: AudioSource::Microphone,
+        }
+    }
+
+    #[test]
+    fn test_subtitles() {
+        let recording = recording();
+        let segments = vec![
+            segment(&recording, 10, "Hello everyone"),
+            segment(&recording, 11, "   "),
+            segment(&recording, 12, "Let's start with the budget for next quarter"),
+        ];
+
+        let srt = render(ExportFormat::Srt, &recording, &segments, None).unwrap();
+        assert_eq!(
+            srt,
+            "1\n00:00:09,000 --> 00:00:10,000\nHello everyone\n\n\
+             2\n00:00:10,000 --> 00:00:12,000\nLet's start with the budget for next quarter\n\n"
+        );
+
+        let vtt = render(ExportFormat::Vtt, &recording, &segments, None).unwrap();
+        assert!(vtt.starts_with("WEBVTT\n\n1\n00:00:09.000 --> 00:00:10.000\n"));
+    }
+
+    #[test]
+    fn test_markdown_and_json() {
+        let recording = recording();
+        let segments = vec![segment(&recording, 30, "Hello"), segment(&recording, 400, "Next topic")];
+
+        let markdown = render(ExportFormat::Markdown, &recording, &segments, None).unwrap();
+        assert!(markdown.starts_with("# Weekly sync: Q3/Q4\n"));
+        assert!(markdown.contains("- **Duration**: 12 min\n"));
+        assert!(markdown.contains("\n### 00:00:00\n\n**[00:00:29]** Hello\n"));
+        assert!(markdown.contains("\n### 00:05:00\n\n**[00:06:39]** Next topic\n"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(ExportFormat::Json, &recording, &segments, None).unwrap()).unwrap();
+        assert_eq!(json["recording"]["name"], "Weekly sync: Q3/Q4");
+        assert_eq!(json["duration_seconds"], 720);
+        assert_eq!(json["segments"][1]["end_seconds"], 400.0);
+        assert_eq!(json["segments"][1]["source"], "microphone");
+
+        assert_eq!(render(ExportFormat::Text, &recording, &segments, None).unwrap(), "Hello\nNext topic\n");
+    }
+
+    #[test]
+    fn test_file_names() {
+        let recording = recording();
+        let mut taken = HashSet::from(["weekly sync_ q3_q4.md".to_string()]);
+        assert_eq!(file_name(&recording, ExportFormat::Markdown, &mut taken), "Weekly sync_ Q3_Q4 (2).md");
+        assert_eq!(file_name(&recording, ExportFormat::Markdown, &mut taken), "Weekly sync_ Q3_Q4 (3).md");
+        assert_eq!(file_name(&recording, ExportFormat::Srt, &mut taken), "Weekly sync_ Q3_Q4.srt");
+    }
+
+    #[test]
+    fn test_existing_files_never_overwritten() {
+        let folder = std::env::temp_dir().join(format!("robert-export-{}", Uuid::new_v4()));
+        fs::create_dir_all(&folder).unwrap();
+        let db = Database::in_memory().unwrap();
+        let recording = db.create_recording("Standup").unwrap();
+        db.add_transcription(recording.id, "Hello", AudioSource::Microphone).unwrap();
+
+        // An explicit file path that exists gets a numbered sibling
+        let target = folder.join("notes.txt");
+        fs::write(&target, "keep me").unwrap();
+        let written = export_recording(&db, &recording, ExportFormat::Text, &target).unwrap();
+        assert_eq!(written, folder.join("notes (2).txt"));
+        let written = export_recording(&db, &recording, ExportFormat::Text, &target).unwrap();
+        assert_eq!(written, folder.join("notes (3).txt"));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "keep me");
+        assert_eq!(fs::read_to_string(&written).unwrap(), "Hello\n");
+
+        // So does an export into a folder already holding one named after the recording
+        fs::write(folder.join("Standup.txt"), "keep me too").unwrap();
+        let written = export_recording(&db, &recording, ExportFormat::Text, &folder).unwrap();
+        assert_eq!(written, folder.join("Standup (2).txt"));
+        assert_eq!(fs::read_to_string(folder.join("Standup.txt")).unwrap(), "keep me too");
+
+        // The final write refuses to replace a file
+        assert!(write_recording(&db, &recording, ExportFormat::Text, &target).is_err());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "keep me");
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,8 +1,23 @@
-use crate::audio::{AudioCapture, DeviceInfo};
+use crate::audio::{AudioCapture, DeviceInfo, KeywordSpotterStatus};
+use crate::conversation;
+use crate::dates;
+use crate::export::{self, ExportFormat};
+use crate::llm::{create_provider, system_text};
+use crate::mcp::McpManager;
+use crate::meeting_notes::{self, notes_to_markdown};
+use crate::memory;
+use crate::prompt::{self, PromptContext};
+use crate::scheduler;
+use crate::search;
 use crate::state::{ActiveRecording, AppState, CopilotUIState, Settings};
-use crate::storage::{Recording, Transcription};
+use crate::storage::{Conversation, ConversationMessage, MeetingNotes, Memory, Note, Recording, RecordingActionItem, Reminder, ReminderKind, Todo, TranscriptFilter, TranscriptMatch, Transcription, UsagePeriod};
+use crate::tools::get_merged_tools;
+use crate::usage::{months_ago, summarize_usage, CommandUsage, UsageSummary};
 use crate::DbState;
+use crate::CommandState;
 use crate::CopilotState;
+use crate::KwsState;
+use crate::SchedulerState;
 use chrono::Utc;
 use serde::Serialize;
 use std::sync::Arc;
@@ -144,12 +159,19 @@ pub async fn stop_recording(
     Ok(())
 }
 
+/// Recordings, most recent first, optionally only those started in a period: `when` is
+/// an EN/FR expression ("yesterday", "la semaine dernière") or ISO date/range, `from`
+/// and `to` are inclusive bounds of the same kind
 #[tauri::command]
 pub async fn list_recordings(
+    when: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
     db: State<'_, DbState>,
 ) -> Result<Vec<Recording>, String> {
+    let (since, until) = dates::resolve_range(when.as_deref(), from.as_deref(), to.as_deref())?;
     let db = db.lock().map_err(|e| e.to_string())?;
-    db.list_recordings().map_err(|e| e.to_string())
+    db.list_recordings_between(since, until).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -183,6 +205,90 @@ pub async fn delete_recording(
     db.delete_recording(id).map_err(|e| e.to_string())
 }
 
+/// Full-text search over all transcriptions, best match first. Dates are YYYY-MM-DD
+/// or relative ("yesterday"), local days, inclusive.
+#[tauri::command]
+pub async fn search_transcripts(
+    query: String,
+    recording_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<usize>,
+    db: State<'_, DbState>,
+) -> Result<Vec<TranscriptMatch>, String> {
+    let recording_id = recording_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let (since, until) = dates::resolve_range(None, from.as_deref(), to.as_deref())?;
+    let filter = TranscriptFilter { recording_id, since, until };
+
+    let db = db.lock().map_err(|e| e.to_string())?;
+    search::search_transcripts(&db, &query, &filter, limit.unwrap_or(50)).map_err(|e| e.to_string())
+}
+
+/// Export a recording as markdown, srt, vtt, json or text to `path` (a file, or a folder
+/// to put it in; default ~/Documents/Robert). Returns the file written.
+#[tauri::command]
+pub async fn export_recording(
+    recording_id: String,
+    format: ExportFormat,
+    path: Option<String>,
+    db: State<'_, DbState>,
+) -> Result<String, String> {
+    let id = Uuid::parse_str(&recording_id).map_err(|e| e.to_string())?;
+    let path = match path {
+        Some(path) => export::expand_home(&path),
+        None => export::default_export_dir().map_err(|e| e.to_string())?,
+    };
+    if path.extension().is_none() {
+        std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+    }
+
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let recording = db.get_recording(id).map_err(|e| e.to_string())?.ok_or("Recording not found")?;
+    export::export_recording(&db, &recording, format, &path)
+        .map(|path| path.display().to_string())
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Export several recordings into `folder` (default ~/Documents/Robert), one file each:
+/// the given ids, or those of a period (`when`, `from`, `to` as in `list_recordings`).
+/// Returns the files written.
+#[tauri::command]
+pub async fn export_recordings(
+    recording_ids: Option<Vec<String>>,
+    when: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    format: ExportFormat,
+    folder: Option<String>,
+    db: State<'_, DbState>,
+) -> Result<Vec<String>, String> {
+    let folder = match folder {
+        Some(folder) => export::expand_home(&folder),
+        None => export::default_export_dir().map_err(|e| e.to_string())?,
+    };
+    let (since, until) = dates::resolve_range(when.as_deref(), from.as_deref(), to.as_deref())?;
+
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let recordings = match recording_ids {
+        Some(ids) => ids
+            .iter()
+            .map(|id| {
+                let id = Uuid::parse_str(id).map_err(|e| e.to_string())?;
+                db.get_recording(id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Recording {} not found", id))
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        None => db.list_recordings_between(since, until).map_err(|e| e.to_string())?,
+    };
+
+    let paths = export::export_recordings(&db, &recordings, format, &folder).map_err(|e| format!("{:#}", e))?;
+    Ok(paths.iter().map(|p| p.display().to_string()).collect())
+}
+
 #[tauri::command]
 pub async fn get_recording_status(
     state: State<'_, Arc<RwLock<AppState>>>,
@@ -199,6 +305,393 @@ pub fn get_copilot_state(
     Ok(state.clone())
 }
 
+#[tauri::command]
+pub fn start_wake_word_enrollment(kws: State<'_, KwsState>) -> Result<(), String> {
+    // The next complete utterance is captured by the audio loop as a template
+    let mut kws = kws.lock().map_err(|e| e.to_string())?;
+    kws.start_enrollment();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_wake_word_samples(kws: State<'_, KwsState>) -> Result<(), String> {
+    let mut kws = kws.lock().map_err(|e| e.to_string())?;
+    kws.clear().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_keyword_spotter_status(kws: State<'_, KwsState>) -> Result<KeywordSpotterStatus, String> {
+    let kws = kws.lock().map_err(|e| e.to_string())?;
+    Ok(kws.status())
+}
+
+#[tauri::command]
+pub async fn list_conversations(db: State<'_, DbState>) -> Result<Vec<Conversation>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.list_conversations().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_conversation_messages(
+    conversation_id: String,
+    db: State<'_, DbState>,
+) -> Result<Vec<ConversationMessage>, String> {
+    let id = Uuid::parse_str(&conversation_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.get_conversation_messages(id).map_err(|e| e.to_string())
+}
+
+/// Continue a past conversation: its messages become the persona's history.
+/// Returns the persona id.
+#[tauri::command]
+pub async fn resume_conversation(
+    conversation_id: String,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    db: State<'_, DbState>,
+) -> Result<String, String> {
+    let id = Uuid::parse_str(&conversation_id).map_err(|e| e.to_string())?;
+    let mut state = state.write().await;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    conversation::resume_conversation(&mut state, &db, id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_conversation(
+    conversation_id: String,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    db: State<'_, DbState>,
+) -> Result<(), String> {
+    let id = Uuid::parse_str(&conversation_id).map_err(|e| e.to_string())?;
+
+    // Deleting the active conversation also clears it from memory
+    let mut state = state.write().await;
+    let active_persona = state
+        .active_conversations
+        .iter()
+        .find(|(_, active)| active.id == id)
+        .map(|(persona_id, _)| persona_id.clone());
+    if let Some(persona_id) = active_persona {
+        conversation::start_new_conversation(&mut state, &persona_id);
+    }
+
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.delete_conversation(id).map_err(|e| e.to_string())
+}
+
+/// LLM usage and cost per day over the last `days` days (default 30)
+#[tauri::command]
+pub async fn get_daily_usage(
+    days: Option<u32>,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    db: State<'_, DbState>,
+) -> Result<Vec<UsageSummary>, String> {
+    let settings = state.read().await.settings.usage.clone();
+    let since = Utc::now() - chrono::Duration::days(days.unwrap_or(30).saturating_sub(1) as i64);
+    let since = since.date_naive().and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let db = db.lock().map_err(|e| e.to_string())?;
+    summarize_usage(&db, &settings, UsagePeriod::Day, since).map_err(|e| e.to_string())
+}
+
+/// LLM usage and cost per month over the last `months` months (default 12)
+#[tauri::command]
+pub async fn get_monthly_usage(
+    months: Option<u32>,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    db: State<'_, DbState>,
+) -> Result<Vec<UsageSummary>, String> {
+    let settings = state.read().await.settings.usage.clone();
+    let since = months_ago(Utc::now(), months.unwrap_or(12));
+    let db = db.lock().map_err(|e| e.to_string())?;
+    summarize_usage(&db, &settings, UsagePeriod::Month, since).map_err(|e| e.to_string())
+}
+
+/// Extract structured notes (decisions, action items...) from a recording with the LLM
+#[tauri::command]
+pub async fn extract_meeting_notes(
+    recording_id: String,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    db: State<'_, DbState>,
+) -> Result<MeetingNotes, String> {
+    let id = Uuid::parse_str(&recording_id).map_err(|e| e.to_string())?;
+    let recording = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        db.get_recording(id).map_err(|e| e.to_string())?.ok_or("Recording not found")?
+    };
+
+    let (provider, config, usage) = {
+        let state = state.read().await;
+        let provider = create_provider(&state.settings).map_err(|e| e.to_string())?;
+        let usage = CommandUsage::new(db.inner().clone(), "Extract meeting notes", state.settings.usage.clone());
+        (provider, state.settings.summary.clone(), usage)
+    };
+
+    meeting_notes::extract_meeting_notes(provider.as_ref(), db.inner(), &recording, &config, Some(&usage))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_meeting_notes(
+    recording_id: String,
+    db: State<'_, DbState>,
+) -> Result<Option<MeetingNotes>, String> {
+    let id = Uuid::parse_str(&recording_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.get_meeting_notes(id).map_err(|e| e.to_string())
+}
+
+/// Meeting notes as "markdown" (default) or "json"
+#[tauri::command]
+pub async fn export_meeting_notes(
+    recording_id: String,
+    format: Option<String>,
+    db: State<'_, DbState>,
+) -> Result<String, String> {
+    let id = Uuid::parse_str(&recording_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let recording = db.get_recording(id).map_err(|e| e.to_string())?.ok_or("Recording not found")?;
+    let notes = db
+        .get_meeting_notes(id)
+        .map_err(|e| e.to_string())?
+        .ok_or("No meeting notes for this recording, extract them first")?;
+
+    match format.as_deref().unwrap_or("markdown") {
+        "markdown" => Ok(notes_to_markdown(&notes, &recording)),
+        "json" => serde_json::to_string_pretty(&notes).map_err(|e| e.to_string()),
+        other => Err(format!("Unknown export format: {}", other)),
+    }
+}
+
+/// Action items of all recordings, optionally for one owner and the recordings
+/// of a period (`when`, `from` and `to` as in `list_recordings`)
+#[tauri::command]
+pub async fn list_action_items(
+    owner: Option<String>,
+    when: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    db: State<'_, DbState>,
+) -> Result<Vec<RecordingActionItem>, String> {
+    let (since, until) = dates::resolve_range(when.as_deref(), from.as_deref(), to.as_deref())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.list_action_items(owner.as_deref(), since, until).map_err(|e| e.to_string())
+}
+
+/// System prompt as sent with the next command of a persona (default: the built-in one),
+/// rendered from `template` if given (unsaved edit) or from the persona's prompt.
+/// `command` shows which memories it would bring in.
+#[tauri::command]
+pub async fn preview_system_prompt(
+    persona_id: Option<String>,
+    template: Option<String>,
+    command: Option<String>,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    db: State<'_, DbState>,
+) -> Result<String, String> {
+    let (persona, mcp_servers) = {
+        let state = state.read().await;
+        let personas = state.settings.resolved_personas();
+        let persona = match persona_id {
+            Some(id) => personas.into_iter().find(|p| p.id == id).ok_or("Persona not found")?,
+            None => personas.into_iter().next().ok_or("No persona configured")?,
+        };
+        (persona, state.settings.mcp_servers.clone())
+    };
+
+    // Same servers as a command would connect to
+    let mcp_manager = (!mcp_servers.is_empty()).then(|| McpManager::new(mcp_servers.clone()));
+    let (_, routing) = get_merged_tools(mcp_manager.as_ref()).await;
+    let routing = routing.into_iter().filter(|(name, _)| persona.allows_tool(name)).collect();
+
+    let state = state.read().await;
+    let mut context = PromptContext::gather(&state, Some(db.inner()), prompt::connected_servers(&mcp_servers, &routing));
+    if let Some(command) = &command {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        context.memories = memory::prompt_memories(&db, command);
+    }
+    let summary = state.conversation_summaries.get(&persona.id).map(String::as_str);
+    let template = template.as_deref().unwrap_or(&persona.system_prompt);
+    Ok(system_text(&prompt::system_blocks(template, &context, summary)))
+}
+
+/// Stored memories, most recently updated first
+#[tauri::command]
+pub async fn list_memories(db: State<'_, DbState>) -> Result<Vec<Memory>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.list_memories().map_err(|e| e.to_string())
+}
+
+/// Pending timers and reminders, soonest first
+#[tauri::command]
+pub async fn list_reminders(db: State<'_, DbState>) -> Result<Vec<Reminder>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.list_pending_reminders().map_err(|e| e.to_string())
+}
+
+/// Remind of `message` at `when` ("in 20 minutes", "tomorrow at 9am", "2026-03-12 15:30")
+#[tauri::command]
+pub async fn add_reminder(
+    message: String,
+    when: String,
+    db: State<'_, DbState>,
+    scheduler: State<'_, SchedulerState>,
+) -> Result<Reminder, String> {
+    let due = scheduler::due_at(&when)?;
+    let reminder = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        db.add_reminder(ReminderKind::Reminder, message.trim(), due).map_err(|e| e.to_string())?
+    };
+    scheduler.reschedule();
+    Ok(reminder)
+}
+
+#[tauri::command]
+pub async fn cancel_reminder(
+    reminder_id: String,
+    db: State<'_, DbState>,
+    scheduler: State<'_, SchedulerState>,
+) -> Result<(), String> {
+    let id = Uuid::parse_str(&reminder_id).map_err(|e| e.to_string())?;
+    let deleted = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        db.delete_reminder(id).map_err(|e| e.to_string())?
+    };
+    if !deleted {
+        return Err("Reminder not found".to_string());
+    }
+    scheduler.reschedule();
+    Ok(())
+}
+
+/// Quick notes, most recent first
+#[tauri::command]
+pub async fn list_notes(db: State<'_, DbState>) -> Result<Vec<Note>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.list_notes().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_note(content: String, db: State<'_, DbState>) -> Result<Note, String> {
+    if content.trim().is_empty() {
+        return Err("The note is empty".to_string());
+    }
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.add_note(content.trim()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_note(note_id: String, content: String, db: State<'_, DbState>) -> Result<(), String> {
+    if content.trim().is_empty() {
+        return Err("The note is empty".to_string());
+    }
+    let id = Uuid::parse_str(&note_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    if !db.update_note(id, content.trim()).map_err(|e| e.to_string())? {
+        return Err("Note not found".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_note(note_id: String, db: State<'_, DbState>) -> Result<(), String> {
+    let id = Uuid::parse_str(&note_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    if !db.delete_note(id).map_err(|e| e.to_string())? {
+        return Err("Note not found".to_string());
+    }
+    Ok(())
+}
+
+/// Open to-dos first, then the done ones when `include_done`
+#[tauri::command]
+pub async fn list_todos(include_done: bool, db: State<'_, DbState>) -> Result<Vec<Todo>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.list_todos(include_done).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_todo(title: String, db: State<'_, DbState>) -> Result<Todo, String> {
+    if title.trim().is_empty() {
+        return Err("The to-do is empty".to_string());
+    }
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.add_todo(title.trim()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_todo(todo_id: String, title: String, db: State<'_, DbState>) -> Result<(), String> {
+    if title.trim().is_empty() {
+        return Err("The to-do is empty".to_string());
+    }
+    let id = Uuid::parse_str(&todo_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    if !db.update_todo(id, title.trim()).map_err(|e| e.to_string())? {
+        return Err("To-do not found".to_string());
+    }
+    Ok(())
+}
+
+/// Check off a to-do, or uncheck it with `done: false`
+#[tauri::command]
+pub async fn set_todo_done(todo_id: String, done: bool, db: State<'_, DbState>) -> Result<(), String> {
+    let id = Uuid::parse_str(&todo_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    if !db.set_todo_done(id, done).map_err(|e| e.to_string())? {
+        return Err("To-do not found".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_todo(todo_id: String, db: State<'_, DbState>) -> Result<(), String> {
+    let id = Uuid::parse_str(&todo_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    if !db.delete_todo(id).map_err(|e| e.to_string())? {
+        return Err("To-do not found".to_string());
+    }
+    Ok(())
+}
+
+/// Keyword search over memories, best match first
+#[tauri::command]
+pub async fn search_memories(query: String, db: State<'_, DbState>) -> Result<Vec<Memory>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    memory::recall(&db, &query, 50).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_memory(content: String, db: State<'_, DbState>) -> Result<Memory, String> {
+    if content.trim().is_empty() {
+        return Err("Memory is empty".to_string());
+    }
+    let db = db.lock().map_err(|e| e.to_string())?;
+    memory::remember(&db, &content).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_memory(memory_id: String, content: String, db: State<'_, DbState>) -> Result<(), String> {
+    let id = Uuid::parse_str(&memory_id).map_err(|e| e.to_string())?;
+    if content.trim().is_empty() {
+        return Err("Memory is empty".to_string());
+    }
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let content = content.trim();
+    match db.update_memory(id, content, &memory::keywords(content)).map_err(|e| e.to_string())? {
+        true => Ok(()),
+        false => Err("Memory not found".to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_memory(memory_id: String, db: State<'_, DbState>) -> Result<(), String> {
+    let id = Uuid::parse_str(&memory_id).map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    match db.delete_memory(id).map_err(|e| e.to_string())? {
+        true => Ok(()),
+        false => Err("Memory not found".to_string()),
+    }
+}
+
 #[tauri::command]
 pub async fn test_mcp_server(url: String) -> Result<Vec<String>, String> {
     crate::mcp::test_mcp_server(&url)
@@ -237,8 +730,35 @@ pub fn set_copilot_alpha(app: tauri::AppHandle, alpha: f64) -> Result<(), String
 }
 
 #[tauri::command]
-pub fn hide_copilot(app: tauri::AppHandle) -> Result<(), String> {
+pub fn cancel_command(
+    app: tauri::AppHandle,
+    copilot: State<'_, CopilotState>,
+    commands: State<'_, CommandState>,
+) -> Result<(), String> {
+    // Stop the running command (HTTP stream and tools) and reset the copilot
+    crate::stop_command(&app, &copilot, &commands);
+    Ok(())
+}
+
+/// Yes or no to the tool confirmation shown in the copilot window
+#[tauri::command]
+pub fn answer_confirmation(confirmed: bool, commands: State<'_, CommandState>) -> Result<(), String> {
+    if commands.answer_confirmation(confirmed) {
+        Ok(())
+    } else {
+        Err("No confirmation pending".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn hide_copilot(app: tauri::AppHandle, copilot: State<'_, CopilotState>) -> Result<(), String> {
     use tauri::Manager;
+
+    // Dismissing the window also ends any follow-up listening
+    if let Ok(mut copilot) = copilot.write() {
+        copilot.follow_up = false;
+    }
+
     if let Some(window) = app.get_webview_window("copilot") {
         window.hide().map_err(|e| e.to_string())
     } else {
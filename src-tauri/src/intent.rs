@@ -0,0 +1,183 @@
+use serde_json::{json, Value};
+
+/// A command recognized locally, run directly as a local tool (no LLM round trip)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Intent {
+    pub tool: &'static str,
+    pub input: Value,
+}
+
+/// Utterances per tool, normalized (lowercase, no accents or punctuation, no politeness)
+const INTENTS: &[(&str, &[&str])] = &[
+    ("start_recording", &[
+        "start recording", "start a recording", "start the recording", "start a new recording",
+        "begin recording", "start recording the meeting", "record the meeting", "record this meeting",
+        "start a meeting", "start the meeting",
+        "demarre l enregistrement", "demarre un enregistrement", "demarre un nouvel enregistrement",
+        "lance l enregistrement", "lance un enregistrement", "commence l enregistrement",
+        "commence un enregistrement", "enregistre la reunion", "demarre la reunion", "demarre une reunion",
+        "lance une reunion", "demarrer l enregistrement", "lancer l enregistrement", "lancer un enregistrement",
+    ]),
+    ("stop_recording", &[
+        "stop recording", "stop the recording", "end the recording", "end recording", "finish the recording",
+        "stop the meeting", "end the meeting",
+        "arrete l enregistrement", "arrete d enregistrer", "stoppe l enregistrement", "termine l enregistrement",
+        "arrete la reunion", "termine la reunion", "fin de la reunion", "fin de l enregistrement",
+        "arreter l enregistrement", "terminer l enregistrement",
+    ]),
+    ("list_recordings", &[
+        "list recordings", "list my recordings", "list the recordings", "list all recordings",
+        "list all my recordings", "show my recordings", "show recordings", "what are my recordings",
+        "list my meetings", "show my meetings",
+        "liste les enregistrements", "liste mes enregistrements", "liste tous les enregistrements",
+        "montre mes enregistrements", "affiche mes enregistrements", "quels sont mes enregistrements",
+        "liste mes reunions", "montre mes reunions", "lister mes enregistrements", "lister les enregistrements",
+        "montrer mes enregistrements",
+    ]),
+];
+
+/// "start a recording called Budget review": the name follows one of these words
+const NAME_KEYWORDS: &[&str] = &["called", "named", "titled", "appele", "appelee", "nomme", "nommee", "intitule", "intitulee"];
+
+/// Politeness and request phrasing ignored when matching
+const FILLER_WORDS: &[&str] = &["please", "stp", "svp", "s", "il", "te", "vous", "plait", "robert"];
+const FILLER_PREFIXES: &[&str] = &[
+    "can you", "could you", "would you", "i want to", "i d like to", "let",
+    "peux tu", "tu peux", "pourrais tu", "tu pourrais", "est ce que tu peux", "je veux", "je voudrais",
+];
+
+/// Recognize a common command. Only exact phrasings match: anything else returns
+/// None and goes to the LLM.
+pub fn match_intent(command: &str) -> Option<Intent> {
+    let words: Vec<String> = normalize(command)
+        .split_whitespace()
+        .filter(|w| !FILLER_WORDS.contains(w))
+        .map(|w| w.to_string())
+        .collect();
+    let text = strip_prefixes(&words.join(" "));
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Some((tool, _)) = INTENTS.iter().find(|(_, phrases)| phrases.contains(&text.as_str())) {
+        return Some(Intent { tool, input: json!({}) });
+    }
+
+    // Named recording: known start phrase, keyword, then any name
+    let (phrase, _) = NAME_KEYWORDS
+        .iter()
+        .filter_map(|keyword| text.split_once(&format!(" {} ", keyword)))
+        .next()?;
+    let starts = INTENTS.iter().find(|(tool, _)| *tool == "start_recording").map(|(_, p)| *p)?;
+    if !starts.contains(&phrase) {
+        return None;
+    }
+    let name = recording_name(command)?;
+    Some(Intent { tool: "start_recording", input: json!({ "name": name }) })
+}
+
+/// Lowercase ASCII words: accents folded, apostrophes and hyphens split words
+pub fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'â' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'î' | 'ï' => 'i',
+            'ô' | 'ö' => 'o',
+            'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether every word of `query` is a word of `text` (after normalization)
+pub fn mentions(text: &str, query: &str) -> bool {
+    let text = normalize(text);
+    let words: Vec<&str> = text.split(' ').collect();
+    let query = normalize(query);
+    !query.is_empty() && query.split(' ').all(|q| words.contains(&q))
+}
+
+fn strip_prefixes(text: &str) -> String {
+    let mut text = text.to_string();
+    while let Some(rest) = FILLER_PREFIXES
+        .iter()
+        .find_map(|p| text.strip_prefix(p).and_then(|r| r.strip_prefix(' ')))
+    {
+        text = rest.to_string();
+    }
+    text
+}
+
+/// Name after the keyword, as spoken (original case and accents, politeness removed)
+fn recording_name(command: &str) -> Option<String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let keyword = words.iter().position(|w| NAME_KEYWORDS.contains(&normalize(w).as_str()))?;
+
+    // A token may normalize to several filler words ("s'il" -> "s il")
+    let is_filler = |w: &str| normalize(w).split(' ').all(|p| FILLER_WORDS.contains(&p));
+    let mut name: Vec<&str> = words[keyword + 1..].to_vec();
+    while name.last().map(|w| is_filler(w)).unwrap_or(false) {
+        name.pop();
+    }
+
+    let name = name
+        .join(" ")
+        .trim_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace() || c == '«' || c == '»')
+        .to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(command: &str) -> Option<&'static str> {
+        match_intent(command).map(|i| i.tool)
+    }
+
+    #[test]
+    fn test_common_commands_en_fr() {
+        assert_eq!(tool("Start recording."), Some("start_recording"));
+        assert_eq!(tool("Could you start a new recording, please?"), Some("start_recording"));
+        assert_eq!(tool("Démarre l'enregistrement s'il te plaît"), Some("start_recording"));
+        assert_eq!(tool("Stop the recording"), Some("stop_recording"));
+        assert_eq!(tool("Arrête l'enregistrement"), Some("stop_recording"));
+        assert_eq!(tool("List my recordings"), Some("list_recordings"));
+        assert_eq!(tool("Peux-tu lister mes enregistrements ?"), Some("list_recordings"));
+        assert_eq!(tool("Liste mes réunions"), Some("list_recordings"));
+    }
+
+    #[test]
+    fn test_named_recording() {
+        let intent = match_intent("Start a recording called Budget Review, please").unwrap();
+        assert_eq!(intent.tool, "start_recording");
+        assert_eq!(intent.input["name"], "Budget Review");
+
+        let intent = match_intent("Lance un enregistrement appelé Réunion d'équipe").unwrap();
+        assert_eq!(intent.input["name"], "Réunion d'équipe");
+    }
+
+    #[test]
+    fn test_unsure_commands_fall_through() {
+        assert_eq!(tool("stop"), None);
+        assert_eq!(tool("start recording and summarize the last meeting"), None);
+        assert_eq!(tool("summarize my recordings"), None);
+        assert_eq!(tool("delete the recording called budget"), None);
+        assert_eq!(tool(""), None);
+    }
+
+    #[test]
+    fn test_mentions() {
+        assert!(mentions("Review PR 42", "pr 42"));
+        assert!(mentions("Le déploiement est jeudi", "deploiement"));
+        assert!(!mentions("Review PR 42", "pr 4"));
+        assert!(!mentions("Review PR 42", ""));
+    }
+}
@@ -1,20 +1,38 @@
 #![allow(unexpected_cfgs)] // objc macro generates cfg(cargo-clippy) checks
 
 mod audio;
+mod conversation;
+mod dates;
+mod export;
 mod handlers;
+mod intent;
 mod llm;
 #[cfg(target_os = "macos")]
 mod macos_tracking;
 mod mcp;
+mod meeting_notes;
+mod memory;
+mod persona;
+mod prompt;
+mod scheduler;
+mod search;
 mod state;
 mod storage;
+mod summary_cache;
 mod tools;
 mod transcription;
+mod usage;
 
-use audio::{AudioCapture, AudioEvent, VadConfig};
-use llm::{AgenticClient, user_message};
-use state::{AppState, CopilotUIState};
+use audio::{AudioCapture, AudioEvent, KeywordSpotter, VadConfig};
+use crossbeam_channel::RecvTimeoutError;
+use llm::{
+    compact_history, create_provider, error_kind, user_message, AgenticClient, CancellationToken, LlmErrorKind, LlmProvider,
+    RetryPolicy, ContentBlock, Message, ToolDefinition,
+};
+use state::{AppState, CommandControl, CopilotUIState};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use storage::{AudioSource, Database};
 use tauri::{
     image::Image,
@@ -25,15 +43,24 @@ use tauri::{
 };
 use tokio::sync::RwLock;
 use mcp::McpManager;
-use tools::{get_merged_tools, ToolExecutor};
+use prompt::PromptContext;
+use scheduler::Scheduler;
+use persona::{is_new_conversation_command, is_stop_command, match_wake_word, parse_confirmation, Persona, WakeMatch};
+use intent::{match_intent, Intent};
+use tools::{get_merged_tools, tool_policy, Confirmer, ToolExecutor, ToolPolicy, ToolResult, ToolSource};
 use transcription::{Transcriber, StreamingTranscriber, StreamingConfig};
+use usage::{CommandUsage, UsageRecorder};
 
 pub type DbState = Arc<Mutex<Database>>;
 pub type CopilotState = Arc<std::sync::RwLock<CopilotUIState>>;
+pub type KwsState = Arc<Mutex<KeywordSpotter>>;
+pub type CommandState = Arc<CommandControl>;
+pub type SchedulerState = Arc<Scheduler>;
 
 const WHISPER_MODEL_FILENAME: &str = "ggml-small.bin";
 
-const WAKE_PATTERNS: &[&str] = &["ok robert", "okay robert", "hey robert", "robert,", "robert "];
+/// How long a tool confirmation waits for the user's answer
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -52,10 +79,33 @@ pub fn run() {
     // Create copilot UI state (using std::sync::RwLock for sync access in callbacks)
     let copilot_state: CopilotState = Arc::new(std::sync::RwLock::new(CopilotUIState::new()));
 
+    // In-flight command, interrupted by a new command, "stop" or Escape
+    let command_state: CommandState = Arc::new(CommandControl::default());
+
+    // Keyword spotter with enrolled wake word samples (shared with enrollment commands)
+    let kws_state: KwsState = Arc::new(Mutex::new(KeywordSpotter::load()));
+
+    // Fires timers and reminders; woken when they change
+    let scheduler_state: SchedulerState = Arc::new(Scheduler::default());
+
+    // Held while the push-to-talk shortcut is down (read by the audio callback)
+    let push_to_talk = Arc::new(AtomicBool::new(false));
+
+    let mut app_state = AppState::load();
+
+    // Continue conversations that were still active before the restart
+    if let Some(db) = db.as_ref().and_then(|db| db.lock().ok()) {
+        conversation::restore_conversations(&mut app_state, &db);
+    }
+    let push_to_talk_shortcut = app_state.settings.push_to_talk_shortcut.clone();
+
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .manage(Arc::new(RwLock::new(AppState::load())))
-        .manage(copilot_state.clone());
+        .manage(Arc::new(RwLock::new(app_state)))
+        .manage(copilot_state.clone())
+        .manage(command_state.clone())
+        .manage(scheduler_state.clone())
+        .manage(kws_state.clone());
 
     // Only manage database if it was created successfully
     if let Some(db) = db.clone() {
@@ -74,6 +124,7 @@ pub fn run() {
 
             setup_tray(app)?;
             setup_global_shortcut(app, db.clone())?;
+            setup_push_to_talk(app, &push_to_talk_shortcut, push_to_talk.clone(), copilot_state.clone(), command_state.clone());
 
             // Resolve whisper model path
             // In dev: look in src-tauri/models/
@@ -95,14 +146,33 @@ pub fn run() {
             // Start audio processing in background
             let app_handle = app.handle().clone();
             let state_clone = app.state::<Arc<RwLock<AppState>>>().inner().clone();
-            let copilot_clone = copilot_state.clone();
-            let db_clone = db.clone();
+            let commands = CommandRunner::new(
+                app_handle.clone(),
+                state_clone.clone(),
+                copilot_state.clone(),
+                command_state.clone(),
+                db.clone(),
+            );
+            let kws_clone = kws_state.clone();
+            let push_to_talk_clone = push_to_talk.clone();
             std::thread::spawn(move || {
-                if let Err(e) = audio_processing_loop(app_handle, state_clone, copilot_clone, db_clone, whisper_path) {
+                if let Err(e) = audio_processing_loop(app_handle, state_clone, commands, kws_clone, push_to_talk_clone, whisper_path) {
                     eprintln!("Audio processing error: {}", e);
                 }
             });
 
+            // Fire timers and reminders (pending ones survive restarts)
+            if let Some(db) = db.clone() {
+                let app_handle = app.handle().clone();
+                let copilot_state = copilot_state.clone();
+                let command_state = command_state.clone();
+                let scheduler_state = scheduler_state.clone();
+                std::thread::spawn(move || match tokio::runtime::Runtime::new() {
+                    Ok(rt) => rt.block_on(scheduler::run(app_handle, db, copilot_state, command_state, scheduler_state)),
+                    Err(e) => eprintln!("Scheduler error: {}", e),
+                });
+            }
+
             println!("[Robert] Loading...");
             Ok(())
         })
@@ -122,6 +192,42 @@ pub fn run() {
             handlers::test_mcp_server,
             handlers::set_copilot_alpha,
             handlers::hide_copilot,
+            handlers::cancel_command,
+            handlers::start_wake_word_enrollment,
+            handlers::clear_wake_word_samples,
+            handlers::get_keyword_spotter_status,
+            handlers::get_daily_usage,
+            handlers::extract_meeting_notes,
+            handlers::get_meeting_notes,
+            handlers::export_meeting_notes,
+            handlers::list_action_items,
+            handlers::preview_system_prompt,
+            handlers::list_memories,
+            handlers::search_memories,
+            handlers::add_memory,
+            handlers::update_memory,
+            handlers::delete_memory,
+            handlers::list_conversations,
+            handlers::get_conversation_messages,
+            handlers::resume_conversation,
+            handlers::delete_conversation,
+            handlers::get_monthly_usage,
+            handlers::answer_confirmation,
+            handlers::search_transcripts,
+            handlers::export_recording,
+            handlers::export_recordings,
+            handlers::list_reminders,
+            handlers::add_reminder,
+            handlers::cancel_reminder,
+            handlers::list_notes,
+            handlers::add_note,
+            handlers::update_note,
+            handlers::delete_note,
+            handlers::list_todos,
+            handlers::add_todo,
+            handlers::update_todo,
+            handlers::set_todo_done,
+            handlers::delete_todo,
         ])
         .run(tauri::generate_context!())
         .expect("error running Robert");
@@ -197,6 +303,66 @@ fn setup_global_shortcut(app: &tauri::App, db: Option<DbState>) -> Result<(), Bo
     Ok(())
 }
 
+/// Register the push-to-talk shortcut: holding it captures one command, no wake word needed
+fn setup_push_to_talk(app: &tauri::App, shortcut: &str, push_to_talk: Arc<AtomicBool>, copilot_state: CopilotState, command_state: CommandState) {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+    if shortcut.trim().is_empty() {
+        return;
+    }
+
+    let parsed: Shortcut = match shortcut.parse() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("[{}] Invalid push-to-talk shortcut '{}': {}", timestamp(), shortcut, e);
+            return;
+        }
+    };
+
+    let result = app.global_shortcut()
+        .on_shortcut(parsed, move |app, _shortcut, event| {
+            match event.state {
+                ShortcutState::Pressed => {
+                    // Ignore key repeat while held
+                    if !push_to_talk.swap(true, Ordering::SeqCst) {
+                        show_copilot_listening(app, &copilot_state, &command_state);
+                    }
+                }
+                ShortcutState::Released => {
+                    push_to_talk.store(false, Ordering::SeqCst);
+                }
+            }
+        });
+
+    match result {
+        Ok(_) => println!("[{}] Push-to-talk shortcut: {}", timestamp(), shortcut),
+        Err(e) => eprintln!("[{}] Could not register push-to-talk shortcut '{}': {}", timestamp(), shortcut, e),
+    }
+}
+
+/// Show the copilot window in listening state for a new command.
+/// Speaking to Robert again interrupts the command still running (barge-in).
+fn show_copilot_listening(app: &tauri::AppHandle, copilot_state: &CopilotState, command_state: &CommandControl) {
+    if command_state.confirmation_pending() {
+        // The command waits for a yes or no: keep it and its question
+        copilot_state.write().unwrap().heard_text.clear();
+    } else {
+        command_state.cancel();
+        let mut copilot = copilot_state.write().unwrap();
+        copilot.visible = true;
+        copilot.state = "listening".to_string();
+        copilot.response_text.clear();
+        copilot.heard_text.clear();
+        copilot.should_close = false;
+        copilot.error = None;
+    }
+
+    if let Some(copilot) = app.get_webview_window("copilot") {
+        let _ = copilot.show();
+        let _ = copilot.set_focus();
+    }
+}
+
 async fn toggle_recording(app: &tauri::AppHandle, state: &Arc<RwLock<AppState>>, db: Option<&DbState>) {
     let db = match db {
         Some(db) => db,
@@ -332,31 +498,12 @@ fn timestamp() -> String {
     format!("{:02}:{:02}:{:02}.{:03}", hours, mins, secs, millis)
 }
 
-fn extract_command(text: &str) -> Option<String> {
-    let text_lower = text.to_lowercase();
-
-    for pattern in WAKE_PATTERNS {
-        if let Some(pos) = text_lower.find(pattern) {
-            let command_start = pos + pattern.len();
-            let command_text = text[command_start..]
-                .trim()
-                .trim_start_matches(|c: char| c.is_ascii_punctuation())
-                .to_string();
-
-            if !command_text.is_empty() {
-                return Some(command_text);
-            }
-        }
-    }
-
-    None
-}
-
 fn audio_processing_loop(
     app: tauri::AppHandle,
     state: Arc<RwLock<AppState>>,
-    copilot_state: CopilotState,
-    db: Option<DbState>,
+    commands: CommandRunner,
+    kws: KwsState,
+    push_to_talk: Arc<AtomicBool>,
     whisper_path: std::path::PathBuf,
 ) -> anyhow::Result<()> {
     if !whisper_path.exists() {
@@ -382,18 +529,28 @@ fn audio_processing_loop(
 
     println!("[{}] Whisper ready (streaming)", timestamp());
 
+    let copilot_state = commands.copilot_state.clone();
+    let command_state = commands.command_state.clone();
+    let db = commands.db.clone();
+
     // Get settings for audio capture
-    let (mic_device, vad_config) = {
+    let settings = {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(async {
-            let state = state.read().await;
-            let vad = VadConfig {
-                speech_threshold: state.settings.speech_threshold,
-                silence_duration_ms: state.settings.silence_duration_ms,
-            };
-            (state.settings.mic_device.clone(), vad)
-        })
+        rt.block_on(async { state.read().await.settings.clone() })
     };
+    let mic_device = settings.mic_device.clone();
+    let vad_config = VadConfig {
+        speech_threshold: settings.speech_threshold,
+        silence_duration_ms: settings.silence_duration_ms,
+    };
+    let kws_enabled = settings.keyword_spotting_enabled;
+    let follow_up_window = Duration::from_millis(settings.follow_up_window_ms);
+    let follow_up_silence = Duration::from_millis(settings.follow_up_silence_ms);
+
+    if kws_enabled {
+        println!("[{}] Keyword spotting enabled ({} samples enrolled)",
+            timestamp(), kws.lock().map(|k| k.status().template_count).unwrap_or(0));
+    }
 
     println!("[{}] VAD settings: threshold={}, silence_ms={}",
         timestamp(), vad_config.speech_threshold, vad_config.silence_duration_ms);
@@ -407,7 +564,8 @@ fn audio_processing_loop(
             println!("[{}] Using default microphone", timestamp());
             AudioCapture::new(vad_config)?
         }
-    };
+    }
+    .with_push_to_talk(push_to_talk.clone());
 
     if let Some(name) = capture.device_name() {
         println!("[{}] Audio device: {}", timestamp(), name);
@@ -425,12 +583,89 @@ fn audio_processing_loop(
     // Track if wake word was detected in current utterance
     let mut wake_word_detected = false;
     let mut overlay_shown = false;
+    // Keyword spotter fired for current utterance (streaming Whisper may run)
+    let mut keyword_heard = false;
+    // Current utterance is being captured with the push-to-talk shortcut
+    let mut push_to_talk_utterance = false;
+    // Open after a response: the next utterance is a command without wake word
+    let mut follow_up: Option<FollowUpWindow> = None;
 
     loop {
-        match event_receiver.recv() {
+        // Follow-up dismissed from the copilot window, or confirmation answered with its buttons
+        let window_closed = match &follow_up {
+            Some(window) if window.confirmation => !command_state.confirmation_pending(),
+            Some(_) => !copilot_state.read().unwrap().follow_up,
+            None => false,
+        };
+        if window_closed {
+            follow_up = None;
+        }
+
+        let follow_up_timeout = match follow_up.as_ref().and_then(|f| f.deadline(follow_up_window, follow_up_silence)) {
+            Some(deadline) => crossbeam_channel::at(deadline),
+            None => crossbeam_channel::never(),
+        };
+
+        let received = crossbeam_channel::select! {
+            recv(event_receiver) -> event => event.map_err(|_| RecvTimeoutError::Disconnected),
+            recv(commands.finished()) -> persona => {
+                // Response done: the next utterance may be a reply
+                if let Ok(persona) = persona {
+                    follow_up = begin_follow_up(&copilot_state, follow_up_window, persona);
+                }
+                continue;
+            }
+            recv(command_state.confirmation_requests()) -> persona => {
+                // A tool waits for the user's approval: the next utterance is the answer
+                if let Ok(persona) = persona {
+                    follow_up = Some(FollowUpWindow::confirmation(persona));
+                }
+                continue;
+            }
+            recv(follow_up_timeout) -> _ => Err(RecvTimeoutError::Timeout),
+        };
+
+        match received {
             Ok(event) => {
+                // Re-read personas so wake word edits apply without restart
+                let personas = rt.block_on(async { state.read().await.settings.resolved_personas() });
+
                 match event {
                     AudioEvent::StreamingChunk(samples) => {
+                        // Push-to-talk or follow-up: no wake word, just show what is being heard
+                        let push_to_talk_held = push_to_talk.load(Ordering::SeqCst);
+                        if push_to_talk_utterance || push_to_talk_held || follow_up.is_some() {
+                            if push_to_talk_held {
+                                push_to_talk_utterance = true;
+                            }
+                            if let Some(window) = follow_up.as_mut() {
+                                window.speaking = true;
+                            }
+                            streaming_transcriber.push_audio(&samples);
+                            if let Ok(result) = streaming_transcriber.transcribe() {
+                                let text = result.text.trim().to_string();
+                                if !text.is_empty() {
+                                    let mut copilot = copilot_state.write().unwrap();
+                                    copilot.heard_text = text;
+                                }
+                            }
+                            continue;
+                        }
+
+                        // Cheap keyword spotting first: skip streaming Whisper until the
+                        // wake word has probably been heard
+                        if kws_enabled && !keyword_heard {
+                            if let Ok(spotter) = kws.lock() {
+                                if spotter.has_templates() && !spotter.is_enrolling() {
+                                    if !spotter.detect(&samples) {
+                                        continue;
+                                    }
+                                    println!("[{}] Keyword spotted, starting streaming transcription", timestamp());
+                                }
+                            }
+                            keyword_heard = true;
+                        }
+
                         // Push audio to streaming transcriber
                         streaming_transcriber.push_audio(&samples);
 
@@ -440,35 +675,21 @@ fn audio_processing_loop(
 
                             if !text.is_empty() && text != "." && text != "..." && text.len() > 1 {
                                 // Check for wake word in streaming text
-                                if !wake_word_detected && contains_wake_word(&text) {
+                                if !wake_word_detected && match_wake_word(&personas, &text).is_some() {
                                     wake_word_detected = true;
                                     println!("[{}] Wake word detected (streaming): {}", timestamp(), text);
 
                                     // Show overlay IMMEDIATELY
                                     if !overlay_shown {
                                         overlay_shown = true;
-
-                                        // Update copilot state
-                                        {
-                                            let mut copilot = copilot_state.write().unwrap();
-                                            copilot.visible = true;
-                                            copilot.state = "listening".to_string();
-                                            copilot.response_text.clear();
-                                            copilot.should_close = false;
-                                        }
-
-                                        // Show the window
-                                        if let Some(copilot) = app.get_webview_window("copilot") {
-                                            let _ = copilot.show();
-                                            let _ = copilot.set_focus();
-                                        }
+                                        show_copilot_listening(&app, &copilot_state, &command_state);
                                     }
                                 }
 
                                 // Update heard_text in copilot OR emit to overlay (not both)
                                 if wake_word_detected {
                                     // Show in copilot only
-                                    if let Some(command_text) = extract_command(&text) {
+                                    if let Some(command_text) = match_wake_word(&personas, &text).and_then(|m| m.command) {
                                         let mut copilot = copilot_state.write().unwrap();
                                         copilot.heard_text = command_text;
                                     }
@@ -481,52 +702,100 @@ fn audio_processing_loop(
                     }
 
                     AudioEvent::SpeechEnded(samples) => {
+                        // Capture this utterance as a wake word sample if enrollment was requested
+                        if let Ok(mut spotter) = kws.lock() {
+                            if spotter.is_enrolling() {
+                                match spotter.enroll(&samples) {
+                                    Ok(count) => {
+                                        println!("[{}] Wake word sample enrolled ({} total)", timestamp(), count);
+                                        let _ = app.emit("wake-word-enrolled", count);
+                                    }
+                                    Err(e) => {
+                                        eprintln!("[{}] Wake word enrollment failed: {}", timestamp(), e);
+                                        let _ = app.emit("error", format!("Wake word enrollment failed: {}", e));
+                                    }
+                                }
+                                streaming_transcriber.reset();
+                                wake_word_detected = false;
+                                overlay_shown = false;
+                                keyword_heard = false;
+                                continue;
+                            }
+                        }
+
                         // Final transcription with full audio (more accurate)
+                        // A command was started (or stopped) by this utterance
+                        let mut command_started = false;
                         if let Ok(text) = final_transcriber.transcribe(&samples) {
                             let text = text.trim().to_string();
 
                             if !text.is_empty() && text != "." && text != "..." && text.len() > 1 {
                                 println!("[{}] Final: {}", timestamp(), text);
 
-                                // Process command if wake word was detected
-                                let is_command = if wake_word_detected {
-                                    if let Some(command_text) = extract_command(&text) {
-                                        println!("[{}] Command: {}", timestamp(), command_text);
+                                // "Robert, stop" (or just "stop" while a reply is expected)
+                                let stop_requested = match match_wake_word(&personas, &text) {
+                                    Some(WakeMatch { command: Some(command_text), .. }) => is_stop_command(&command_text),
+                                    _ => follow_up.is_some() && is_stop_command(&text),
+                                };
+
+                                // Process command if wake word was detected (or a follow-up is expected)
+                                let is_command = if stop_requested {
+                                    println!("[{}] Stop requested", timestamp());
+                                    commands.stop();
+                                    command_started = true;
+                                    true
+                                } else if let Some(window) = follow_up.as_ref().filter(|w| w.confirmation) {
+                                    // "Robert, yes" works too, but a bare "OK Robert" is no answer:
+                                    // the question stays open
+                                    match match_wake_word(&personas, &text) {
+                                        Some(WakeMatch { command: None, .. }) => {
+                                            println!("[{}] Wake word alone, still waiting for a confirmation", timestamp());
+                                        }
+                                        wake_match => {
+                                            let reply = wake_match.and_then(|m| m.command).unwrap_or_else(|| text.clone());
+                                            commands.answer_confirmation(&rt, window.persona.clone(), reply);
+                                            command_started = true;
+                                        }
+                                    }
+                                    true
+                                } else if let Some(window) = &follow_up {
+                                    // Another persona's wake word takes over, otherwise the same persona continues
+                                    let (persona, command_text) = match match_wake_word(&personas, &text) {
+                                        Some(m) => (m.persona.clone(), m.command.unwrap_or_else(|| text.clone())),
+                                        None => (window.persona.clone(), text.clone()),
+                                    };
+                                    println!("[{}] Command (follow-up, {}): {}", timestamp(), persona.name, command_text);
+
+                                    show_copilot_listening(&app, &copilot_state, &command_state);
+                                    {
+                                        let mut copilot = copilot_state.write().unwrap();
+                                        copilot.heard_text = command_text.clone();
+                                    }
+
+                                    commands.spawn(&rt, persona, command_text);
+                                    command_started = true;
+                                    true
+                                } else if wake_word_detected {
+                                    if let Some(WakeMatch { persona, command: Some(command_text) }) = match_wake_word(&personas, &text) {
+                                        println!("[{}] Command ({}): {}", timestamp(), persona.name, command_text);
 
-                                        let db_ref = db.clone();
-                                        let copilot_clone = copilot_state.clone();
-                                        rt.block_on(async {
-                                            process_command(&app, &state, &copilot_clone, &command_text, db_ref.as_ref()).await;
-                                        });
+                                        commands.spawn(&rt, persona.clone(), command_text);
+                                        command_started = true;
                                         true
                                     } else {
                                         // Wake word detected but no command extracted
                                         // This might happen if transcription changed
                                         false
                                     }
-                                } else if let Some(command_text) = extract_command(&text) {
+                                } else if let Some(WakeMatch { persona, command: Some(command_text) }) = match_wake_word(&personas, &text) {
                                     // Fallback: wake word in final transcription but not in streaming
-                                    println!("[{}] Command (late detection): {}", timestamp(), command_text);
+                                    println!("[{}] Command (late detection, {}): {}", timestamp(), persona.name, command_text);
 
                                     // Show overlay if not already shown
-                                    {
-                                        let mut copilot = copilot_state.write().unwrap();
-                                        copilot.visible = true;
-                                        copilot.state = "listening".to_string();
-                                        copilot.response_text.clear();
-                                        copilot.should_close = false;
-                                    }
-
-                                    if let Some(copilot) = app.get_webview_window("copilot") {
-                                        let _ = copilot.show();
-                                        let _ = copilot.set_focus();
-                                    }
+                                    show_copilot_listening(&app, &copilot_state, &command_state);
 
-                                    let db_ref = db.clone();
-                                    let copilot_clone = copilot_state.clone();
-                                    rt.block_on(async {
-                                        process_command(&app, &state, &copilot_clone, &command_text, db_ref.as_ref()).await;
-                                    });
+                                    commands.spawn(&rt, persona.clone(), command_text);
+                                    command_started = true;
                                     true
                                 } else {
                                     false
@@ -560,79 +829,404 @@ fn audio_processing_loop(
                         streaming_transcriber.reset();
                         wake_word_detected = false;
                         overlay_shown = false;
+                        keyword_heard = false;
+
+                        if command_started {
+                            // Reopened once the response is done
+                            follow_up = None;
+                        } else if let Some(window) = follow_up.as_mut() {
+                            // Noise or empty utterance: keep waiting, silence timer restarts
+                            window.speaking = false;
+                            window.last_activity = Instant::now();
+                        }
+                    }
+
+                    AudioEvent::PushToTalkEnded(samples) => {
+                        // Whole utterance is the command, no wake word extraction
+                        let text = if samples.is_empty() {
+                            String::new()
+                        } else {
+                            final_transcriber.transcribe(&samples)
+                                .map(|t| t.trim().to_string())
+                                .unwrap_or_default()
+                        };
+
+                        if samples.is_empty() && !command_state.confirmation_pending() {
+                            // Key tapped too briefly: leave the listening state
+                            copilot_state.write().unwrap().reset();
+                            if let Some(window) = app.get_webview_window("copilot") {
+                                let _ = window.hide();
+                            }
+                        } else if is_stop_command(&text) {
+                            println!("[{}] Stop requested (push-to-talk)", timestamp());
+                            commands.stop();
+                            follow_up = None;
+                        } else if command_state.confirmation_pending() {
+                            // Nothing heard: the question stays on screen
+                            if !text.is_empty() {
+                                let persona = follow_up.take().map(|w| w.persona).unwrap_or_else(|| personas[0].clone());
+                                commands.answer_confirmation(&rt, persona, text);
+                            }
+                        } else if !text.is_empty() && text != "." && text != "..." && text.len() > 1 {
+                            // Push-to-talk always goes to the first persona
+                            let persona = personas[0].clone();
+                            println!("[{}] Command (push-to-talk, {}): {}", timestamp(), persona.name, text);
+                            {
+                                let mut copilot = copilot_state.write().unwrap();
+                                copilot.heard_text = text.clone();
+                            }
+
+                            commands.spawn(&rt, persona, text);
+                            follow_up = None;
+                        } else {
+                            // Nothing heard: let the copilot window close
+                            let mut copilot = copilot_state.write().unwrap();
+                            copilot.should_close = true;
+                        }
+
+                        streaming_transcriber.reset();
+                        push_to_talk_utterance = false;
+                        wake_word_detected = false;
+                        overlay_shown = false;
+                        keyword_heard = false;
                     }
                 }
             }
-            Err(_) => break,
+            Err(RecvTimeoutError::Timeout) if follow_up.as_ref().is_some_and(|w| w.confirmation) => {
+                // The confirmer gives up on its own
+                follow_up = None;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                println!("[{}] Follow-up window closed", timestamp());
+                follow_up = None;
+                let mut copilot = copilot_state.write().unwrap();
+                copilot.follow_up = false;
+                copilot.should_close = true;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
         }
     }
 
     Ok(())
 }
 
-/// Check if text contains any wake word pattern
-fn contains_wake_word(text: &str) -> bool {
-    let text_lower = text.to_lowercase();
-    WAKE_PATTERNS.iter().any(|pattern| text_lower.contains(pattern))
+/// Listening window after a response, during which the next utterance is a command
+struct FollowUpWindow {
+    /// Persona that answered last (handles the reply)
+    persona: Persona,
+    started: Instant,
+    last_activity: Instant,
+    /// An utterance is in progress (never cut it off)
+    speaking: bool,
+    /// Waiting for a yes or no to a tool confirmation
+    confirmation: bool,
 }
 
-const SYSTEM_PROMPT: &str = "You are Robert, a voice assistant that helps users manage their meeting recordings. \
-You can start/stop recordings, list them, summarize them, get their content, rename them, and delete them. \
-When the user confirms an action (like 'yes', 'go ahead', 'do it', 'tu peux y aller'), execute the action discussed. \
-Always respond in the same language the user speaks.";
+impl FollowUpWindow {
+    fn new(persona: Persona) -> Self {
+        let now = Instant::now();
+        Self {
+            persona,
+            started: now,
+            last_activity: now,
+            speaking: false,
+            confirmation: false,
+        }
+    }
 
-const MAX_HISTORY_MESSAGES: usize = 40;
+    fn confirmation(persona: Persona) -> Self {
+        Self { confirmation: true, ..Self::new(persona) }
+    }
 
-async fn process_command(app: &tauri::AppHandle, state: &Arc<RwLock<AppState>>, copilot_state: &CopilotState, command_text: &str, db: Option<&DbState>) {
-    let api_key = {
-        let state = state.read().await;
-        state.settings.anthropic_api_key.clone()
-    };
+    /// Closes after `window`, or earlier after `silence` without anyone speaking
+    fn deadline(&self, window: Duration, silence: Duration) -> Option<Instant> {
+        if self.speaking {
+            return None;
+        }
+        if self.confirmation {
+            return Some(self.started + CONFIRMATION_TIMEOUT);
+        }
+        Some((self.started + window).min(self.last_activity + silence))
+    }
+}
 
-    let api_key = match api_key {
-        Some(key) if !key.is_empty() => {
-            let trimmed = key.trim().to_string();
-            println!("[{}] API key: {}...{} (len={})",
-                timestamp(),
-                &trimmed.chars().take(10).collect::<String>(),
-                &trimmed.chars().rev().take(4).collect::<String>().chars().rev().collect::<String>(),
-                trimmed.len()
-            );
-            trimmed
-        },
-        _ => {
-            println!("[{}] No Anthropic API key configured", timestamp());
-            let _ = app.emit("command-response", "Please configure your Anthropic API key in settings");
-            return;
+/// Open a follow-up window after a response (disabled when the window is zero)
+fn begin_follow_up(copilot_state: &CopilotState, window: Duration, persona: Persona) -> Option<FollowUpWindow> {
+    if window.is_zero() {
+        return None;
+    }
+
+    let mut copilot = copilot_state.write().unwrap();
+    copilot.follow_up = true;
+    Some(FollowUpWindow::new(persona))
+}
+
+/// Runs voice commands in the background so the audio loop keeps listening
+#[derive(Clone)]
+struct CommandRunner {
+    app: tauri::AppHandle,
+    state: Arc<RwLock<AppState>>,
+    copilot_state: CopilotState,
+    command_state: CommandState,
+    db: Option<DbState>,
+    /// Persona of each command that ran to completion (opens a follow-up)
+    finished_tx: crossbeam_channel::Sender<Persona>,
+    finished_rx: crossbeam_channel::Receiver<Persona>,
+}
+
+impl CommandRunner {
+    fn new(
+        app: tauri::AppHandle,
+        state: Arc<RwLock<AppState>>,
+        copilot_state: CopilotState,
+        command_state: CommandState,
+        db: Option<DbState>,
+    ) -> Self {
+        let (finished_tx, finished_rx) = crossbeam_channel::unbounded();
+        Self { app, state, copilot_state, command_state, db, finished_tx, finished_rx }
+    }
+
+    fn finished(&self) -> &crossbeam_channel::Receiver<Persona> {
+        &self.finished_rx
+    }
+
+    /// Start a command, interrupting the one in flight
+    fn spawn(&self, rt: &tokio::runtime::Runtime, persona: Persona, command_text: String) {
+        let runner = self.clone();
+        rt.spawn(async move {
+            let completed = process_command(
+                &runner.app,
+                &runner.state,
+                &runner.copilot_state,
+                &runner.command_state,
+                &persona,
+                &command_text,
+                runner.db.as_ref(),
+            )
+            .await;
+            if completed {
+                let _ = runner.finished_tx.send(persona);
+            }
+        });
+    }
+
+    fn stop(&self) {
+        stop_command(&self.app, &self.copilot_state, &self.command_state);
+    }
+
+    /// Answer the pending tool confirmation. Anything but a yes or no declines it and
+    /// runs as a new command.
+    fn answer_confirmation(&self, rt: &tokio::runtime::Runtime, persona: Persona, reply: String) {
+        match parse_confirmation(&reply) {
+            Some(confirmed) => {
+                println!("[{}] Confirmation answered: {}", timestamp(), reply);
+                self.command_state.answer_confirmation(confirmed);
+                self.copilot_state.write().unwrap().heard_text = reply;
+            }
+            None => {
+                println!("[{}] Confirmation declined, new command ({}): {}", timestamp(), persona.name, reply);
+                self.command_state.answer_confirmation(false);
+                show_copilot_listening(&self.app, &self.copilot_state, &self.command_state);
+                self.copilot_state.write().unwrap().heard_text = reply.clone();
+                self.spawn(rt, persona, reply);
+            }
         }
+    }
+}
+
+/// Interrupt the in-flight command and dismiss the copilot window
+pub(crate) fn stop_command(app: &tauri::AppHandle, copilot_state: &CopilotState, command_state: &CommandControl) {
+    command_state.cancel();
+    if let Ok(mut copilot) = copilot_state.write() {
+        copilot.reset();
+    }
+    if let Some(window) = app.get_webview_window("copilot") {
+        let _ = window.hide();
+    }
+}
+
+/// Asks for tool confirmations in the copilot window; the answer comes by voice
+/// (see the audio loop) or from the window's buttons
+struct CopilotConfirmer {
+    app: tauri::AppHandle,
+    copilot_state: CopilotState,
+    command_state: CommandState,
+    persona: Persona,
+    cancel: CancellationToken,
+}
+
+impl Confirmer for CopilotConfirmer {
+    fn confirm(&self, question: String) -> futures::future::BoxFuture<'static, Option<bool>> {
+        let app = self.app.clone();
+        let copilot_state = self.copilot_state.clone();
+        let command_state = self.command_state.clone();
+        let persona = self.persona.clone();
+        let cancel = self.cancel.clone();
+
+        Box::pin(async move {
+            let _turn = command_state.confirmation_turn().await;
+            if cancel.is_cancelled() {
+                return None;
+            }
+
+            let answer = command_state.await_confirmation(persona);
+            {
+                let mut copilot = copilot_state.write().unwrap();
+                copilot.state = "confirming".to_string();
+                copilot.confirmation = Some(question.clone());
+                copilot.follow_up = false;
+            }
+            let _ = app.emit("confirmation-requested", &question);
+
+            let confirmed = tokio::select! {
+                answer = tokio::time::timeout(CONFIRMATION_TIMEOUT, answer) => answer.ok().and_then(|a| a.ok()),
+                _ = cancel.cancelled() => None,
+            };
+            println!("[{}] Confirmation {:?}: {}", timestamp(), confirmed, question);
+
+            if !cancel.is_cancelled() {
+                let mut copilot = copilot_state.write().unwrap();
+                copilot.confirmation = None;
+                copilot.state = "thinking".to_string();
+            }
+            confirmed
+        })
+    }
+}
+
+/// Run a locally recognized command directly with its tool, keeping it in the
+/// conversation so follow-up questions to the LLM have the context
+async fn run_local_intent(
+    app: &tauri::AppHandle,
+    state: &Arc<RwLock<AppState>>,
+    copilot_state: &CopilotState,
+    persona: &Persona,
+    command_text: &str,
+    intent: Intent,
+    db: Option<&DbState>,
+) {
+    let started = std::time::Instant::now();
+    let routing = std::collections::HashMap::from([(intent.tool.to_string(), ToolSource::Local)]);
+    let executor = ToolExecutor::new(app.clone(), state.clone(), db.cloned(), None, routing);
+
+    let response = match executor.execute(intent.tool, intent.input.clone()).await {
+        ToolResult::Success(text) | ToolResult::Error(text) => text,
+        ToolResult::Exit => String::new(),
     };
+    println!(
+        "[{}] Local intent {} ({} ms): {}",
+        timestamp(),
+        intent.tool,
+        started.elapsed().as_millis(),
+        response
+    );
 
-    // Get current history and add user message
-    let mut messages = {
-        let mut state_guard = state.write().await;
+    let _ = app.emit("command-response", &response);
+    {
+        let mut copilot = copilot_state.write().unwrap();
+        copilot.state = "responding".to_string();
+        copilot.response_text = response.clone();
+        copilot.follow_up = false;
+        copilot.should_close = true;
+    }
+
+    let messages = vec![
+        user_message(command_text),
+        Message {
+            role: "assistant".to_string(),
+            content: vec![ContentBlock::Text { text: response }],
+        },
+    ];
+    let mut state_guard = state.write().await;
+    let conversation_id = conversation::ensure_conversation(&mut state_guard, db, &persona.id, command_text);
+    conversation::persist_messages(db, conversation_id, &messages);
+    state_guard.conversation_histories.entry(persona.id.clone()).or_default().extend(messages);
+}
+
+/// Run one command through the agentic loop. Returns false if it was interrupted.
+async fn process_command(
+    app: &tauri::AppHandle,
+    state: &Arc<RwLock<AppState>>,
+    copilot_state: &CopilotState,
+    command_state: &CommandState,
+    persona: &Persona,
+    command_text: &str,
+    db: Option<&DbState>,
+) -> bool {
+    // Interrupt the previous command and wait until its history is saved
+    let (cancel, _running) = command_state.begin().await;
+    if cancel.is_cancelled() {
+        // Superseded before it even started
+        return false;
+    }
 
-        // Add user message
-        state_guard.conversation_history.push(user_message(command_text));
+    // "New conversation": forget the context, no LLM call needed
+    if is_new_conversation_command(command_text) {
+        println!("[{}] Starting a new conversation ({})", timestamp(), persona.name);
+        conversation::start_new_conversation(&mut *state.write().await, &persona.id);
+        let mut copilot = copilot_state.write().unwrap();
+        copilot.state = "responding".to_string();
+        copilot.response_text = "Starting a new conversation.".to_string();
+        copilot.should_close = true;
+        return true;
+    }
+
+    // Common commands run locally: fast, and they work without an API key or network
+    // (tools that need a confirmation go through the LLM, which asks for it)
+    let (local_intents_enabled, tool_policies) = {
+        let state = state.read().await;
+        (state.settings.local_intents_enabled, state.settings.tool_policies.clone())
+    };
+    if let Some(intent) = match_intent(command_text).filter(|i| {
+        local_intents_enabled && persona.allows_tool(i.tool) && tool_policy(&tool_policies, i.tool) == ToolPolicy::Safe
+    }) {
+        run_local_intent(app, state, copilot_state, persona, command_text, intent, db).await;
+        return true;
+    }
 
-        // Trim history if too long
-        if state_guard.conversation_history.len() > MAX_HISTORY_MESSAGES {
-            let drain_count = state_guard.conversation_history.len() - MAX_HISTORY_MESSAGES;
-            state_guard.conversation_history.drain(0..drain_count);
+    let (provider, retry_policy, usage_settings, history_token_budget) = {
+        let state = state.read().await;
+        let mut retry_policy = RetryPolicy::default();
+        if let Some(max_retries) = state.settings.llm.max_retries {
+            retry_policy.max_retries = max_retries;
         }
+        (
+            create_provider(&state.settings),
+            retry_policy,
+            state.settings.usage.clone(),
+            state.settings.history_token_budget,
+        )
+    };
 
-        state_guard.conversation_history.clone()
+    let provider = match provider {
+        Ok(provider) => {
+            println!("[{}] LLM model: {}", timestamp(), provider.model());
+            provider
+        }
+        Err(e) => {
+            println!("[{}] LLM not configured: {}", timestamp(), e);
+            let _ = app.emit("command-response", e.to_string());
+            let mut copilot = copilot_state.write().unwrap();
+            copilot.state = "responding".to_string();
+            copilot.response_text = format!(
+                "{}. Without it I can only start, stop and list recordings.",
+                e.to_string().trim_end_matches('.')
+            );
+            copilot.should_close = true;
+            return true;
+        }
     };
 
-    println!("[{}] Starting agentic loop with {} messages in history", timestamp(), messages.len());
+    // Token usage of this command (agentic turns and summaries) is stored per command
+    let usage_recorder: Option<Arc<dyn UsageRecorder>> = db
+        .map(|db| Arc::new(CommandUsage::new(db.clone(), command_text, usage_settings)) as Arc<dyn UsageRecorder>);
 
-    // Update copilot state to thinking
     {
         let mut copilot = copilot_state.write().unwrap();
         copilot.state = "thinking".to_string();
+        copilot.follow_up = false;
     }
 
-    let client = AgenticClient::new(&api_key);
-
     // Get MCP server configs and create manager
     let mcp_servers = {
         let state = state.read().await;
@@ -642,38 +1236,146 @@ async fn process_command(app: &tauri::AppHandle, state: &Arc<RwLock<AppState>>,
     let mcp_manager = if mcp_servers.is_empty() {
         None
     } else {
-        Some(Arc::new(McpManager::new(mcp_servers)))
+        Some(Arc::new(McpManager::new(mcp_servers.clone())))
     };
 
     // Get merged tools (local + MCP) and routing table
     let (tools, routing) = get_merged_tools(mcp_manager.as_ref().map(|m| m.as_ref())).await;
 
+    // Restrict to the persona's tool subset (routing too, so the executor rejects the rest),
+    // without the tools the user forbade
+    let allowed = |name: &str| persona.allows_tool(name) && tool_policy(&tool_policies, name) != ToolPolicy::Forbidden;
+    let tools: Vec<_> = tools.into_iter().filter(|t| allowed(&t.name)).collect();
+    let routing: std::collections::HashMap<_, _> = routing.into_iter().filter(|(name, _)| allowed(name)).collect();
+
+    // Date, active recording, connected servers... filled into the persona's prompt template
+    let prompt_context = {
+        let state = state.read().await;
+        let mut context = PromptContext::gather(&state, db, prompt::connected_servers(&mcp_servers, &routing));
+        if let Some(db) = db.and_then(|db| db.lock().ok()) {
+            context.memories = memory::prompt_memories(&db, command_text);
+        }
+        context
+    };
+
     // Create tool executor with MCP support
-    let executor = ToolExecutor::new(
+    let mut executor = ToolExecutor::new(
         app.clone(),
         state.clone(),
         db.cloned(),
         mcp_manager,
         routing,
     );
+    if let Some(recorder) = &usage_recorder {
+        executor = executor.with_usage_recorder(recorder.clone());
+    }
+    executor = executor.with_confirmer(Arc::new(CopilotConfirmer {
+        app: app.clone(),
+        copilot_state: copilot_state.clone(),
+        command_state: command_state.clone(),
+        persona: persona.clone(),
+        cancel: cancel.clone(),
+    }));
+
+    let command = AgenticCommand {
+        provider,
+        retry_policy,
+        usage_recorder,
+        history_token_budget,
+        tools: &tools,
+        prompt_context,
+        cancel: &cancel,
+    };
+    run_agentic_command(command, state, copilot_state, persona, command_text, db, |tool_name: &str, tool_input: serde_json::Value| {
+        let executor = executor.clone();
+        let name = tool_name.to_string();
+        Box::pin(async move {
+            executor.execute(&name, tool_input).await
+        })
+    })
+    .await
+}
+
+/// What the LLM part of a command runs with
+struct AgenticCommand<'a> {
+    provider: Arc<dyn LlmProvider>,
+    retry_policy: RetryPolicy,
+    usage_recorder: Option<Arc<dyn UsageRecorder>>,
+    history_token_budget: usize,
+    tools: &'a [ToolDefinition],
+    prompt_context: PromptContext,
+    cancel: &'a CancellationToken,
+}
+
+/// LLM part of a command: compacts the persona's history, runs the agentic loop with
+/// `execute_tool`, streams the answer to the copilot window and saves the history.
+/// Returns false if it was interrupted.
+async fn run_agentic_command<F>(
+    command: AgenticCommand<'_>,
+    state: &Arc<RwLock<AppState>>,
+    copilot_state: &CopilotState,
+    persona: &Persona,
+    command_text: &str,
+    db: Option<&DbState>,
+    execute_tool: F,
+) -> bool
+where
+    F: Fn(&str, serde_json::Value) -> std::pin::Pin<Box<dyn std::future::Future<Output = ToolResult> + Send>> + Send + Sync,
+{
+    let cancel = command.cancel;
+
+    // Get current history and summary of this persona and add user message
+    let (conversation_id, mut messages, mut summary) = {
+        let mut state_guard = state.write().await;
+        let conversation_id = conversation::ensure_conversation(&mut state_guard, db, &persona.id, command_text);
+        let mut history = state_guard.conversation_histories.get(&persona.id).cloned().unwrap_or_default();
+        history.push(user_message(command_text));
+        let summary = state_guard.conversation_summaries.get(&persona.id).cloned();
+        (conversation_id, history, summary)
+    };
+
+    // Fold older turns into the running summary when over budget
+    let compaction = compact_history(
+        command.provider.as_ref(),
+        &mut messages,
+        &mut summary,
+        command.history_token_budget,
+        command.usage_recorder.as_deref(),
+    );
+    tokio::select! {
+        _ = compaction => {}
+        _ = cancel.cancelled() => return false,
+    }
+
+    // Messages from the user command on are stored once the loop is done
+    let first_new_message = messages.len() - 1;
+
+    println!("[{}] Starting agentic loop ({}) with {} messages in history", timestamp(), persona.name, messages.len());
+
+    let mut client = AgenticClient::new(command.provider).with_retry_policy(command.retry_policy);
+    if let Some(recorder) = command.usage_recorder {
+        client = client.with_usage_recorder(recorder);
+    }
+
     let copilot_for_callback = copilot_state.clone();
     let has_started_responding = std::sync::atomic::AtomicBool::new(false);
 
+    // Static part of the persona prompt and the summary are cached, the context is not.
+    // The summary only changes when the history is compacted.
+    let system = prompt::system_blocks(&persona.system_prompt, &command.prompt_context, summary.as_deref());
+
     // Run agentic loop
     let result = client.run_agentic_loop(
         &mut messages,
-        &tools,
-        SYSTEM_PROMPT,
-        // Tool execution callback
-        |tool_name: &str, tool_input: serde_json::Value| {
-            let executor = executor.clone();
-            let name = tool_name.to_string();
-            Box::pin(async move {
-                executor.execute(&name, tool_input).await
-            })
-        },
+        command.tools,
+        &system,
+        cancel,
+        execute_tool,
         // Text streaming callback
         |text: &str| {
+            if cancel.is_cancelled() {
+                return;
+            }
             // Update to responding state on first chunk
             if !has_started_responding.swap(true, std::sync::atomic::Ordering::SeqCst) {
                 if let Ok(mut copilot) = copilot_for_callback.write() {
@@ -687,12 +1389,12 @@ async fn process_command(app: &tauri::AppHandle, state: &Arc<RwLock<AppState>>,
         },
     ).await;
 
-    match result {
+    let completed = match result {
         Ok(final_text) => {
             if !final_text.is_empty() {
                 // Truncate log to avoid verbose output
-                let preview = if final_text.len() > 100 {
-                    format!("{}...", &final_text[..100])
+                let preview = if final_text.chars().count() > 100 {
+                    format!("{}...", final_text.chars().take(100).collect::<String>())
                 } else {
                     final_text.clone()
                 };
@@ -703,22 +1405,152 @@ async fn process_command(app: &tauri::AppHandle, state: &Arc<RwLock<AppState>>,
                 let mut copilot = copilot_state.write().unwrap();
                 copilot.should_close = true;
             }
+            true
+        }
+        Err(e) if error_kind(&e) == Some(LlmErrorKind::Cancelled) => {
+            // Whoever interrupted already reset the copilot window
+            println!("[{}] Command interrupted ({})", timestamp(), persona.name);
+            false
         }
         Err(e) => {
             eprintln!("[{}] Agentic loop error: {}", timestamp(), e);
             // Set error message and signal close
             {
+                let kind = error_kind(&e);
+                let message = kind
+                    .map(|k| k.user_message())
+                    .unwrap_or("Sorry, I couldn't process that command");
                 let mut copilot = copilot_state.write().unwrap();
-                copilot.response_text = "Sorry, I couldn't process that command".to_string();
+                // Keep partial streamed text visible, the error follows it
+                if copilot.response_text.is_empty() {
+                    copilot.response_text = message.to_string();
+                } else {
+                    copilot.response_text.push_str(&format!("\n\n*{}*", message));
+                }
+                copilot.error = kind;
                 copilot.state = "responding".to_string();
                 copilot.should_close = true;
             }
+            true
         }
-    }
+    };
 
-    // Save updated history
+    // Save updated history (including an interrupted turn)
+    conversation::persist_messages(db, conversation_id, &messages[first_new_message..]);
     {
         let mut state_guard = state.write().await;
-        state_guard.conversation_history = messages;
+        state_guard.conversation_histories.insert(persona.id.clone(), messages);
+        match summary {
+            Some(summary) => state_guard.conversation_summaries.insert(persona.id.clone(), summary),
+            None => state_guard.conversation_summaries.remove(&persona.id),
+        };
+    }
+
+    completed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use state::Settings;
+    use std::path::PathBuf;
+
+    /// Settings replaying `fixture` (JSON) instead of calling the API, as configured in the app
+    fn replay_settings(fixture: &str) -> (Settings, PathBuf) {
+        let path = std::env::temp_dir().join(format!("fixture-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, fixture).unwrap();
+        let mut settings = Settings::default();
+        settings.llm.fixtures = serde_json::from_value(serde_json::json!({ "mode": "replay", "path": path })).unwrap();
+        (settings, path)
+    }
+
+    /// Run `command_text` as the default persona, without tools definitions or database.
+    /// Returns whether it completed, the saved history and the copilot state.
+    async fn run_command<F>(settings: Settings, command_text: &str, execute_tool: F) -> (bool, Vec<Message>, CopilotState)
+    where
+        F: Fn(&str, serde_json::Value) -> std::pin::Pin<Box<dyn std::future::Future<Output = ToolResult> + Send>> + Send + Sync,
+    {
+        let state = Arc::new(RwLock::new(AppState::default()));
+        let copilot_state: CopilotState = Arc::new(std::sync::RwLock::new(CopilotUIState::new()));
+        let persona = Persona::default_persona(&[]);
+        let cancel = CancellationToken::new();
+        let command = AgenticCommand {
+            provider: create_provider(&settings).unwrap(),
+            retry_policy: RetryPolicy { max_retries: 0, ..Default::default() },
+            usage_recorder: None,
+            history_token_budget: settings.history_token_budget,
+            tools: &[],
+            prompt_context: PromptContext::gather(&*state.read().await, None, Vec::new()),
+            cancel: &cancel,
+        };
+
+        let completed = run_agentic_command(command, &state, &copilot_state, &persona, command_text, None, execute_tool).await;
+        let history = state.read().await.conversation_histories.get(&persona.id).cloned().unwrap_or_default();
+        (completed, history, copilot_state)
+    }
+
+    #[tokio::test]
+    async fn test_command_replayed_with_parallel_tools() {
+        let (settings, path) = replay_settings(include_str!("../fixtures/llm/rename_two_recordings.json"));
+
+        // The two renames of the second turn wait for each other: they only finish if run in parallel
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let execute_tool = move |name: &str, input: serde_json::Value| {
+            let barrier = barrier.clone();
+            let renaming = name == "rename_recording";
+            Box::pin(async move {
+                if renaming {
+                    tokio::time::timeout(Duration::from_secs(5), barrier.wait())
+                        .await
+                        .expect("rename_recording calls were not run in parallel");
+                }
+                let result = match input["recording_index"].as_u64() {
+                    Some(1) => format!("Renamed 'Meeting 2' to '{}'", input["new_name"].as_str().unwrap_or("")),
+                    Some(2) => format!("Renamed 'Meeting 1' to '{}'", input["new_name"].as_str().unwrap_or("")),
+                    _ => "1. Meeting 2\n2. Meeting 1".to_string(),
+                };
+                ToolResult::Success(result)
+            }) as std::pin::Pin<Box<dyn std::future::Future<Output = ToolResult> + Send>>
+        };
+
+        let (completed, history, copilot_state) =
+            run_command(settings, "Rename my last two recordings to Standup and Retro", execute_tool).await;
+        let _ = std::fs::remove_file(&path);
+
+        assert!(completed);
+        let copilot = copilot_state.read().unwrap();
+        assert_eq!(copilot.state, "responding");
+        assert!(copilot.response_text.ends_with("Done: your last two recordings are now Standup and Retro."));
+        assert!(copilot.should_close);
+        assert_eq!(copilot.error, None);
+
+        // Whole exchange kept for the next command
+        let roles: Vec<&str> = history.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "user", "assistant", "user", "assistant"]);
+        assert_eq!(history[3].content.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_command_replayed_api_error_shown_to_user() {
+        let fixture = serde_json::json!({
+            "interactions": [{
+                "status": 429,
+                "response": r#"{"type":"error","error":{"type":"rate_limit_error","message":"Slow down"}}"#,
+            }],
+        });
+        let (settings, path) = replay_settings(&fixture.to_string());
+
+        let (completed, history, copilot_state) = run_command(settings, "What's on my agenda?", |_: &str, _| {
+            Box::pin(async { ToolResult::Error("No tools in this test".to_string()) })
+                as std::pin::Pin<Box<dyn std::future::Future<Output = ToolResult> + Send>>
+        })
+        .await;
+        let _ = std::fs::remove_file(&path);
+
+        assert!(completed);
+        let copilot = copilot_state.read().unwrap();
+        assert_eq!(copilot.error, Some(LlmErrorKind::RateLimited));
+        assert_eq!(copilot.response_text, LlmErrorKind::RateLimited.user_message());
+        assert_eq!(history[0].role, "user");
     }
 }
@@ -0,0 +1,402 @@
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+
+use super::cancel::CancellationToken;
+use super::error::LlmError;
+use super::provider::{ChatRequest, LlmProvider};
+use super::retry::{with_retry, RetryPolicy};
+use super::types::{ContentBlock, Message, SystemBlock, ToolDefinition};
+use crate::tools::ToolResult;
+use crate::usage::UsageRecorder;
+
+const MAX_ITERATIONS: usize = 30;
+
+// ============================================================================
+// Agentic Client
+// ============================================================================
+
+pub struct AgenticClient {
+    provider: Arc<dyn LlmProvider>,
+    retry_policy: RetryPolicy,
+    usage: Option<Arc<dyn UsageRecorder>>,
+}
+
+impl AgenticClient {
+    pub fn new(provider: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            provider,
+            retry_policy: RetryPolicy::default(),
+            usage: None,
+        }
+    }
+
+    /// Record token usage of each turn and enforce the budget before each call
+    pub fn with_usage_recorder(mut self, usage: Arc<dyn UsageRecorder>) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Run the agentic loop - pattern identical to JS @anthropic-ai/sdk
+    ///
+    /// Loop until stop_reason == "end_turn" OR no tool_use blocks.
+    /// When `cancel` fires, the HTTP stream and pending tools are dropped, the
+    /// partial turn is recorded in `messages` and a `Cancelled` error is returned.
+    pub async fn run_agentic_loop<F, G>(
+        &self,
+        messages: &mut Vec<Message>,
+        tools: &[ToolDefinition],
+        system: &[SystemBlock],
+        cancel: &CancellationToken,
+        execute_tool: F,
+        on_text: G,
+    ) -> Result<String>
+    where
+        F: Fn(&str, serde_json::Value) -> std::pin::Pin<Box<dyn std::future::Future<Output = ToolResult> + Send>> + Send + Sync,
+        G: Fn(&str) + Send + Sync,
+    {
+        let mut final_text = String::new();
+
+        for _iteration in 0..MAX_ITERATIONS {
+            if let Some(usage) = &self.usage {
+                usage.check_budget()?;
+            }
+
+            // 1. Stream one assistant turn from the provider.
+            // Transient failures are retried, unless text was already shown to the user.
+            let partial_text = Mutex::new(String::new());
+            let tracked_on_text = |text: &str| {
+                if cancel.is_cancelled() {
+                    return;
+                }
+                partial_text.lock().unwrap().push_str(text);
+                on_text(text);
+            };
+            let history: &[Message] = messages;
+            let streamed = tokio::select! {
+                turn = with_retry(
+                    &self.retry_policy,
+                    || {
+                        self.provider.stream_turn(
+                            ChatRequest {
+                                system,
+                                messages: history,
+                                tools,
+                            },
+                            &tracked_on_text,
+                        )
+                    },
+                    || partial_text.lock().unwrap().is_empty(),
+                ) => Some(turn),
+                _ = cancel.cancelled() => None,
+            };
+            let turn = match streamed {
+                Some(turn) => turn?,
+                None => {
+                    record_interruption(messages, &partial_text.into_inner().unwrap());
+                    return Err(LlmError::cancelled().into());
+                }
+            };
+            if let Some(usage) = &self.usage {
+                usage.record(self.provider.model(), &turn.usage);
+            }
+            // Cache hits (read) vs misses (written or uncached input)
+            if turn.usage.cache_read_input_tokens > 0 || turn.usage.cache_creation_input_tokens > 0 {
+                println!(
+                    "[LLM] Cache: {} read, {} written, {} uncached",
+                    turn.usage.cache_read_input_tokens,
+                    turn.usage.cache_creation_input_tokens,
+                    turn.usage.input_tokens
+                );
+            }
+
+            // 2. Build assistant content for history
+            let mut assistant_content: Vec<ContentBlock> = vec![];
+            if !turn.text.is_empty() {
+                assistant_content.push(ContentBlock::Text { text: turn.text.clone() });
+            }
+            for tu in &turn.tool_uses {
+                assistant_content.push(ContentBlock::ToolUse {
+                    id: tu.id.clone(),
+                    name: tu.name.clone(),
+                    input: tu.input.clone(),
+                });
+            }
+
+            // 3. Add assistant response to history (only if non-empty)
+            if !assistant_content.is_empty() {
+                messages.push(Message {
+                    role: "assistant".to_string(),
+                    content: assistant_content,
+                });
+            }
+
+            // 4. Check stop condition
+            if turn.stop_reason == "end_turn" || turn.tool_uses.is_empty() {
+                final_text = turn.text;
+                break;
+            }
+
+            // 5. Execute tools IN PARALLEL
+            let tool_futures: Vec<_> = turn.tool_uses.iter()
+                .map(|tu| execute_tool(&tu.name, tu.input.clone()))
+                .collect();
+
+            let results = tokio::select! {
+                results = futures::future::join_all(tool_futures) => results,
+                _ = cancel.cancelled() => {
+                    // Every tool_use needs a matching tool_result to keep history valid
+                    messages.push(Message {
+                        role: "user".to_string(),
+                        content: turn.tool_uses.iter()
+                            .map(|tu| ContentBlock::ToolResult {
+                                tool_use_id: tu.id.clone(),
+                                content: "Cancelled by the user".to_string(),
+                            })
+                            .collect(),
+                    });
+                    record_interruption(messages, "");
+                    return Err(LlmError::cancelled().into());
+                }
+            };
+
+            // 6. Build tool_results
+            let tool_results: Vec<ContentBlock> = turn.tool_uses.iter()
+                .zip(results)
+                .map(|(tu, result)| {
+                    let content = match result {
+                        ToolResult::Success(msg) => {
+                            // Truncate log to avoid repeating full content
+                            let preview = if msg.chars().count() > 80 {
+                                format!("{}...", msg.chars().take(80).collect::<String>())
+                            } else {
+                                msg.clone()
+                            };
+                            println!("[Tool OK] {}: {}", tu.name, preview);
+                            msg
+                        }
+                        ToolResult::Error(err) => {
+                            println!("[Tool Error] {}: {}", tu.name, err);
+                            format!("Error: {}", err)
+                        }
+                        ToolResult::Exit => {
+                            println!("[Tool Exit] {}", tu.name);
+                            "Exiting application".to_string()
+                        }
+                    };
+
+                    ContentBlock::ToolResult {
+                        tool_use_id: tu.id.clone(),
+                        content,
+                    }
+                })
+                .collect();
+
+            // 7. Add user message with tool_results
+            messages.push(Message {
+                role: "user".to_string(),
+                content: tool_results,
+            });
+        }
+
+        Ok(final_text)
+    }
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Close an interrupted turn with an assistant message, so history keeps
+/// alternating roles and the model knows its answer was cut short
+fn record_interruption(messages: &mut Vec<Message>, partial_text: &str) {
+    let text = if partial_text.trim().is_empty() {
+        "[Interrupted by the user]".to_string()
+    } else {
+        format!("{} [Interrupted by the user]", partial_text)
+    };
+    messages.push(Message {
+        role: "assistant".to_string(),
+        content: vec![ContentBlock::Text { text }],
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::error::{error_kind, LlmErrorKind};
+    use crate::llm::mock_server::{anthropic_text_response, http_response, sse_response, MockServer};
+    use crate::llm::provider::LlmConfig;
+    use crate::llm::types::user_message;
+    use std::time::Duration;
+
+    const OVERLOADED: &str = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+
+    fn client(server: &MockServer) -> AgenticClient {
+        let config = LlmConfig {
+            base_url: Some(server.base_url.clone()),
+            ..Default::default()
+        };
+        let provider = Arc::new(super::super::anthropic::AnthropicProvider::new("test-key", &config));
+        AgenticClient::new(provider).with_retry_policy(RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        })
+    }
+
+    async fn run(client: &AgenticClient) -> Result<String> {
+        let mut messages = vec![user_message("hello")];
+        client
+            .run_agentic_loop(
+                &mut messages,
+                &[],
+                &[],
+                &CancellationToken::new(),
+                |_: &str, _: serde_json::Value| Box::pin(async { ToolResult::Success(String::new()) }),
+                |_: &str| {},
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_retries_overloaded_then_succeeds() {
+        let server = MockServer::start(vec![
+            http_response(529, &[("retry-after", "0")], OVERLOADED),
+            http_response(429, &[], r#"{"type":"error","error":{"type":"rate_limit_error","message":"Slow down"}}"#),
+            anthropic_text_response("Done"),
+        ])
+        .await;
+
+        assert_eq!(run(&client(&server)).await.unwrap(), "Done");
+        assert_eq!(server.request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let server = MockServer::start(vec![
+            http_response(529, &[], OVERLOADED),
+            http_response(529, &[], OVERLOADED),
+            http_response(529, &[], OVERLOADED),
+        ])
+        .await;
+
+        let err = run(&client(&server)).await.unwrap_err();
+        assert_eq!(error_kind(&err), Some(LlmErrorKind::Overloaded));
+        assert_eq!(server.request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_auth_error_or_after_streamed_text() {
+        let server = MockServer::start(vec![http_response(
+            401,
+            &[],
+            r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#,
+        )])
+        .await;
+        let err = run(&client(&server)).await.unwrap_err();
+        assert_eq!(error_kind(&err), Some(LlmErrorKind::Authentication));
+        assert_eq!(server.request_count(), 1);
+
+        // Text already reached the user: retrying would repeat it
+        let server = MockServer::start(vec![
+            sse_response(&[
+                ("content_block_start", r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#),
+                ("content_block_delta", r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hel"}}"#),
+            ]),
+            anthropic_text_response("Hello"),
+        ])
+        .await;
+        let err = run(&client(&server)).await.unwrap_err();
+        assert_eq!(error_kind(&err), Some(LlmErrorKind::StreamInterrupted));
+        assert_eq!(server.request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_during_tools_keeps_history_valid() {
+        let server = MockServer::start(vec![sse_response(&[
+            ("content_block_start", r#"{"type":"content_block_start","index":0,"content_block":{"type":"tool_use","id":"toolu_1","name":"slow_tool"}}"#),
+            ("content_block_delta", r#"{"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":"{}"}}"#),
+            ("content_block_stop", r#"{"type":"content_block_stop","index":0}"#),
+            ("message_delta", r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"}}"#),
+            ("message_stop", r#"{"type":"message_stop"}"#),
+        ])])
+        .await;
+
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            canceller.cancel();
+        });
+
+        let mut messages = vec![user_message("hello")];
+        let err = client(&server)
+            .run_agentic_loop(
+                &mut messages,
+                &[],
+                &[],
+                &cancel,
+                // Tool never finishes on its own
+                |_: &str, _: serde_json::Value| Box::pin(futures::future::pending()),
+                |_: &str| {},
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(error_kind(&err), Some(LlmErrorKind::Cancelled));
+        let roles: Vec<&str> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "user", "assistant"]);
+        assert!(matches!(
+            &messages[2].content[0],
+            ContentBlock::ToolResult { tool_use_id, .. } if tool_use_id == "toolu_1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_replays_multi_step_tool_loop_from_fixture() {
+        let fixture = serde_json::from_str(include_str!("../../fixtures/llm/rename_two_recordings.json")).unwrap();
+        let server = crate::llm::fixtures::FixtureServer::start(fixture).unwrap();
+        let config = LlmConfig { base_url: Some(server.base_url.clone()), ..Default::default() };
+        let client = AgenticClient::new(Arc::new(super::super::anthropic::AnthropicProvider::new("replay", &config)));
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let mut messages = vec![user_message("Rename my last two recordings to Standup and Retro")];
+        let text = client
+            .run_agentic_loop(
+                &mut messages,
+                &[],
+                &[],
+                &CancellationToken::new(),
+                move |name: &str, input: serde_json::Value| {
+                    recorded.lock().unwrap().push((name.to_string(), input.clone()));
+                    let result = match input["recording_index"].as_u64() {
+                        Some(1) => format!("Renamed 'Meeting 2' to '{}'", input["new_name"].as_str().unwrap_or("")),
+                        Some(2) => format!("Renamed 'Meeting 1' to '{}'", input["new_name"].as_str().unwrap_or("")),
+                        _ => "1. Meeting 2\n2. Meeting 1".to_string(),
+                    };
+                    Box::pin(async move { ToolResult::Success(result) })
+                },
+                |_: &str| {},
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(text, "Done: your last two recordings are now Standup and Retro.");
+        let calls = calls.lock().unwrap();
+        let names: Vec<&str> = calls.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["list_recordings", "rename_recording", "rename_recording"]);
+        assert_eq!(calls[2].1["new_name"], "Retro");
+
+        // user, assistant(text + tool_use), user(result), assistant(2 tool_uses), user(2 results), assistant
+        let roles: Vec<&str> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["user", "assistant", "user", "assistant", "user", "assistant"]);
+        assert_eq!(messages[3].content.len(), 2);
+        assert_eq!(messages[4].content.len(), 2);
+    }
+}
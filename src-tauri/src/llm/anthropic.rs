@@ -2,56 +2,60 @@ use anyhow::{anyhow, Result};
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use crate::tools::ToolResult;
-
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
-const MODEL: &str = "claude-sonnet-4-20250514";
-const MAX_TOKENS: u32 = 4096;
-const MAX_ITERATIONS: usize = 30;
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::error::LlmError;
+use super::fixtures::{check_recorded_status, read_json, FixtureRecorder, RecordedStream};
+use super::provider::{
+    AssistantTurn, BoxFuture, ChatRequest, Completion, Extraction, LlmConfig, LlmProvider, TextCallback, ToolUse,
+};
+use super::sse::SseDecoder;
+use super::types::{ContentBlock, Message, SystemBlock, ToolDefinition};
+use crate::usage::Usage;
+
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+const DEFAULT_MAX_TOKENS: u32 = 4096;
 
 // ============================================================================
 // Types for Anthropic API
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Message {
-    pub role: String,
-    pub content: Vec<ContentBlock>,
+#[derive(Serialize)]
+struct StreamRequest<'a> {
+    model: String,
+    max_tokens: u32,
+    system: Vec<SystemBlockParam<'a>>,
+    messages: Vec<Value>,
+    tools: Vec<ToolParam<'a>>,
+    stream: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-pub enum ContentBlock {
-    #[serde(rename = "text")]
-    Text { text: String },
-    #[serde(rename = "tool_use")]
-    ToolUse {
-        id: String,
-        name: String,
-        input: serde_json::Value,
-    },
-    #[serde(rename = "tool_result")]
-    ToolResult {
-        tool_use_id: String,
-        content: String,
-    },
+/// Prompt caching breakpoint: everything up to and including this block is cached
+#[derive(Debug, Clone, Copy, Serialize)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    cache_type: &'static str,
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct ToolDefinition {
-    pub name: String,
-    pub description: String,
-    pub input_schema: serde_json::Value,
+const EPHEMERAL: CacheControl = CacheControl { cache_type: "ephemeral" };
+
+#[derive(Serialize)]
+struct SystemBlockParam<'a> {
+    #[serde(rename = "type")]
+    block_type: &'static str,
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
 }
 
 #[derive(Serialize)]
-struct StreamRequest {
-    model: String,
-    max_tokens: u32,
-    system: String,
-    messages: Vec<Message>,
-    tools: Vec<ToolDefinition>,
-    stream: bool,
+struct ToolParam<'a> {
+    #[serde(flatten)]
+    tool: &'a ToolDefinition,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,6 +66,18 @@ struct StreamEvent {
     content_block: Option<ContentBlockEvent>,
     #[serde(default)]
     delta: Option<DeltaEvent>,
+    /// message_start: initial usage (input and cache tokens)
+    #[serde(default)]
+    message: Option<MessageStartEvent>,
+    /// message_delta: cumulative usage (output tokens)
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStartEvent {
+    #[serde(default)]
+    usage: Option<Usage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -86,334 +102,462 @@ struct DeltaEvent {
     stop_reason: Option<String>,
 }
 
-
-// ============================================================================
-// Tool Use Block for internal tracking
-// ============================================================================
-
-#[derive(Debug, Clone)]
-struct ToolUseBlock {
-    id: String,
-    name: String,
-    input: serde_json::Value,
-}
-
 // ============================================================================
-// Agentic Client
+// Anthropic provider
 // ============================================================================
 
-pub struct AgenticClient {
+pub struct AnthropicProvider {
     client: Client,
     api_key: String,
+    base_url: String,
+    model: String,
+    max_tokens: u32,
+    prompt_caching: bool,
+    recorder: Option<Arc<FixtureRecorder>>,
 }
 
-impl AgenticClient {
-    pub fn new(api_key: &str) -> Self {
+impl AnthropicProvider {
+    pub fn new(api_key: &str, config: &LlmConfig) -> Self {
         Self {
             client: Client::new(),
             api_key: api_key.to_string(),
+            base_url: config
+                .base_url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            model: config.model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            max_tokens: config.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            prompt_caching: config.prompt_caching.unwrap_or(true),
+            recorder: None,
         }
     }
 
-    /// Run the agentic loop - pattern identical to JS @anthropic-ai/sdk
-    ///
-    /// Loop until stop_reason == "end_turn" OR no tool_use blocks
-    pub async fn run_agentic_loop<F, G>(
-        &self,
-        messages: &mut Vec<Message>,
-        tools: &[ToolDefinition],
-        system: &str,
-        execute_tool: F,
-        on_text: G,
-    ) -> Result<String>
-    where
-        F: Fn(&str, serde_json::Value) -> std::pin::Pin<Box<dyn std::future::Future<Output = ToolResult> + Send>> + Send + Sync,
-        G: Fn(&str) + Send + Sync,
-    {
-        let mut final_text = String::new();
-
-        for _iteration in 0..MAX_ITERATIONS {
-            // Debug: println!("[Iteration {}] Calling Claude API with {} messages...", iteration, messages.len());
-
-            // 1. Create streaming request
-            let request = StreamRequest {
-                model: MODEL.to_string(),
-                max_tokens: MAX_TOKENS,
-                system: system.to_string(),
-                messages: messages.clone(),
-                tools: tools.to_vec(),
-                stream: true,
-            };
-
-            let response = self.client
-                .post(ANTHROPIC_API_URL)
-                .header("x-api-key", &self.api_key)
-                .header("anthropic-version", "2023-06-01")
-                .header("content-type", "application/json")
-                .json(&request)
-                .send()
-                .await?;
-
-            if !response.status().is_success() {
-                let error_text = response.text().await?;
-                return Err(anyhow!("Anthropic API error: {}", error_text));
-            }
+    /// Save every exchange to a fixture file
+    pub fn with_recorder(mut self, recorder: Arc<FixtureRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    fn messages_url(&self) -> String {
+        format!("{}/v1/messages", self.base_url)
+    }
 
-            // 2. Process SSE stream
-            let mut text_content = String::new();
-            let mut tool_uses: Vec<ToolUseBlock> = vec![];
-            let mut current_tool_input = String::new();
-            let mut current_tool_id = String::new();
-            let mut current_tool_name = String::new();
-            let mut stop_reason = String::new();
-
-            let mut stream = response.bytes_stream();
-            let mut buffer = String::new();
-
-            while let Some(chunk) = stream.next().await {
-                let chunk = chunk?;
-                let chunk_str = String::from_utf8_lossy(&chunk);
-                buffer.push_str(&chunk_str);
-
-                // Process complete SSE lines
-                while let Some(pos) = buffer.find("\n\n") {
-                    let line = buffer[..pos].to_string();
-                    buffer = buffer[pos + 2..].to_string();
-
-                    // Parse SSE event - find the data: line within the block
-                    let data_line = line.lines()
-                        .find(|l| l.starts_with("data: "))
-                        .and_then(|l| l.strip_prefix("data: "));
-
-                    if let Some(data) = data_line {
-                        if data == "[DONE]" {
-                            continue;
+    async fn stream_turn_impl(&self, request: ChatRequest<'_>, on_text: TextCallback<'_>) -> Result<AssistantTurn> {
+        let body = build_stream_request(
+            &self.model,
+            self.max_tokens,
+            &request,
+            self.prompt_caching,
+        )?;
+
+        let response = self.client
+            .post(self.messages_url())
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(LlmError::network)?;
+
+        let response = check_recorded_status(response, self.recorder.as_ref(), &body).await?;
+        let status = response.status().as_u16();
+
+        // Process SSE stream
+        let mut turn = AssistantTurn::default();
+        let mut message_stopped = false;
+        let mut current_tool_input = String::new();
+        let mut current_tool_id = String::new();
+        let mut current_tool_name = String::new();
+
+        let mut stream = RecordedStream::new(response.bytes_stream(), status, self.recorder.as_ref(), &body);
+        let mut decoder = SseDecoder::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| LlmError::interrupted(e.to_string()))?;
+
+            for sse in decoder.feed(&chunk) {
+                let data = sse.data.as_str();
+                if data == "[DONE]" {
+                    continue;
+                }
+
+                if let Ok(event) = serde_json::from_str::<StreamEvent>(data) {
+                    match event.event_type.as_str() {
+                        // Keep-alive, nothing to do
+                        "ping" => {}
+
+                        // message_start: input and cache token usage
+                        "message_start" => {
+                            if let Some(usage) = event.message.and_then(|m| m.usage) {
+                                turn.usage.update(&usage);
+                            }
                         }
 
-                        if let Ok(event) = serde_json::from_str::<StreamEvent>(data) {
-                            match event.event_type.as_str() {
-                                // content_block_start: beginning of text or tool_use
-                                "content_block_start" => {
-                                    if let Some(cb) = event.content_block {
-                                        if cb.block_type == "tool_use" {
-                                            current_tool_id = cb.id.unwrap_or_default();
-                                            current_tool_name = cb.name.unwrap_or_default();
-                                            current_tool_input.clear();
-                                            println!("[Tool Start] {}", current_tool_name);
-                                        }
-                                    }
-                                }
+                        // Mid-stream failure (e.g. overloaded_error)
+                        "error" => {
+                            return Err(LlmError::from_stream_event(data).into());
+                        }
 
-                                // content_block_delta: text chunks or partial JSON
-                                "content_block_delta" => {
-                                    if let Some(delta) = event.delta {
-                                        match delta.delta_type.as_deref() {
-                                            Some("text_delta") => {
-                                                if let Some(text) = delta.text {
-                                                    on_text(&text);
-                                                    text_content.push_str(&text);
-                                                }
-                                            }
-                                            Some("input_json_delta") => {
-                                                if let Some(json) = delta.partial_json {
-                                                    current_tool_input.push_str(&json);
-                                                }
-                                            }
-                                            _ => {}
-                                        }
-                                    }
+                        // content_block_start: beginning of text or tool_use
+                        "content_block_start" => {
+                            if let Some(cb) = event.content_block {
+                                if cb.block_type == "tool_use" {
+                                    current_tool_id = cb.id.unwrap_or_default();
+                                    current_tool_name = cb.name.unwrap_or_default();
+                                    current_tool_input.clear();
+                                    println!("[Tool Start] {}", current_tool_name);
                                 }
+                            }
+                        }
 
-                                // content_block_stop: end of a block
-                                "content_block_stop" => {
-                                    if !current_tool_id.is_empty() {
-                                        let input: serde_json::Value = serde_json::from_str(&current_tool_input)
-                                            .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
-
-                                        tool_uses.push(ToolUseBlock {
-                                            id: current_tool_id.clone(),
-                                            name: current_tool_name.clone(),
-                                            input,
-                                        });
-
-                                        current_tool_id.clear();
-                                        current_tool_name.clear();
-                                        current_tool_input.clear();
+                        // content_block_delta: text chunks or partial JSON
+                        "content_block_delta" => {
+                            if let Some(delta) = event.delta {
+                                match delta.delta_type.as_deref() {
+                                    Some("text_delta") => {
+                                        if let Some(text) = delta.text {
+                                            on_text(&text);
+                                            turn.text.push_str(&text);
+                                        }
                                     }
-                                }
-
-                                // message_delta: contains stop_reason
-                                "message_delta" => {
-                                    if let Some(delta) = event.delta {
-                                        if let Some(reason) = delta.stop_reason {
-                                            stop_reason = reason;
+                                    Some("input_json_delta") => {
+                                        if let Some(json) = delta.partial_json {
+                                            current_tool_input.push_str(&json);
                                         }
                                     }
+                                    _ => {}
                                 }
+                            }
+                        }
 
-                                _ => {}
+                        // content_block_stop: end of a block
+                        "content_block_stop" => {
+                            if !current_tool_id.is_empty() {
+                                let input: serde_json::Value = serde_json::from_str(&current_tool_input)
+                                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+
+                                turn.tool_uses.push(ToolUse {
+                                    id: current_tool_id.clone(),
+                                    name: current_tool_name.clone(),
+                                    input,
+                                });
+
+                                current_tool_id.clear();
+                                current_tool_name.clear();
+                                current_tool_input.clear();
                             }
                         }
+
+                        // message_delta: contains stop_reason and output usage
+                        "message_delta" => {
+                            if let Some(delta) = event.delta {
+                                if let Some(reason) = delta.stop_reason {
+                                    turn.stop_reason = reason;
+                                }
+                            }
+                            if let Some(usage) = event.usage {
+                                turn.usage.update(&usage);
+                            }
+                        }
+
+                        "message_stop" => {
+                            message_stopped = true;
+                        }
+
+                        _ => {}
                     }
                 }
             }
+        }
 
-            // Debug: println!("[Iteration {}] Stop reason: {}, Tool uses: {}", iteration, stop_reason, tool_uses.len());
-
-            // 3. Build assistant content for history
-            let mut assistant_content: Vec<ContentBlock> = vec![];
-            if !text_content.is_empty() {
-                assistant_content.push(ContentBlock::Text { text: text_content.clone() });
-            }
-            for tu in &tool_uses {
-                assistant_content.push(ContentBlock::ToolUse {
-                    id: tu.id.clone(),
-                    name: tu.name.clone(),
-                    input: tu.input.clone(),
-                });
-            }
+        if !message_stopped {
+            return Err(LlmError::interrupted("stream ended before message_stop").into());
+        }
 
-            // 4. Add assistant response to history (only if non-empty)
-            if !assistant_content.is_empty() {
-                messages.push(Message {
-                    role: "assistant".to_string(),
-                    content: assistant_content,
-                });
-            }
+        Ok(turn)
+    }
 
-            // 5. Check stop condition
-            if stop_reason == "end_turn" || tool_uses.is_empty() {
-                final_text = text_content;
-                break;
-            }
+    async fn complete_impl(&self, prompt: &str, max_tokens: u32) -> Result<Completion> {
+        #[derive(Serialize)]
+        struct Request {
+            model: String,
+            max_tokens: u32,
+            messages: Vec<Message>,
+        }
 
-            // 6. Execute tools IN PARALLEL
-            let tool_futures: Vec<_> = tool_uses.iter()
-                .map(|tu| execute_tool(&tu.name, tu.input.clone()))
-                .collect();
-
-            let results = futures::future::join_all(tool_futures).await;
-
-            // 7. Build tool_results
-            let tool_results: Vec<ContentBlock> = tool_uses.iter()
-                .zip(results)
-                .map(|(tu, result)| {
-                    let content = match result {
-                        ToolResult::Success(msg) => {
-                            // Truncate log to avoid repeating full content
-                            let preview = if msg.len() > 80 {
-                                format!("{}...", &msg[..80])
-                            } else {
-                                msg.clone()
-                            };
-                            println!("[Tool OK] {}: {}", tu.name, preview);
-                            msg
-                        }
-                        ToolResult::Error(err) => {
-                            println!("[Tool Error] {}: {}", tu.name, err);
-                            format!("Error: {}", err)
-                        }
-                        ToolResult::Exit => {
-                            println!("[Tool Exit] {}", tu.name);
-                            "Exiting application".to_string()
-                        }
-                    };
+        #[derive(Deserialize)]
+        struct Response {
+            content: Vec<ContentBlockResponse>,
+            #[serde(default)]
+            usage: Usage,
+        }
 
-                    ContentBlock::ToolResult {
-                        tool_use_id: tu.id.clone(),
-                        content,
-                    }
-                })
-                .collect();
+        #[derive(Deserialize)]
+        struct ContentBlockResponse {
+            #[serde(rename = "type")]
+            block_type: String,
+            #[serde(default)]
+            text: Option<String>,
+        }
 
-            // 8. Add user message with tool_results
-            messages.push(Message {
+        let request = Request {
+            model: self.model.clone(),
+            max_tokens,
+            messages: vec![Message {
                 role: "user".to_string(),
-                content: tool_results,
-            });
+                content: vec![ContentBlock::Text { text: prompt.to_string() }],
+            }],
+        };
+
+        let response = self.client
+            .post(self.messages_url())
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(LlmError::network)?;
+
+        let response = check_recorded_status(response, self.recorder.as_ref(), &request).await?;
+        let response: Response = read_json(response, self.recorder.as_ref(), &request).await?;
+
+        for block in response.content {
+            if block.block_type == "text" {
+                if let Some(text) = block.text {
+                    return Ok(Completion { text, usage: response.usage });
+                }
+            }
         }
 
-        Ok(final_text)
+        Err(anyhow!("No text response from Anthropic"))
     }
-}
 
-// ============================================================================
-// Helper functions
-// ============================================================================
+    async fn extract_impl(&self, prompt: &str, tool: &ToolDefinition, max_tokens: u32) -> Result<Extraction> {
+        let request = serde_json::json!({
+            "model": self.model,
+            "max_tokens": max_tokens,
+            "messages": [{ "role": "user", "content": prompt }],
+            "tools": [tool],
+            "tool_choice": { "type": "tool", "name": tool.name },
+        });
+
+        let response = self.client
+            .post(self.messages_url())
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(LlmError::network)?;
+
+        let response = check_recorded_status(response, self.recorder.as_ref(), &request).await?;
+        let response: Value = read_json(response, self.recorder.as_ref(), &request).await?;
+        let usage: Usage = serde_json::from_value(response["usage"].clone()).unwrap_or_default();
+
+        let input = response["content"]
+            .as_array()
+            .and_then(|blocks| blocks.iter().find(|b| b["type"] == "tool_use" && b["name"] == tool.name.as_str()))
+            .map(|block| block["input"].clone())
+            .ok_or_else(|| anyhow!("No {} call in the Anthropic response", tool.name))?;
+
+        Ok(Extraction { input, usage })
+    }
+}
 
-/// Create a user message with text content
-pub fn user_message(text: &str) -> Message {
-    Message {
-        role: "user".to_string(),
-        content: vec![ContentBlock::Text { text: text.to_string() }],
+/// Build a streaming request. With caching, breakpoints go on the last tool, the last
+/// cacheable system block and the last message, so the next iteration of the
+/// agentic loop (same tools, same system prompt, longer history) reads them from cache.
+fn build_stream_request<'a>(
+    model: &str,
+    max_tokens: u32,
+    request: &ChatRequest<'a>,
+    caching: bool,
+) -> Result<StreamRequest<'a>> {
+    let breakpoint = |is_last: bool| (caching && is_last).then_some(EPHEMERAL);
+
+    let tools = request
+        .tools
+        .iter()
+        .enumerate()
+        .map(|(i, tool)| ToolParam {
+            tool,
+            cache_control: breakpoint(i + 1 == request.tools.len()),
+        })
+        .collect();
+
+    // Empty text blocks are rejected by the API
+    let system_blocks: Vec<&SystemBlock> = request.system.iter().filter(|b| !b.text.trim().is_empty()).collect();
+    let last_cacheable = system_blocks.iter().rposition(|b| b.cacheable);
+    let system = system_blocks
+        .iter()
+        .enumerate()
+        .map(|(i, block)| SystemBlockParam {
+            block_type: "text",
+            text: &block.text,
+            cache_control: breakpoint(Some(i) == last_cacheable),
+        })
+        .collect();
+
+    let mut messages = request
+        .messages
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<serde_json::Result<Vec<Value>>>()?;
+    if caching {
+        let last_block = messages
+            .last_mut()
+            .and_then(|m| m["content"].as_array_mut())
+            .and_then(|blocks| blocks.last_mut());
+        if let Some(Value::Object(block)) = last_block {
+            block.insert("cache_control".to_string(), serde_json::to_value(EPHEMERAL)?);
+        }
     }
+
+    Ok(StreamRequest {
+        model: model.to_string(),
+        max_tokens,
+        system,
+        messages,
+        tools,
+        stream: true,
+    })
 }
 
-/// Summarize text using Claude (non-streaming, simple completion)
-pub async fn summarize(api_key: &str, text: &str) -> Result<String> {
-    let client = Client::new();
+impl LlmProvider for AnthropicProvider {
+    fn model(&self) -> &str {
+        &self.model
+    }
 
-    #[derive(Serialize)]
-    struct Request {
-        model: String,
+    fn stream_turn<'a>(
+        &'a self,
+        request: ChatRequest<'a>,
+        on_text: TextCallback<'a>,
+    ) -> BoxFuture<'a, Result<AssistantTurn>> {
+        Box::pin(self.stream_turn_impl(request, on_text))
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str, max_tokens: u32) -> BoxFuture<'a, Result<Completion>> {
+        Box::pin(self.complete_impl(prompt, max_tokens))
+    }
+
+    fn extract<'a>(
+        &'a self,
+        prompt: &'a str,
+        tool: &'a ToolDefinition,
         max_tokens: u32,
-        messages: Vec<Message>,
+    ) -> BoxFuture<'a, Result<Extraction>> {
+        Box::pin(self.extract_impl(prompt, tool, max_tokens))
     }
+}
 
-    #[derive(Deserialize)]
-    struct Response {
-        content: Vec<ContentBlockResponse>,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::error::{error_kind, LlmErrorKind};
+    use crate::llm::mock_server::{anthropic_text_response, http_response, sse_response, MockServer};
+    use crate::llm::types::user_message;
+
+    async fn stream(server: &MockServer) -> Result<AssistantTurn> {
+        let config = LlmConfig {
+            base_url: Some(server.base_url.clone()),
+            ..Default::default()
+        };
+        let provider = AnthropicProvider::new("test-key", &config);
+        let messages = vec![user_message("hello")];
+        provider
+            .stream_turn(ChatRequest { system: &[], messages: &messages, tools: &[] }, &|_| {})
+            .await
     }
 
-    #[derive(Deserialize)]
-    struct ContentBlockResponse {
-        #[serde(rename = "type")]
-        block_type: String,
-        #[serde(default)]
-        text: Option<String>,
+    #[tokio::test]
+    async fn test_stream_with_ping() {
+        let server = MockServer::start(vec![anthropic_text_response("Bonjour")]).await;
+        let turn = stream(&server).await.unwrap();
+        assert_eq!(turn.text, "Bonjour");
+        assert_eq!(turn.stop_reason, "end_turn");
+        assert_eq!(turn.usage.input_tokens, 25);
+        assert_eq!(turn.usage.cache_read_input_tokens, 10);
+        assert_eq!(turn.usage.output_tokens, 12);
     }
 
-    let request = Request {
-        model: MODEL.to_string(),
-        max_tokens: 2048,
-        messages: vec![Message {
-            role: "user".to_string(),
-            content: vec![ContentBlock::Text {
-                text: format!(
-                    "Summarize the following audio transcription. Adapt your summary to the content type:\n\
-                    - For formal meetings: key points, decisions, action items\n\
-                    - For informal conversations: main topics discussed, people mentioned, any plans or intentions\n\
-                    - For any content: always provide a useful summary, never refuse\n\n\
-                    Keep it concise. Respond in the same language as the transcription.\n\n{}",
-                    text
-                ),
-            }],
-        }],
-    };
-
-    let response = client
-        .post(ANTHROPIC_API_URL)
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(anyhow!("Anthropic API error: {}", error_text));
+    #[tokio::test]
+    async fn test_stream_with_crlf_framing() {
+        let response = anthropic_text_response("Réunion d'équipe");
+        let (headers, body) = response.split_once("\r\n\r\n").unwrap();
+        let server = MockServer::start(vec![format!("{}\r\n\r\n{}", headers, body.replace('\n', "\r\n"))]).await;
+        let turn = stream(&server).await.unwrap();
+        assert_eq!(turn.text, "Réunion d'équipe");
+        assert_eq!(turn.stop_reason, "end_turn");
     }
 
-    let response: Response = response.json().await?;
+    #[test]
+    fn test_cache_breakpoints() {
+        let tools = vec![
+            ToolDefinition { name: "a".to_string(), description: String::new(), input_schema: serde_json::json!({}) },
+            ToolDefinition { name: "b".to_string(), description: String::new(), input_schema: serde_json::json!({}) },
+        ];
+        let system = vec![SystemBlock::cached("You are Robert."), SystemBlock::dynamic("Time: 10:00")];
+        let messages = vec![user_message("hello"), user_message("again")];
+        let request = ChatRequest { system: &system, messages: &messages, tools: &tools };
+
+        let body = serde_json::to_value(build_stream_request("m", 10, &request, true).unwrap()).unwrap();
+        assert!(body["tools"][0].get("cache_control").is_none());
+        assert_eq!(body["tools"][1]["cache_control"]["type"], "ephemeral");
+        assert_eq!(body["tools"][1]["name"], "b");
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        assert!(body["system"][1].get("cache_control").is_none());
+        assert!(body["messages"][0]["content"][0].get("cache_control").is_none());
+        assert_eq!(body["messages"][1]["content"][0]["cache_control"]["type"], "ephemeral");
+
+        let body = serde_json::to_value(build_stream_request("m", 10, &request, false).unwrap()).unwrap();
+        assert!(!body.to_string().contains("cache_control"));
+    }
 
-    for block in response.content {
-        if block.block_type == "text" {
-            if let Some(text) = block.text {
-                return Ok(text);
-            }
-        }
+    #[tokio::test]
+    async fn test_mid_stream_error_event() {
+        let server = MockServer::start(vec![sse_response(&[
+            ("message_start", r#"{"type":"message_start","message":{}}"#),
+            ("error", r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#),
+        ])])
+        .await;
+        let err = stream(&server).await.unwrap_err();
+        assert_eq!(error_kind(&err), Some(LlmErrorKind::Overloaded));
     }
 
-    Err(anyhow!("No text response from Anthropic"))
+    #[tokio::test]
+    async fn test_truncated_stream() {
+        let server = MockServer::start(vec![sse_response(&[
+            ("message_start", r#"{"type":"message_start","message":{}}"#),
+            ("content_block_start", r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#),
+            ("content_block_delta", r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hel"}}"#),
+        ])])
+        .await;
+        let err = stream(&server).await.unwrap_err();
+        assert_eq!(error_kind(&err), Some(LlmErrorKind::StreamInterrupted));
+    }
+
+    #[tokio::test]
+    async fn test_extract_reads_forced_tool_call() {
+        let body = r#"{"content":[{"type":"tool_use","id":"toolu_1","name":"record_notes","input":{"topics":["budget"]}}],"usage":{"input_tokens":40,"output_tokens":9}}"#;
+        let server = MockServer::start(vec![http_response(200, &[], body)]).await;
+        let config = LlmConfig {
+            base_url: Some(server.base_url.clone()),
+            ..Default::default()
+        };
+        let provider = AnthropicProvider::new("test-key", &config);
+        let tool = ToolDefinition {
+            name: "record_notes".to_string(),
+            description: "Record notes".to_string(),
+            input_schema: serde_json::json!({ "type": "object" }),
+        };
+
+        let extraction = provider.extract("notes please", &tool, 100).await.unwrap();
+        assert_eq!(extraction.input["topics"][0], "budget");
+        assert_eq!(extraction.usage.output_tokens, 9);
+    }
 }
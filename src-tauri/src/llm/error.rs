@@ -0,0 +1,194 @@
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+// ============================================================================
+// Typed LLM errors
+// ============================================================================
+
+/// Error categories, decide whether a call is retried and what the user sees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmErrorKind {
+    /// 429 / rate_limit_error
+    RateLimited,
+    /// 529 / overloaded_error
+    Overloaded,
+    /// 401, 403 / authentication_error, permission_error
+    Authentication,
+    /// 400, 404, 413 / invalid_request_error...
+    InvalidRequest,
+    /// 5xx / api_error
+    Server,
+    /// Connection failed or timed out before a response
+    Network,
+    /// Stream ended or broke before the message was complete
+    StreamInterrupted,
+    /// Interrupted by the user (not an API failure)
+    Cancelled,
+    /// Monthly spending limit reached, no call was made
+    BudgetExceeded,
+}
+
+impl LlmErrorKind {
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited | Self::Overloaded | Self::Server | Self::Network | Self::StreamInterrupted
+        )
+    }
+
+    /// Short message shown in the copilot window
+    pub fn user_message(self) -> &'static str {
+        match self {
+            Self::RateLimited => "Rate limit reached, please try again in a moment.",
+            Self::Overloaded => "The model is overloaded right now, please try again shortly.",
+            Self::Authentication => "The API key was rejected, please check your settings.",
+            Self::InvalidRequest => "The request was rejected by the API.",
+            Self::Server => "The API had an internal error, please try again.",
+            Self::Network => "Couldn't reach the API, please check your connection.",
+            Self::StreamInterrupted => "The response was cut off, please try again.",
+            Self::Cancelled => "Stopped.",
+            Self::BudgetExceeded => "The monthly LLM budget is used up, you can raise it in settings.",
+        }
+    }
+
+    /// Map an API error type (`{"type":"error","error":{"type":...}}`)
+    fn from_api_type(error_type: &str) -> Option<Self> {
+        match error_type {
+            "rate_limit_error" => Some(Self::RateLimited),
+            "overloaded_error" => Some(Self::Overloaded),
+            "authentication_error" | "permission_error" => Some(Self::Authentication),
+            "invalid_request_error" | "not_found_error" | "request_too_large" => Some(Self::InvalidRequest),
+            "api_error" | "server_error" => Some(Self::Server),
+            _ => None,
+        }
+    }
+
+    fn from_status(status: u16) -> Self {
+        match status {
+            429 => Self::RateLimited,
+            529 | 503 => Self::Overloaded,
+            401 | 403 => Self::Authentication,
+            500..=599 => Self::Server,
+            _ => Self::InvalidRequest,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LlmError {
+    pub kind: LlmErrorKind,
+    pub message: String,
+    /// Delay requested by the server (`retry-after` header)
+    pub retry_after: Option<Duration>,
+}
+
+impl LlmError {
+    pub fn new(kind: LlmErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into(), retry_after: None }
+    }
+
+    /// Error for a non-2xx HTTP response; the body's error type wins over the status
+    pub fn from_response(status: u16, body: &str, retry_after: Option<Duration>) -> Self {
+        let (api_type, message) = parse_error_body(body);
+        let kind = api_type
+            .as_deref()
+            .and_then(LlmErrorKind::from_api_type)
+            .unwrap_or_else(|| LlmErrorKind::from_status(status));
+
+        Self {
+            kind,
+            message: format!("HTTP {}: {}", status, message.unwrap_or_else(|| body.trim().to_string())),
+            retry_after,
+        }
+    }
+
+    /// Error for an `error` event received in the middle of a stream
+    pub fn from_stream_event(data: &str) -> Self {
+        let (api_type, message) = parse_error_body(data);
+        let kind = api_type
+            .as_deref()
+            .and_then(LlmErrorKind::from_api_type)
+            .unwrap_or(LlmErrorKind::Server);
+
+        Self::new(kind, message.unwrap_or_else(|| data.to_string()))
+    }
+
+    pub fn network(err: reqwest::Error) -> Self {
+        Self::new(LlmErrorKind::Network, err.to_string())
+    }
+
+    pub fn interrupted(message: impl Into<String>) -> Self {
+        Self::new(LlmErrorKind::StreamInterrupted, message)
+    }
+
+    pub fn cancelled() -> Self {
+        Self::new(LlmErrorKind::Cancelled, "interrupted by the user")
+    }
+}
+
+impl fmt::Display for LlmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+/// Category of an error returned by a provider, if it is an `LlmError`
+pub fn error_kind(err: &anyhow::Error) -> Option<LlmErrorKind> {
+    err.downcast_ref::<LlmError>().map(|e| e.kind)
+}
+
+/// Parse the `retry-after` header (seconds; HTTP dates are ignored)
+pub fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    let seconds: f64 = value.trim().parse().ok()?;
+    if seconds.is_finite() && seconds >= 0.0 {
+        Some(Duration::from_secs_f64(seconds))
+    } else {
+        None
+    }
+}
+
+/// Turn a non-2xx response into a typed error
+pub async fn check_status(response: reqwest::Response) -> anyhow::Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = retry_after(response.headers());
+    let body = response.text().await.unwrap_or_default();
+    Err(LlmError::from_response(status.as_u16(), &body, retry_after).into())
+}
+
+/// Extract (error type, message) from an Anthropic or OpenAI-style error body
+fn parse_error_body(body: &str) -> (Option<String>, Option<String>) {
+    let value: serde_json::Value = match serde_json::from_str(body) {
+        Ok(v) => v,
+        Err(_) => return (None, None),
+    };
+    let error = &value["error"];
+    let api_type = error["type"].as_str().map(|s| s.to_string());
+    let message = error["message"].as_str().map(|s| s.to_string());
+    (api_type, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_type_wins_over_status() {
+        let body = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        let err = LlmError::from_response(500, body, None);
+        assert_eq!(err.kind, LlmErrorKind::Overloaded);
+        assert_eq!(err.message, "HTTP 500: Overloaded");
+
+        let err = LlmError::from_response(401, "unauthorized", None);
+        assert_eq!(err.kind, LlmErrorKind::Authentication);
+        assert!(!err.kind.is_retryable());
+    }
+}
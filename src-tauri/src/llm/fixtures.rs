@@ -0,0 +1,496 @@
+//! Record and replay of LLM API exchanges. In record mode, providers append each
+//! request and its raw response (SSE stream or JSON) to a fixture file; in replay
+//! mode, a local server serves them back in order, so the agentic loop runs
+//! deterministically without network or API key.
+
+use anyhow::{anyhow, Result};
+use futures::Stream;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+
+use super::error::{check_status, retry_after, LlmError};
+
+/// Record or replay LLM calls (development and tests)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixtureMode {
+    Record,
+    Replay,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureConfig {
+    pub mode: FixtureMode,
+    pub path: PathBuf,
+    /// Record over an existing fixture file (refused otherwise)
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// One request/response pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Request body, without the system prompt. On replay, only the fields present
+    /// here are checked.
+    #[serde(default)]
+    pub request: Option<Value>,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    /// Raw response body: SSE text for streamed calls, JSON otherwise
+    pub response: String,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureFile {
+    pub interactions: Vec<Interaction>,
+}
+
+impl FixtureFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read fixture {}: {}", path.display(), e))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+// ============================================================================
+// Recording
+// ============================================================================
+
+/// Appends interactions to a fixture file (rewritten after each one)
+pub struct FixtureRecorder {
+    path: PathBuf,
+    file: Mutex<FixtureFile>,
+}
+
+impl FixtureRecorder {
+    /// Fails if `path` exists, unless `overwrite`
+    pub fn new(path: &Path, overwrite: bool) -> Result<Self> {
+        if path.exists() && !overwrite {
+            return Err(anyhow!(
+                "Fixture {} already exists, set overwrite to record over it",
+                path.display()
+            ));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(FixtureFile::default()),
+        })
+    }
+
+    /// Recorder shared by every provider recording to `path`, so the fixture
+    /// covers all commands of the session
+    pub fn shared(path: &Path, overwrite: bool) -> Result<Arc<Self>> {
+        static RECORDERS: OnceLock<Mutex<HashMap<PathBuf, Arc<FixtureRecorder>>>> = OnceLock::new();
+        let mut recorders = RECORDERS.get_or_init(Default::default).lock().unwrap();
+        if let Some(recorder) = recorders.get(path) {
+            return Ok(recorder.clone());
+        }
+        let recorder = Arc::new(Self::new(path, overwrite)?);
+        recorders.insert(path.to_path_buf(), recorder.clone());
+        Ok(recorder)
+    }
+
+    pub fn record(&self, request: Value, status: u16, response: String) {
+        let mut file = match self.file.lock() {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+        file.interactions.push(Interaction { request: Some(stable_request(request)), status, response });
+
+        let saved = serde_json::to_string_pretty(&*file)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(std::fs::write(&self.path, json)?));
+        if let Err(e) = saved {
+            eprintln!("[Fixtures] Failed to write {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Response byte stream that also records the body; the interaction is saved
+/// when the stream is dropped, so interrupted streams are recorded as received
+pub struct RecordedStream<S> {
+    inner: S,
+    recording: Option<(Arc<FixtureRecorder>, Value)>,
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl<S> RecordedStream<S> {
+    pub fn new(inner: S, status: u16, recorder: Option<&Arc<FixtureRecorder>>, request: impl Serialize) -> Self {
+        let recording = recorder.map(|r| (r.clone(), serde_json::to_value(request).unwrap_or(Value::Null)));
+        Self { inner, recording, status, body: Vec::new() }
+    }
+}
+
+impl<S, B, E> Stream for RecordedStream<S>
+where
+    S: Stream<Item = std::result::Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Item = std::result::Result<B, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.inner).poll_next(cx);
+        if let Poll::Ready(Some(Ok(chunk))) = &poll {
+            if self.recording.is_some() {
+                let chunk = chunk.as_ref().to_vec();
+                self.body.extend_from_slice(&chunk);
+            }
+        }
+        poll
+    }
+}
+
+impl<S> Drop for RecordedStream<S> {
+    fn drop(&mut self) {
+        if let Some((recorder, request)) = self.recording.take() {
+            recorder.record(request, self.status, String::from_utf8_lossy(&self.body).into_owned());
+        }
+    }
+}
+
+/// Read a non-streamed JSON response, recording it
+pub async fn read_json<T: DeserializeOwned>(
+    response: reqwest::Response,
+    recorder: Option<&Arc<FixtureRecorder>>,
+    request: impl Serialize,
+) -> Result<T> {
+    let status = response.status().as_u16();
+    let body = response.text().await.map_err(|e| LlmError::interrupted(e.to_string()))?;
+    if let Some(recorder) = recorder {
+        recorder.record(serde_json::to_value(request).unwrap_or(Value::Null), status, body.clone());
+    }
+    Ok(serde_json::from_str(&body).map_err(|e| LlmError::interrupted(e.to_string()))?)
+}
+
+/// `check_status` that also records error responses, so a replay fails the same way
+pub async fn check_recorded_status(
+    response: reqwest::Response,
+    recorder: Option<&Arc<FixtureRecorder>>,
+    request: impl Serialize,
+) -> Result<reqwest::Response> {
+    let recorder = match recorder {
+        Some(recorder) if !response.status().is_success() => recorder,
+        _ => return check_status(response).await,
+    };
+
+    let status = response.status().as_u16();
+    let retry_after = retry_after(response.headers());
+    let body = response.text().await.unwrap_or_default();
+    recorder.record(serde_json::to_value(request).unwrap_or(Value::Null), status, body.clone());
+    Err(LlmError::from_response(status, &body, retry_after).into())
+}
+
+// ============================================================================
+// Replay
+// ============================================================================
+
+/// Local HTTP server answering requests with the fixture's responses, in order
+pub struct FixtureServer {
+    pub base_url: String,
+}
+
+impl FixtureServer {
+    pub fn start(fixture: FixtureFile) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+
+        std::thread::spawn(move || {
+            let mut interactions = fixture.interactions.into_iter();
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                };
+                let request = stable_request(read_request_body(&mut stream));
+                let response = match interactions.next() {
+                    Some(interaction) => match mismatch(interaction.request.map(stable_request).as_ref(), &request) {
+                        None => http_response(interaction.status, &interaction.response),
+                        Some(reason) => error_response(&format!("Request does not match the fixture: {}", reason)),
+                    },
+                    None => error_response("No more recorded responses in the fixture"),
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        Ok(Self { base_url })
+    }
+
+    /// Replay server shared by every provider replaying `path`, so successive
+    /// commands continue through the fixture
+    pub fn shared(path: &Path) -> Result<String> {
+        static SERVERS: OnceLock<Mutex<HashMap<PathBuf, String>>> = OnceLock::new();
+        let mut servers = SERVERS.get_or_init(Default::default).lock().unwrap();
+        if let Some(url) = servers.get(path) {
+            return Ok(url.clone());
+        }
+        let server = Self::start(FixtureFile::load(path)?)?;
+        servers.insert(path.to_path_buf(), server.base_url.clone());
+        Ok(server.base_url)
+    }
+}
+
+/// Request as recorded and compared on replay: the system prompt is left out, since it
+/// holds the time, the recordings and the memories of the moment (Anthropic sends it as
+/// `system`, OpenAI-compatible servers as a first "system" message)
+fn stable_request(mut request: Value) -> Value {
+    if let Some(request) = request.as_object_mut() {
+        request.remove("system");
+        if let Some(Value::Array(messages)) = request.get_mut("messages") {
+            messages.retain(|message| message["role"] != "system");
+        }
+    }
+    request
+}
+
+/// Why `actual` doesn't contain `expected` (fields of expected objects must match,
+/// arrays must have the same length), or None if it does
+fn mismatch(expected: Option<&Value>, actual: &Value) -> Option<String> {
+    fn check(expected: &Value, actual: &Value, path: &str) -> Option<String> {
+        match (expected, actual) {
+            (Value::Object(expected), Value::Object(actual)) => expected.iter().find_map(|(key, value)| {
+                let path = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(actual) => check(value, actual, &path),
+                    None => Some(format!("{} is missing", path)),
+                }
+            }),
+            (Value::Array(expected), Value::Array(actual)) => {
+                if expected.len() != actual.len() {
+                    return Some(format!("{} has {} items instead of {}", path, actual.len(), expected.len()));
+                }
+                expected
+                    .iter()
+                    .zip(actual)
+                    .enumerate()
+                    .find_map(|(i, (e, a))| check(e, a, &format!("{}[{}]", path, i)))
+            }
+            _ if expected == actual => None,
+            _ => Some(format!("{} is {} instead of {}", path, actual, expected)),
+        }
+    }
+
+    expected.and_then(|expected| check(expected, actual, "request"))
+}
+
+fn http_response(status: u16, body: &str) -> String {
+    let content_type = if body.starts_with("event:") || body.starts_with("data:") {
+        "text/event-stream"
+    } else {
+        "application/json"
+    };
+    format!(
+        "HTTP/1.1 {} Replay\r\ncontent-type: {}\r\nconnection: close\r\ncontent-length: {}\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Non-retryable API error, so a broken fixture fails the test at once
+fn error_response(message: &str) -> String {
+    let body = serde_json::json!({
+        "type": "error",
+        "error": { "type": "invalid_request_error", "message": message },
+    });
+    http_response(400, &body.to_string())
+}
+
+fn read_request_body(stream: &mut TcpStream) -> Value {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 8192];
+
+    loop {
+        let n = match stream.read(&mut buf) {
+            Ok(0) | Err(_) => return Value::Null,
+            Ok(n) => n,
+        };
+        data.extend_from_slice(&buf[..n]);
+
+        let header_end = match data.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(pos) => pos,
+            None => continue,
+        };
+        let headers = String::from_utf8_lossy(&data[..header_end]);
+        let content_length = headers
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if data.len() >= header_end + 4 + content_length {
+            let body = &data[header_end + 4..header_end + 4 + content_length];
+            return serde_json::from_slice(body).unwrap_or(Value::Null);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::anthropic::AnthropicProvider;
+    use crate::llm::error::{error_kind, LlmErrorKind};
+    use crate::llm::mock_server::{anthropic_text_response, MockServer};
+    use crate::llm::provider::{ChatRequest, LlmConfig, LlmProvider};
+    use crate::llm::types::user_message;
+    use crate::persona::Persona;
+    use crate::prompt::{system_blocks, PromptContext};
+    use chrono::{FixedOffset, TimeZone};
+    use serde_json::json;
+
+    #[test]
+    fn test_request_subset_matching() {
+        let actual = json!({
+            "model": "claude",
+            "messages": [{ "role": "user", "content": [{ "type": "text", "text": "hi" }] }],
+            "stream": true,
+        });
+
+        assert_eq!(mismatch(None, &actual), None);
+        assert_eq!(mismatch(Some(&json!({ "stream": true })), &actual), None);
+        assert_eq!(
+            mismatch(Some(&json!({ "messages": [{ "role": "user" }] })), &actual),
+            None
+        );
+        assert_eq!(
+            mismatch(Some(&json!({ "messages": [{ "role": "assistant" }] })), &actual).as_deref(),
+            Some(r#"request.messages[0].role is "user" instead of "assistant""#)
+        );
+        assert!(mismatch(Some(&json!({ "messages": [] })), &actual).is_some());
+        assert!(mismatch(Some(&json!({ "tools": [] })), &actual).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_round_trip() {
+        let path = std::env::temp_dir().join(format!("fixture-{}.json", uuid::Uuid::new_v4()));
+        let messages = vec![user_message("hello")];
+        let request = || ChatRequest { system: &[], messages: &messages, tools: &[] };
+
+        // Record against a live (mock) API
+        let server = MockServer::start(vec![anthropic_text_response("Hi there")]).await;
+        let config = LlmConfig { base_url: Some(server.base_url.clone()), ..Default::default() };
+        let provider = AnthropicProvider::new("test-key", &config).with_recorder(Arc::new(FixtureRecorder::new(&path, false).unwrap()));
+        let recorded = provider.stream_turn(request(), &|_: &str| {}).await.unwrap();
+        drop(provider);
+
+        let fixture = FixtureFile::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(fixture.interactions.len(), 1);
+        assert_eq!(fixture.interactions[0].request.as_ref().unwrap()["stream"], true);
+
+        // Replay without the API
+        let replay = FixtureServer::start(fixture).unwrap();
+        let config = LlmConfig { base_url: Some(replay.base_url.clone()), ..Default::default() };
+        let provider = AnthropicProvider::new("replay", &config);
+        let replayed = provider.stream_turn(request(), &|_: &str| {}).await.unwrap();
+        assert_eq!(replayed.text, recorded.text);
+        assert_eq!(replayed.text, "Hi there");
+        assert_eq!(replayed.usage.output_tokens, recorded.usage.output_tokens);
+
+        // Fixture exhausted: the call fails instead of reaching the network
+        assert!(provider.stream_turn(request(), &|_: &str| {}).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_ignores_the_system_prompt() {
+        let path = std::env::temp_dir().join(format!("fixture-{}.json", uuid::Uuid::new_v4()));
+        let template = Persona::default_persona(&[]).system_prompt;
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        let mut context = PromptContext {
+            now: offset.with_ymd_and_hms(2026, 3, 12, 9, 5, 0).unwrap(),
+            timezone: "Europe/Paris".to_string(),
+            locale: "fr-FR".to_string(),
+            user_name: None,
+            active_recording: None,
+            recording_count: 3,
+            mcp_servers: Vec::new(),
+            memories: vec!["My manager is Alice".to_string()],
+        };
+        let messages = vec![user_message("hello")];
+
+        let server = MockServer::start(vec![anthropic_text_response("Hi there")]).await;
+        let config = LlmConfig { base_url: Some(server.base_url.clone()), ..Default::default() };
+        let provider = AnthropicProvider::new("test-key", &config).with_recorder(Arc::new(FixtureRecorder::new(&path, false).unwrap()));
+        let system = system_blocks(&template, &context, None);
+        provider.stream_turn(ChatRequest { system: &system, messages: &messages, tools: &[] }, &|_: &str| {}).await.unwrap();
+        drop(provider);
+
+        let fixture = FixtureFile::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let recorded = fixture.interactions[0].request.as_ref().unwrap();
+        assert!(recorded.get("system").is_none() && recorded["messages"].is_array());
+
+        // Replayed later, with another recording and other memories
+        context.now += chrono::Duration::minutes(42);
+        context.active_recording = Some("Standup".to_string());
+        context.recording_count = 4;
+        context.memories = vec!["The deploy is Thursday".to_string()];
+        let replay = FixtureServer::start(fixture).unwrap();
+        let config = LlmConfig { base_url: Some(replay.base_url.clone()), ..Default::default() };
+        let provider = AnthropicProvider::new("replay", &config);
+        let system = system_blocks(&template, &context, None);
+        let replayed = provider
+            .stream_turn(ChatRequest { system: &system, messages: &messages, tools: &[] }, &|_: &str| {})
+            .await
+            .unwrap();
+        assert_eq!(replayed.text, "Hi there");
+    }
+
+    #[test]
+    fn test_stable_request() {
+        let openai = json!({
+            "model": "llama",
+            "messages": [{ "role": "system", "content": "It is 09:05" }, { "role": "user", "content": "hi" }],
+        });
+        assert_eq!(
+            stable_request(openai),
+            json!({ "model": "llama", "messages": [{ "role": "user", "content": "hi" }] })
+        );
+        let anthropic = json!({ "system": [{ "type": "text", "text": "It is 09:05" }], "messages": [] });
+        assert_eq!(stable_request(anthropic), json!({ "messages": [] }));
+    }
+
+    #[tokio::test]
+    async fn test_records_and_replays_error_status() {
+        let path = std::env::temp_dir().join(format!("fixture-{}.json", uuid::Uuid::new_v4()));
+        let messages = vec![user_message("hello")];
+        let request = || ChatRequest { system: &[], messages: &messages, tools: &[] };
+        let body = r#"{"type":"error","error":{"type":"rate_limit_error","message":"Slow down"}}"#;
+
+        let server = MockServer::start(vec![http_response(429, body)]).await;
+        let config = LlmConfig { base_url: Some(server.base_url.clone()), ..Default::default() };
+        let provider = AnthropicProvider::new("test-key", &config).with_recorder(Arc::new(FixtureRecorder::new(&path, false).unwrap()));
+        let err = provider.stream_turn(request(), &|_: &str| {}).await.unwrap_err();
+        assert_eq!(error_kind(&err), Some(LlmErrorKind::RateLimited));
+
+        // An existing fixture is only recorded over when asked to
+        assert!(FixtureRecorder::new(&path, false).is_err());
+        assert!(FixtureRecorder::new(&path, true).is_ok());
+
+        let fixture = FixtureFile::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(fixture.interactions.len(), 1);
+        assert_eq!(fixture.interactions[0].status, 429);
+
+        let replay = FixtureServer::start(fixture).unwrap();
+        let config = LlmConfig { base_url: Some(replay.base_url.clone()), ..Default::default() };
+        let provider = AnthropicProvider::new("replay", &config);
+        let err = provider.stream_turn(request(), &|_: &str| {}).await.unwrap_err();
+        assert_eq!(error_kind(&err), Some(LlmErrorKind::RateLimited));
+    }
+}
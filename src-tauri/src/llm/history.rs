@@ -0,0 +1,213 @@
+use anyhow::Result;
+
+use super::provider::LlmProvider;
+use super::summarize::complete_with_usage;
+use super::types::{ContentBlock, Message, SystemBlock};
+use crate::usage::UsageRecorder;
+
+/// Rough token estimate: ~4 characters per token of serialized JSON
+const CHARS_PER_TOKEN: usize = 4;
+/// Tool inputs and results are cut to this many characters in the summary prompt
+const MAX_BLOCK_CHARS_IN_SUMMARY: usize = 500;
+
+// ============================================================================
+// History compaction
+// ============================================================================
+
+pub fn estimate_text_tokens(text: &str) -> usize {
+    text.len() / CHARS_PER_TOKEN + 1
+}
+
+pub fn estimate_tokens(messages: &[Message]) -> usize {
+    messages
+        .iter()
+        .map(|m| estimate_text_tokens(&serde_json::to_string(&m.content).unwrap_or_default()))
+        .sum()
+}
+
+/// A turn starts with a user message carrying text (tool results belong to the previous turn)
+fn is_turn_start(message: &Message) -> bool {
+    message.role == "user"
+        && message.content.iter().any(|b| matches!(b, ContentBlock::Text { .. }))
+        && !message.content.iter().any(|b| matches!(b, ContentBlock::ToolResult { .. }))
+}
+
+/// Index from which to keep messages so the kept part fits in `keep_tokens`.
+/// Always a turn start, and never past the last turn (the current command).
+fn split_point(messages: &[Message], keep_tokens: usize) -> usize {
+    let starts: Vec<usize> = (0..messages.len()).filter(|&i| is_turn_start(&messages[i])).collect();
+    let last = match starts.last() {
+        Some(&last) => last,
+        None => return 0,
+    };
+
+    starts
+        .iter()
+        .copied()
+        .find(|&i| estimate_tokens(&messages[i..]) <= keep_tokens)
+        .unwrap_or(last)
+}
+
+/// Summary of the compacted turns, appended to the system prompt
+pub fn summary_block(summary: &str) -> SystemBlock {
+    SystemBlock::cached(&format!("Summary of the earlier conversation:\n{}", summary))
+}
+
+/// Keep the history within `max_tokens` (summary included). Older turns are cut at a
+/// turn boundary and folded into the running `summary`; if summarizing fails they are
+/// dropped and the previous summary is kept.
+pub async fn compact_history(
+    provider: &dyn LlmProvider,
+    messages: &mut Vec<Message>,
+    summary: &mut Option<String>,
+    max_tokens: usize,
+    usage: Option<&dyn UsageRecorder>,
+) {
+    // Never start with an assistant message or orphan tool results
+    if let Some(first) = messages.iter().position(is_turn_start) {
+        messages.drain(0..first);
+    }
+
+    let summary_tokens = summary.as_deref().map(estimate_text_tokens).unwrap_or(0);
+    if max_tokens == 0 || estimate_tokens(messages) + summary_tokens <= max_tokens {
+        return;
+    }
+
+    // Leave room so compaction doesn't run again on the next command
+    let cut = split_point(messages, max_tokens / 2);
+    if cut == 0 {
+        return;
+    }
+    let older: Vec<Message> = messages.drain(0..cut).collect();
+    println!("[History] Compacting {} messages, keeping {}", older.len(), messages.len());
+
+    match summarize_turns(provider, summary.as_deref(), &older, usage).await {
+        Ok(updated) => *summary = Some(updated),
+        Err(e) => eprintln!("[History] Failed to summarize, older messages dropped: {}", e),
+    }
+}
+
+/// Ask the LLM to fold `older` messages into the existing summary
+async fn summarize_turns(
+    provider: &dyn LlmProvider,
+    summary: Option<&str>,
+    older: &[Message],
+    usage: Option<&dyn UsageRecorder>,
+) -> Result<String> {
+    let prompt = format!(
+        "You maintain a running summary of a conversation between a user and a voice assistant.\n\
+        Update the summary with the new messages below. Keep facts, names, decisions, results of \
+        actions and anything the user asked to remember or do later. Be concise, use the language \
+        of the conversation, and answer with the summary only.\n\n\
+        Current summary:\n{}\n\nNew messages:\n{}",
+        summary.unwrap_or("(none)"),
+        render_transcript(older)
+    );
+
+    let summary = complete_with_usage(provider, &prompt, 1024, usage).await?;
+    Ok(summary.trim().to_string())
+}
+
+fn render_transcript(messages: &[Message]) -> String {
+    let mut lines = Vec::new();
+    for message in messages {
+        for block in &message.content {
+            let line = match block {
+                ContentBlock::Text { text } => format!("{}: {}", message.role, text),
+                ContentBlock::ToolUse { name, input, .. } => {
+                    format!("assistant called {}({})", name, truncate(&input.to_string()))
+                }
+                ContentBlock::ToolResult { content, .. } => format!("tool result: {}", truncate(content)),
+            };
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_BLOCK_CHARS_IN_SUMMARY {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_BLOCK_CHARS_IN_SUMMARY).collect();
+    format!("{}...", cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::anthropic::AnthropicProvider;
+    use crate::llm::mock_server::{http_response, MockServer};
+    use crate::llm::provider::LlmConfig;
+    use crate::llm::types::user_message;
+
+    fn assistant_text(text: &str) -> Message {
+        Message {
+            role: "assistant".to_string(),
+            content: vec![ContentBlock::Text { text: text.to_string() }],
+        }
+    }
+
+    /// Turn with a tool call: user, assistant tool_use, user tool_result, assistant text
+    fn tool_turn(i: usize) -> Vec<Message> {
+        let id = format!("toolu_{}", i);
+        vec![
+            user_message(&format!("command {} {}", i, "x".repeat(200))),
+            Message {
+                role: "assistant".to_string(),
+                content: vec![ContentBlock::ToolUse {
+                    id: id.clone(),
+                    name: "list_recordings".to_string(),
+                    input: serde_json::json!({}),
+                }],
+            },
+            Message {
+                role: "user".to_string(),
+                content: vec![ContentBlock::ToolResult { tool_use_id: id, content: "y".repeat(200) }],
+            },
+            assistant_text("done"),
+        ]
+    }
+
+    #[test]
+    fn test_split_point_keeps_tool_pairs() {
+        let mut messages: Vec<Message> = (0..5).flat_map(tool_turn).collect();
+        messages.push(user_message("current"));
+
+        for keep in [0, 50, 150, 300, 10_000] {
+            let cut = split_point(&messages, keep);
+            assert!(is_turn_start(&messages[cut]), "cut at {} for budget {}", cut, keep);
+        }
+        // Tiny budget: only the current command is kept
+        assert_eq!(split_point(&messages, 0), messages.len() - 1);
+        assert_eq!(split_point(&messages, 10_000), 0);
+    }
+
+    #[tokio::test]
+    async fn test_compact_history_folds_old_turns_into_summary() {
+        let body = r#"{"content":[{"type":"text","text":"User listed recordings five times."}],"usage":{"input_tokens":300,"output_tokens":8}}"#;
+        let server = MockServer::start(vec![http_response(200, &[], body)]).await;
+        let config = LlmConfig { base_url: Some(server.base_url.clone()), ..Default::default() };
+        let provider = AnthropicProvider::new("test-key", &config);
+
+        // Leading assistant message from an old, badly trimmed history
+        let mut messages = vec![assistant_text("stale")];
+        messages.extend((0..5).flat_map(tool_turn));
+        messages.push(user_message("current"));
+        let mut summary = None;
+
+        compact_history(&provider, &mut messages, &mut summary, 400, None).await;
+
+        assert_eq!(server.request_count(), 1);
+        assert_eq!(summary.as_deref(), Some("User listed recordings five times."));
+        assert!(is_turn_start(&messages[0]));
+        assert!(estimate_tokens(&messages) <= 200);
+        assert_eq!(messages.last().map(|m| m.role.as_str()), Some("user"));
+
+        // Within budget: nothing to do
+        let before = messages.len();
+        compact_history(&provider, &mut messages, &mut summary, 10_000, None).await;
+        assert_eq!(messages.len(), before);
+        assert_eq!(server.request_count(), 1);
+    }
+}
@@ -0,0 +1,109 @@
+//! Minimal HTTP server replaying canned responses, for testing providers
+//! against real sockets (status codes, headers, SSE streams cut short...).
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+pub struct MockServer {
+    pub base_url: String,
+    requests: Arc<AtomicUsize>,
+}
+
+impl MockServer {
+    /// Serve `responses` in order, one per connection (raw HTTP/1.1 text)
+    pub async fn start(responses: Vec<String>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                read_request(&mut socket).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    /// Number of requests received so far
+    pub fn request_count(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+/// Raw HTTP response with a body; the connection is closed afterwards
+pub fn http_response(status: u16, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {} Mock\r\nconnection: close\r\n", status);
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str(&format!("content-length: {}\r\n\r\n{}", body.len(), body));
+    response
+}
+
+/// Streaming SSE response made of (event, data) pairs, ended by closing the socket
+pub fn sse_response(events: &[(&str, &str)]) -> String {
+    let mut response =
+        "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\n".to_string();
+    for (event, data) in events {
+        response.push_str(&format!("event: {}\ndata: {}\n\n", event, data));
+    }
+    response
+}
+
+/// Complete Anthropic SSE stream answering `text`
+pub fn anthropic_text_response(text: &str) -> String {
+    let delta = serde_json::json!({
+        "type": "content_block_delta",
+        "index": 0,
+        "delta": { "type": "text_delta", "text": text },
+    })
+    .to_string();
+
+    sse_response(&[
+        ("message_start", r#"{"type":"message_start","message":{"id":"msg_1","role":"assistant","content":[],"usage":{"input_tokens":25,"cache_read_input_tokens":10,"output_tokens":1}}}"#),
+        ("content_block_start", r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#),
+        ("ping", r#"{"type":"ping"}"#),
+        ("content_block_delta", &delta),
+        ("content_block_stop", r#"{"type":"content_block_stop","index":0}"#),
+        ("message_delta", r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":12}}"#),
+        ("message_stop", r#"{"type":"message_stop"}"#),
+    ])
+}
+
+/// Consume the request headers and body so the client sees a clean exchange
+async fn read_request(socket: &mut tokio::net::TcpStream) {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+
+    loop {
+        let n = match socket.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => n,
+        };
+        data.extend_from_slice(&buf[..n]);
+
+        let text = String::from_utf8_lossy(&data);
+        if let Some(header_end) = text.find("\r\n\r\n") {
+            let content_length = text[..header_end]
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            if data.len() >= header_end + 4 + content_length {
+                return;
+            }
+        }
+    }
+}
@@ -1,3 +1,26 @@
+mod agent;
 mod anthropic;
+mod cancel;
+mod error;
+mod fixtures;
+mod history;
+#[cfg(test)]
+pub mod mock_server;
+mod openai;
+mod provider;
+mod retry;
+mod sse;
+mod summarize;
+mod types;
 
-pub use anthropic::{AgenticClient, Message, ToolDefinition, summarize, user_message};
+pub use agent::AgenticClient;
+pub use cancel::CancellationToken;
+pub use error::{error_kind, LlmError, LlmErrorKind};
+pub use history::{compact_history, summary_block};
+pub use provider::{create_provider, LlmConfig, LlmProvider};
+pub use retry::RetryPolicy;
+pub use sse::SseDecoder;
+pub use summarize::{
+    extract_structured, split_transcript, summarize_transcript, ChunkSummaryCache, SummaryConfig, TranscriptSegment,
+};
+pub use types::{system_text, user_message, ContentBlock, Message, SystemBlock, ToolDefinition};
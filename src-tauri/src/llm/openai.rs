@@ -0,0 +1,425 @@
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::error::LlmError;
+use super::fixtures::{check_recorded_status, read_json, FixtureRecorder, RecordedStream};
+use super::provider::{
+    AssistantTurn, BoxFuture, ChatRequest, Completion, Extraction, LlmConfig, LlmProvider, TextCallback, ToolUse,
+};
+use super::sse::SseDecoder;
+use super::types::{system_text, ContentBlock, Message, SystemBlock, ToolDefinition};
+use crate::usage::Usage;
+
+// Ollama's OpenAI-compatible endpoint; llama.cpp server and vLLM use /v1 as well
+const DEFAULT_BASE_URL: &str = "http://localhost:11434/v1";
+const DEFAULT_MODEL: &str = "llama3.1";
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+// ============================================================================
+// Types for chat-completions streaming
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    /// Some servers report mid-stream failures as `data: {"error": {...}}`
+    #[serde(default)]
+    error: Option<Value>,
+    /// Sent in the last chunk when `stream_options.include_usage` is set
+    #[serde(default)]
+    usage: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: Option<ChoiceDelta>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChoiceDelta {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<FunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionDelta {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<String>,
+}
+
+/// Tool call being assembled from streamed fragments
+#[derive(Default)]
+struct PendingToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+// ============================================================================
+// OpenAI-compatible provider
+// ============================================================================
+
+pub struct OpenAiProvider {
+    client: Client,
+    api_key: Option<String>,
+    base_url: String,
+    model: String,
+    max_tokens: u32,
+    recorder: Option<Arc<FixtureRecorder>>,
+}
+
+impl OpenAiProvider {
+    pub fn new(config: &LlmConfig) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: config.api_key.clone().filter(|k| !k.trim().is_empty()),
+            base_url: config
+                .base_url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            model: config.model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            max_tokens: config.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            recorder: None,
+        }
+    }
+
+    /// Save every exchange to a fixture file
+    pub fn with_recorder(mut self, recorder: Arc<FixtureRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    fn post(&self, body: &Value) -> reqwest::RequestBuilder {
+        let mut request = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("content-type", "application/json")
+            .json(body);
+
+        // Local servers usually don't need a key
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key.trim());
+        }
+
+        request
+    }
+
+    async fn stream_turn_impl(&self, request: ChatRequest<'_>, on_text: TextCallback<'_>) -> Result<AssistantTurn> {
+        let mut body = json!({
+            "model": self.model,
+            "max_tokens": self.max_tokens,
+            "messages": to_openai_messages(request.system, request.messages),
+            "stream": true,
+            "stream_options": { "include_usage": true },
+        });
+        if !request.tools.is_empty() {
+            body["tools"] = to_openai_tools(request.tools);
+        }
+
+        let response = self.post(&body).send().await.map_err(LlmError::network)?;
+        let response = check_recorded_status(response, self.recorder.as_ref(), &body).await?;
+        let status = response.status().as_u16();
+
+        let mut turn = AssistantTurn::default();
+        // Tool calls are streamed as fragments keyed by index
+        let mut pending: BTreeMap<usize, PendingToolCall> = BTreeMap::new();
+        let mut finish_reason = String::new();
+        let mut done = false;
+
+        let mut stream = RecordedStream::new(response.bytes_stream(), status, self.recorder.as_ref(), &body);
+        let mut decoder = SseDecoder::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| LlmError::interrupted(e.to_string()))?;
+
+            for sse in decoder.feed(&chunk) {
+                let data = sse.data.trim();
+                if data == "[DONE]" {
+                    done = true;
+                    continue;
+                }
+
+                let chunk: StreamChunk = match serde_json::from_str(data) {
+                    Ok(c) => c,
+                    Err(_) => continue,
+                };
+                if chunk.error.is_some() {
+                    return Err(LlmError::from_stream_event(data).into());
+                }
+                if let Some(usage) = &chunk.usage {
+                    turn.usage = to_usage(usage);
+                }
+
+                for choice in chunk.choices {
+                    if let Some(delta) = choice.delta {
+                        if let Some(text) = delta.content.filter(|t| !t.is_empty()) {
+                            on_text(&text);
+                            turn.text.push_str(&text);
+                        }
+
+                        for call in delta.tool_calls.unwrap_or_default() {
+                            let entry = pending.entry(call.index).or_default();
+                            if let Some(id) = call.id {
+                                entry.id = id;
+                            }
+                            if let Some(function) = call.function {
+                                if let Some(name) = function.name {
+                                    if entry.name.is_empty() {
+                                        println!("[Tool Start] {}", name);
+                                    }
+                                    entry.name.push_str(&name);
+                                }
+                                if let Some(arguments) = function.arguments {
+                                    entry.arguments.push_str(&arguments);
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some(reason) = choice.finish_reason {
+                        finish_reason = reason;
+                    }
+                }
+            }
+        }
+
+        // Servers send a finish_reason and/or [DONE]; neither means the stream was cut
+        if !done && finish_reason.is_empty() {
+            return Err(LlmError::interrupted("stream ended before finish_reason").into());
+        }
+
+        for (index, call) in pending {
+            let input: Value = serde_json::from_str(&call.arguments)
+                .unwrap_or(Value::Object(serde_json::Map::new()));
+            turn.tool_uses.push(ToolUse {
+                // Some servers omit ids; results are matched back by id
+                id: if call.id.is_empty() { format!("call_{}", index) } else { call.id },
+                name: call.name,
+                input,
+            });
+        }
+
+        turn.stop_reason = match finish_reason.as_str() {
+            "tool_calls" | "function_call" => "tool_use",
+            "length" => "max_tokens",
+            _ if !turn.tool_uses.is_empty() => "tool_use",
+            _ => "end_turn",
+        }
+        .to_string();
+
+        Ok(turn)
+    }
+
+    async fn complete_impl(&self, prompt: &str, max_tokens: u32) -> Result<Completion> {
+        let body = json!({
+            "model": self.model,
+            "max_tokens": max_tokens,
+            "messages": [{ "role": "user", "content": prompt }],
+            "stream": false,
+        });
+
+        let response = self.post(&body).send().await.map_err(LlmError::network)?;
+        let response = check_recorded_status(response, self.recorder.as_ref(), &body).await?;
+
+        let response: Value = read_json(response, self.recorder.as_ref(), &body).await?;
+        let text = response["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow!("No text response from LLM"))?;
+
+        Ok(Completion { text, usage: to_usage(&response["usage"]) })
+    }
+
+    async fn extract_impl(&self, prompt: &str, tool: &ToolDefinition, max_tokens: u32) -> Result<Extraction> {
+        let body = json!({
+            "model": self.model,
+            "max_tokens": max_tokens,
+            "messages": [{ "role": "user", "content": prompt }],
+            "tools": to_openai_tools(std::slice::from_ref(tool)),
+            "tool_choice": { "type": "function", "function": { "name": tool.name } },
+            "stream": false,
+        });
+
+        let response = self.post(&body).send().await.map_err(LlmError::network)?;
+        let response = check_recorded_status(response, self.recorder.as_ref(), &body).await?;
+
+        let response: Value = read_json(response, self.recorder.as_ref(), &body).await?;
+        let message = &response["choices"][0]["message"];
+
+        // Servers without tool_choice support may answer with the JSON as plain content
+        let arguments = message["tool_calls"][0]["function"]["arguments"]
+            .as_str()
+            .or_else(|| message["content"].as_str())
+            .ok_or_else(|| anyhow!("No {} call in the LLM response", tool.name))?;
+        let input: Value = serde_json::from_str(arguments)
+            .map_err(|e| anyhow!("Invalid {} arguments from LLM: {}", tool.name, e))?;
+
+        Ok(Extraction { input, usage: to_usage(&response["usage"]) })
+    }
+}
+
+impl LlmProvider for OpenAiProvider {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn stream_turn<'a>(
+        &'a self,
+        request: ChatRequest<'a>,
+        on_text: TextCallback<'a>,
+    ) -> BoxFuture<'a, Result<AssistantTurn>> {
+        Box::pin(self.stream_turn_impl(request, on_text))
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str, max_tokens: u32) -> BoxFuture<'a, Result<Completion>> {
+        Box::pin(self.complete_impl(prompt, max_tokens))
+    }
+
+    fn extract<'a>(
+        &'a self,
+        prompt: &'a str,
+        tool: &'a ToolDefinition,
+        max_tokens: u32,
+    ) -> BoxFuture<'a, Result<Extraction>> {
+        Box::pin(self.extract_impl(prompt, tool, max_tokens))
+    }
+}
+
+// ============================================================================
+// Format conversion
+// ============================================================================
+
+fn to_openai_tools(tools: &[ToolDefinition]) -> Value {
+    Value::Array(
+        tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.input_schema,
+                    }
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Chat-completions usage (prompt tokens include cached ones)
+fn to_usage(usage: &Value) -> Usage {
+    let prompt = usage["prompt_tokens"].as_u64().unwrap_or(0);
+    let cached = usage["prompt_tokens_details"]["cached_tokens"].as_u64().unwrap_or(0);
+    Usage {
+        input_tokens: prompt.saturating_sub(cached),
+        output_tokens: usage["completion_tokens"].as_u64().unwrap_or(0),
+        cache_creation_input_tokens: 0,
+        cache_read_input_tokens: cached,
+    }
+}
+
+/// Convert Anthropic-style history to chat-completions messages
+fn to_openai_messages(system: &[SystemBlock], messages: &[Message]) -> Vec<Value> {
+    let mut result = vec![json!({ "role": "system", "content": system_text(system) })];
+
+    for message in messages {
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        let mut tool_results = Vec::new();
+
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text: t } => text.push_str(t),
+                ContentBlock::ToolUse { id, name, input } => tool_calls.push(json!({
+                    "id": id,
+                    "type": "function",
+                    "function": { "name": name, "arguments": input.to_string() },
+                })),
+                ContentBlock::ToolResult { tool_use_id, content } => tool_results.push(json!({
+                    "role": "tool",
+                    "tool_call_id": tool_use_id,
+                    "content": content,
+                })),
+            }
+        }
+
+        if message.role == "assistant" {
+            let mut assistant = json!({
+                "role": "assistant",
+                "content": if text.is_empty() { Value::Null } else { Value::String(text) },
+            });
+            if !tool_calls.is_empty() {
+                assistant["tool_calls"] = Value::Array(tool_calls);
+            }
+            result.push(assistant);
+        } else {
+            // Tool results must directly follow the assistant message that called them
+            result.extend(tool_results);
+            if !text.is_empty() {
+                result.push(json!({ "role": message.role, "content": text }));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converts_tool_round_trip() {
+        let messages = vec![
+            Message {
+                role: "assistant".to_string(),
+                content: vec![ContentBlock::ToolUse {
+                    id: "call_1".to_string(),
+                    name: "list_recordings".to_string(),
+                    input: json!({}),
+                }],
+            },
+            Message {
+                role: "user".to_string(),
+                content: vec![ContentBlock::ToolResult {
+                    tool_use_id: "call_1".to_string(),
+                    content: "No recordings found.".to_string(),
+                }],
+            },
+        ];
+
+        let converted = to_openai_messages(&[SystemBlock::cached("system prompt")], &messages);
+        assert_eq!(converted.len(), 3);
+        assert_eq!(converted[0]["role"], "system");
+        assert_eq!(converted[1]["role"], "assistant");
+        assert_eq!(converted[1]["content"], Value::Null);
+        assert_eq!(converted[1]["tool_calls"][0]["function"]["name"], "list_recordings");
+        assert_eq!(converted[2]["role"], "tool");
+        assert_eq!(converted[2]["tool_call_id"], "call_1");
+    }
+}
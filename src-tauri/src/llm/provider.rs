@@ -0,0 +1,161 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::anthropic::AnthropicProvider;
+use super::fixtures::{FixtureConfig, FixtureMode, FixtureRecorder, FixtureServer};
+use super::openai::OpenAiProvider;
+use super::types::{Message, SystemBlock, ToolDefinition};
+use crate::state::Settings;
+use crate::usage::Usage;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Callback receiving streamed text chunks
+pub type TextCallback<'a> = &'a (dyn Fn(&str) + Send + Sync);
+
+/// Which backend serves chat requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmProviderKind {
+    #[default]
+    Anthropic,
+    /// Any OpenAI-compatible chat-completions server (Ollama, llama.cpp server, vLLM...)
+    OpenaiCompatible,
+}
+
+/// LLM backend configuration (model, endpoint and limits)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LlmConfig {
+    #[serde(default)]
+    pub provider: LlmProviderKind,
+    /// Model name (provider default if not set)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// API base URL (provider default if not set)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Max output tokens per call (provider default if not set)
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// API key for OpenAI-compatible servers (Anthropic uses `anthropic_api_key`)
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Retries on rate limit / overload / network errors (default 3)
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Cache tools, system prompt and history prefix between calls (Anthropic, default true)
+    #[serde(default)]
+    pub prompt_caching: Option<bool>,
+    /// Record API exchanges to a fixture file, or replay one instead of calling the API
+    #[serde(default)]
+    pub fixtures: Option<FixtureConfig>,
+}
+
+/// One request to the model: system prompt, conversation and available tools
+pub struct ChatRequest<'a> {
+    pub system: &'a [SystemBlock],
+    pub messages: &'a [Message],
+    pub tools: &'a [ToolDefinition],
+}
+
+/// A tool call requested by the model
+#[derive(Debug, Clone)]
+pub struct ToolUse {
+    pub id: String,
+    pub name: String,
+    pub input: serde_json::Value,
+}
+
+/// Result of one streamed model turn
+#[derive(Debug, Default)]
+pub struct AssistantTurn {
+    pub text: String,
+    pub tool_uses: Vec<ToolUse>,
+    /// Normalized to Anthropic values: "end_turn", "tool_use", "max_tokens"
+    pub stop_reason: String,
+    pub usage: Usage,
+}
+
+/// Result of a non-streaming completion
+#[derive(Debug, Default)]
+pub struct Completion {
+    pub text: String,
+    pub usage: Usage,
+}
+
+/// Result of a forced tool call: the tool input is the structured output
+#[derive(Debug, Default)]
+pub struct Extraction {
+    pub input: serde_json::Value,
+    pub usage: Usage,
+}
+
+/// A chat backend supporting streaming and tool use
+pub trait LlmProvider: Send + Sync {
+    /// Model used for requests
+    fn model(&self) -> &str;
+
+    /// Stream one assistant turn, calling `on_text` for each text chunk
+    fn stream_turn<'a>(
+        &'a self,
+        request: ChatRequest<'a>,
+        on_text: TextCallback<'a>,
+    ) -> BoxFuture<'a, Result<AssistantTurn>>;
+
+    /// Simple non-streaming completion of a single user prompt
+    fn complete<'a>(&'a self, prompt: &'a str, max_tokens: u32) -> BoxFuture<'a, Result<Completion>>;
+
+    /// Non-streaming call forcing the model to answer by calling `tool` (JSON-schema output)
+    fn extract<'a>(
+        &'a self,
+        prompt: &'a str,
+        tool: &'a ToolDefinition,
+        max_tokens: u32,
+    ) -> BoxFuture<'a, Result<Extraction>>;
+}
+
+/// Build the provider selected in settings
+pub fn create_provider(settings: &Settings) -> Result<Arc<dyn LlmProvider>> {
+    let mut config = settings.llm.clone();
+    let mut recorder = None;
+
+    match &config.fixtures {
+        Some(FixtureConfig { mode: FixtureMode::Record, path, overwrite }) => {
+            println!("[LLM] Recording exchanges to {}", path.display());
+            recorder = Some(FixtureRecorder::shared(path, *overwrite)?);
+        }
+        Some(FixtureConfig { mode: FixtureMode::Replay, path, .. }) => {
+            println!("[LLM] Replaying exchanges from {}", path.display());
+            config.base_url = Some(FixtureServer::shared(path)?);
+        }
+        None => {}
+    }
+
+    match config.provider {
+        LlmProviderKind::Anthropic => {
+            let replaying = matches!(&config.fixtures, Some(f) if matches!(f.mode, FixtureMode::Replay));
+            let api_key = settings
+                .anthropic_api_key
+                .as_deref()
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .or(replaying.then_some("replay"))
+                .ok_or_else(|| anyhow!("Please configure your Anthropic API key in settings"))?;
+            let mut provider = AnthropicProvider::new(api_key, &config);
+            if let Some(recorder) = recorder {
+                provider = provider.with_recorder(recorder);
+            }
+            Ok(Arc::new(provider))
+        }
+        LlmProviderKind::OpenaiCompatible => {
+            let mut provider = OpenAiProvider::new(&config);
+            if let Some(recorder) = recorder {
+                provider = provider.with_recorder(recorder);
+            }
+            Ok(Arc::new(provider))
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use super::error::LlmError;
+
+/// Exponential backoff with jitter for transient API errors
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(20),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (0-based).
+    /// A server `retry-after` is honored as is (capped at `max_delay`);
+    /// otherwise base * 2^attempt with jitter in [50%, 100%].
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+
+        let exponential = self.base_delay.saturating_mul(1u32 << attempt.min(16));
+        let capped = exponential.min(self.max_delay);
+        capped.mul_f64(0.5 + 0.5 * random_unit())
+    }
+}
+
+/// Run `op` until it succeeds, fails with a non-retryable error, or retries
+/// are exhausted. `can_retry` can veto a retry (e.g. once output was streamed).
+pub async fn with_retry<T, F, Fut>(
+    policy: &RetryPolicy,
+    mut op: F,
+    can_retry: impl Fn() -> bool,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        let err = match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        let retry_after = match err.downcast_ref::<LlmError>() {
+            Some(llm_err) if llm_err.kind.is_retryable() => llm_err.retry_after,
+            _ => return Err(err),
+        };
+        if attempt >= policy.max_retries || !can_retry() {
+            return Err(err);
+        }
+
+        let delay = policy.delay(attempt, retry_after);
+        println!(
+            "[LLM] {} - retry {}/{} in {}ms",
+            err,
+            attempt + 1,
+            policy.max_retries,
+            delay.as_millis()
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Random value in [0, 1) without pulling in a rand dependency
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_backoff_and_retry_after() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+
+        for attempt in 0..3 {
+            let full = Duration::from_millis(100 << attempt);
+            let delay = policy.delay(attempt, None);
+            assert!(delay >= full / 2 && delay <= full, "attempt {}: {:?}", attempt, delay);
+        }
+        assert!(policy.delay(10, None) <= Duration::from_secs(1));
+
+        assert_eq!(policy.delay(0, Some(Duration::from_millis(700))), Duration::from_millis(700));
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(30))), Duration::from_secs(1));
+    }
+}
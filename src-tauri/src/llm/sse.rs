@@ -0,0 +1,219 @@
+//! Server-sent events decoder, following the WHATWG event stream format: works on
+//! raw bytes (UTF-8 characters may be split across network chunks), accepts CRLF,
+//! LF and CR line endings, joins multi-line `data:` fields, and tracks `id:` and
+//! `retry:`.
+
+use std::time::Duration;
+
+/// One dispatched event
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    /// `event:` field, "message" if absent
+    pub event: String,
+    /// `data:` lines joined with "\n"
+    pub data: String,
+    /// Last event id seen so far in the stream (empty if none)
+    pub id: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    /// Bytes of the current, incomplete line
+    line: Vec<u8>,
+    /// The previous chunk ended with CR: a LF at the start of the next one belongs to it
+    after_cr: bool,
+    /// First line seen (a leading byte order mark is skipped)
+    started: bool,
+    event_type: String,
+    data: String,
+    last_event_id: String,
+    retry: Option<Duration>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode a chunk of the stream, returning the events it completes
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        let mut events = Vec::new();
+        for &byte in chunk {
+            if self.after_cr {
+                self.after_cr = false;
+                if byte == b'\n' {
+                    continue;
+                }
+            }
+            match byte {
+                b'\r' | b'\n' => {
+                    self.after_cr = byte == b'\r';
+                    let line = std::mem::take(&mut self.line);
+                    if let Some(event) = self.process_line(&line) {
+                        events.push(event);
+                    }
+                }
+                _ => self.line.push(byte),
+            }
+        }
+        events
+    }
+
+    /// Reconnection delay requested by the server with `retry:`
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    pub fn last_event_id(&self) -> &str {
+        &self.last_event_id
+    }
+
+    fn process_line(&mut self, line: &[u8]) -> Option<SseEvent> {
+        let mut line = line;
+        if !self.started {
+            self.started = true;
+            line = line.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(line);
+        }
+
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line[0] == b':' {
+            return None; // comment (often a keep-alive)
+        }
+
+        let line = String::from_utf8_lossy(line);
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line.as_ref(), ""),
+        };
+
+        match field {
+            "event" => self.event_type = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = value.to_string(),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(ms) = value.parse() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Blank line: emit the buffered event, if it has data
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event_type = std::mem::take(&mut self.event_type);
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let mut data = std::mem::take(&mut self.data);
+        data.pop(); // trailing "\n"
+        Some(SseEvent {
+            event: if event_type.is_empty() { "message".to_string() } else { event_type },
+            data,
+            id: self.last_event_id.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(chunks: &[&[u8]]) -> Vec<SseEvent> {
+        let mut decoder = SseDecoder::new();
+        chunks.iter().flat_map(|chunk| decoder.feed(chunk)).collect()
+    }
+
+    fn event(event: &str, data: &str, id: &str) -> SseEvent {
+        SseEvent { event: event.to_string(), data: data.to_string(), id: id.to_string() }
+    }
+
+    #[test]
+    fn test_fields() {
+        let events = decode(&[b"\xEF\xBB\xBF: keep-alive\nevent: ping\ndata\n\ndata:first\ndata: second\nid: 7\nretry: 2500\n\n"]);
+        assert_eq!(events, vec![event("ping", "", ""), event("message", "first\nsecond", "7")]);
+
+        let mut decoder = SseDecoder::new();
+        decoder.feed(b"retry: 1000\nretry: soon\nid: a\0b\nid\nevent: x\n\n");
+        assert_eq!(decoder.retry(), Some(Duration::from_millis(1000)));
+        // "id" without value resets the id; an event without data is not dispatched
+        assert_eq!(decoder.last_event_id(), "");
+        assert_eq!(decoder.feed(b"data: y\n\n"), vec![event("message", "y", "")]);
+
+        // An unterminated event is dropped
+        assert!(decode(&[b"data: partial"]).is_empty());
+    }
+
+    #[test]
+    fn test_line_endings_and_split_characters() {
+        let events = decode(&[b"data: caf\xC3", b"\xA9\r", b"\n\r", b"\ndata: \xE2\x82", b"\xAC\r\r"]);
+        assert_eq!(events, vec![event("message", "café", ""), event("message", "€", "")]);
+    }
+
+    /// Deterministic xorshift, enough to shuffle line endings and chunk boundaries
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self, max: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % max as u64) as usize
+        }
+    }
+
+    #[test]
+    fn test_random_framing_and_chunking() {
+        let expected = vec![
+            event("message_start", "{\"text\":\"Réunion d'équipe\"}", ""),
+            event("content_block_delta", "{\"text\":\"Ça marche — 👍\"}", "1"),
+            event("message", "ligne 1\nligne 2\n\nligne 4", "2"),
+            event("error", "", "2"),
+        ];
+        let mut rng = Rng(0x2545F4914F6CDD1D);
+
+        for _ in 0..500 {
+            let mut stream = String::new();
+            for (i, e) in expected.iter().enumerate() {
+                let eol = ["\n", "\r\n", "\r"][rng.next(3)];
+                if rng.next(2) == 0 {
+                    stream.push_str(&format!(":comment{}", eol));
+                }
+                if e.event != "message" {
+                    stream.push_str(&format!("event:{}{}{}", [" ", ""][rng.next(2)], e.event, eol));
+                }
+                for line in e.data.split('\n') {
+                    stream.push_str(&format!("data: {}{}", line, eol));
+                }
+                if i == 1 {
+                    stream.push_str(&format!("id: 1{}", eol));
+                }
+                if i == 2 {
+                    stream.push_str(&format!("id: 2{}retry: 3000{}", eol, eol));
+                }
+                stream.push_str(eol);
+            }
+
+            // Cut anywhere, including inside characters and between CR and LF
+            let bytes = stream.as_bytes();
+            let mut decoder = SseDecoder::new();
+            let mut events = Vec::new();
+            let mut start = 0;
+            while start < bytes.len() {
+                let end = (start + 1 + rng.next(12)).min(bytes.len());
+                events.extend(decoder.feed(&bytes[start..end]));
+                start = end;
+            }
+
+            assert_eq!(events, expected, "stream: {:?}", stream);
+            assert_eq!(decoder.retry(), Some(Duration::from_millis(3000)));
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use super::history::estimate_text_tokens;
+use super::provider::LlmProvider;
+use super::types::ToolDefinition;
+use super::retry::{with_retry, RetryPolicy};
+use crate::usage::UsageRecorder;
+
+/// Bump when the chunk prompt changes, so cached chunk summaries are recomputed
+const CHUNK_PROMPT_VERSION: u32 = 1;
+
+// ============================================================================
+// Map-reduce summarization of long transcriptions
+// ============================================================================
+
+/// How long transcriptions are split before being summarized
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryConfig {
+    /// Estimated tokens of transcription per chunk
+    #[serde(default = "default_chunk_tokens")]
+    pub chunk_tokens: usize,
+    /// Chunks never span more than this many minutes of recording
+    #[serde(default = "default_chunk_minutes")]
+    pub chunk_minutes: u32,
+    /// Chunk summaries requested in parallel
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+}
+
+fn default_chunk_tokens() -> usize {
+    6000
+}
+
+fn default_chunk_minutes() -> u32 {
+    15
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            chunk_tokens: default_chunk_tokens(),
+            chunk_minutes: default_chunk_minutes(),
+            concurrency: default_concurrency(),
+        }
+    }
+}
+
+/// One transcribed segment of a recording
+pub struct TranscriptSegment<'a> {
+    pub timestamp: DateTime<Utc>,
+    pub text: &'a str,
+}
+
+/// Stores chunk summaries by content key, so unchanged chunks are not summarized again
+pub trait ChunkSummaryCache: Send + Sync {
+    fn get(&self, key: &str) -> Option<String>;
+
+    fn put(&self, key: &str, summary: &str);
+
+    /// Called with the keys of the current chunks once summarization is done
+    fn retain(&self, _keys: &[String]) {}
+}
+
+/// Check the budget, run a simple completion with retries and record its usage
+pub(super) async fn complete_with_usage(
+    provider: &dyn LlmProvider,
+    prompt: &str,
+    max_tokens: u32,
+    usage: Option<&dyn UsageRecorder>,
+) -> Result<String> {
+    if let Some(usage) = usage {
+        usage.check_budget()?;
+    }
+
+    let completion = with_retry(&RetryPolicy::default(), || provider.complete(prompt, max_tokens), || true).await?;
+    if let Some(usage) = usage {
+        usage.record(provider.model(), &completion.usage);
+    }
+
+    Ok(completion.text)
+}
+
+/// Structured output: force a call to `tool` and return its JSON input (budget, retries and usage as above)
+pub async fn extract_structured(
+    provider: &dyn LlmProvider,
+    prompt: &str,
+    tool: &ToolDefinition,
+    max_tokens: u32,
+    usage: Option<&dyn UsageRecorder>,
+) -> Result<serde_json::Value> {
+    if let Some(usage) = usage {
+        usage.check_budget()?;
+    }
+
+    let extraction = with_retry(&RetryPolicy::default(), || provider.extract(prompt, tool, max_tokens), || true).await?;
+    if let Some(usage) = usage {
+        usage.record(provider.model(), &extraction.usage);
+    }
+
+    Ok(extraction.input)
+}
+
+/// Summarize text with the configured LLM (non-streaming, simple completion)
+pub async fn summarize(provider: &dyn LlmProvider, text: &str, usage: Option<&dyn UsageRecorder>) -> Result<String> {
+    let prompt = format!(
+        "Summarize the following audio transcription. Adapt your summary to the content type:\n\
+        - For formal meetings: key points, decisions, action items\n\
+        - For informal conversations: main topics discussed, people mentioned, any plans or intentions\n\
+        - For any content: always provide a useful summary, never refuse\n\n\
+        Keep it concise. Respond in the same language as the transcription.\n\n{}",
+        text
+    );
+    complete_with_usage(provider, &prompt, 2048, usage).await
+}
+
+/// Summarize a transcription of any length: chunks are summarized in parallel (map),
+/// then their summaries are merged (reduce). Short transcriptions take a single call.
+pub async fn summarize_transcript(
+    provider: &dyn LlmProvider,
+    segments: &[TranscriptSegment<'_>],
+    config: &SummaryConfig,
+    cache: Option<&dyn ChunkSummaryCache>,
+    usage: Option<&dyn UsageRecorder>,
+) -> Result<String> {
+    let chunks = split_transcript(segments, config);
+    if chunks.len() <= 1 {
+        let text = chunks.into_iter().next().unwrap_or_default();
+        return summarize(provider, &text, usage).await;
+    }
+
+    let keys: Vec<String> = chunks.iter().map(|c| chunk_key(provider.model(), c)).collect();
+    let cached = keys.iter().filter(|k| cache.and_then(|c| c.get(k)).is_some()).count();
+    println!("[Summary] {} chunks ({} cached)", chunks.len(), cached);
+
+    // Map: summaries come back in chunk order whatever order they finish in
+    let limit = Semaphore::new(config.concurrency.max(1));
+    let mut summaries = try_join_all(
+        chunks.iter().zip(&keys).map(|(chunk, key)| summarize_chunk(provider, chunk, key, cache, usage, &limit)),
+    )
+    .await?;
+
+    if let Some(cache) = cache {
+        cache.retain(&keys);
+    }
+
+    // Reduce: merge summaries in groups until they fit in one prompt
+    while summaries.len() > 1 && estimate_text_tokens(&summaries.join("\n\n")) > config.chunk_tokens {
+        let groups = group_by_tokens(&summaries, config.chunk_tokens);
+        if groups.len() == summaries.len() {
+            // Each summary alone fills a chunk, grouping can't shrink the list
+            break;
+        }
+        summaries = try_join_all(groups.into_iter().map(|group| merge_group(provider, group, usage, &limit))).await?;
+    }
+
+    merge_summaries(provider, summaries, usage).await
+}
+
+/// Split segments into chunks of at most `chunk_tokens`, never crossing a
+/// `chunk_minutes` window (counted from the first segment). Windows keep chunk
+/// boundaries stable when a segment is edited, so the other chunks stay cached.
+pub fn split_transcript(segments: &[TranscriptSegment<'_>], config: &SummaryConfig) -> Vec<String> {
+    let start = match segments.first() {
+        Some(first) => first.timestamp,
+        None => return Vec::new(),
+    };
+    let window_secs = i64::from(config.chunk_minutes.max(1)) * 60;
+
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_window = 0;
+
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let window = (segment.timestamp - start).num_seconds().max(0) / window_secs;
+        let too_long = estimate_text_tokens(&current) + estimate_text_tokens(text) > config.chunk_tokens;
+
+        if !current.is_empty() && (window != current_window || too_long) {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(text);
+        current_window = window;
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Consecutive summaries grouped so each group fits in `max_tokens`
+fn group_by_tokens(summaries: &[String], max_tokens: usize) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut tokens = 0;
+    for summary in summaries {
+        let summary_tokens = estimate_text_tokens(summary);
+        match groups.last_mut() {
+            Some(group) if tokens + summary_tokens <= max_tokens => {
+                group.push(summary.clone());
+                tokens += summary_tokens;
+            }
+            _ => {
+                groups.push(vec![summary.clone()]);
+                tokens = summary_tokens;
+            }
+        }
+    }
+    groups
+}
+
+/// Stable content key (FNV-1a): the same text with the same model and prompt hits the cache
+fn chunk_key(model: &str, text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in format!("v{}\0{}\0{}", CHUNK_PROMPT_VERSION, model, text).bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}-{}", hash, text.len())
+}
+
+async fn summarize_chunk(
+    provider: &dyn LlmProvider,
+    chunk: &str,
+    key: &str,
+    cache: Option<&dyn ChunkSummaryCache>,
+    usage: Option<&dyn UsageRecorder>,
+    limit: &Semaphore,
+) -> Result<String> {
+    if let Some(summary) = cache.and_then(|c| c.get(key)) {
+        return Ok(summary);
+    }
+    let _permit = limit.acquire().await?;
+
+    let prompt = format!(
+        "The following is one part of a longer audio transcription. Summarize this part: \
+        topics, key points, decisions, action items, people and figures mentioned. Keep details \
+        that a summary of the whole recording may need. Respond in the same language as the \
+        transcription, with the summary only.\n\n{}",
+        chunk
+    );
+    let summary = complete_with_usage(provider, &prompt, 1024, usage).await?;
+
+    if let Some(cache) = cache {
+        cache.put(key, &summary);
+    }
+    Ok(summary)
+}
+
+/// Intermediate reduce step, sharing the concurrency limit of the map step
+async fn merge_group(
+    provider: &dyn LlmProvider,
+    summaries: Vec<String>,
+    usage: Option<&dyn UsageRecorder>,
+    limit: &Semaphore,
+) -> Result<String> {
+    let _permit = limit.acquire().await?;
+    merge_summaries(provider, summaries, usage).await
+}
+
+/// Merge consecutive part summaries into one summary
+async fn merge_summaries(
+    provider: &dyn LlmProvider,
+    summaries: Vec<String>,
+    usage: Option<&dyn UsageRecorder>,
+) -> Result<String> {
+    let parts = summaries
+        .iter()
+        .enumerate()
+        .map(|(i, s)| format!("Part {}:\n{}", i + 1, s))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let prompt = format!(
+        "The following are summaries of consecutive parts of one audio transcription. Merge them \
+        into a single summary of the whole. Adapt your summary to the content type:\n\
+        - For formal meetings: key points, decisions, action items\n\
+        - For informal conversations: main topics discussed, people mentioned, any plans or intentions\n\
+        - For any content: always provide a useful summary, never refuse\n\n\
+        Keep it concise and don't mention the parts. Respond in the same language as the summaries.\n\n{}",
+        parts
+    );
+    complete_with_usage(provider, &prompt, 2048, usage).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::anthropic::AnthropicProvider;
+    use crate::llm::mock_server::{http_response, MockServer};
+    use crate::llm::provider::LlmConfig;
+    use chrono::Duration;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryCache(Mutex<HashMap<String, String>>);
+
+    impl ChunkSummaryCache for MemoryCache {
+        fn get(&self, key: &str) -> Option<String> {
+            self.0.lock().unwrap().get(key).cloned()
+        }
+
+        fn put(&self, key: &str, summary: &str) {
+            self.0.lock().unwrap().insert(key.to_string(), summary.to_string());
+        }
+
+        fn retain(&self, keys: &[String]) {
+            self.0.lock().unwrap().retain(|k, _| keys.contains(k));
+        }
+    }
+
+    fn completion(text: &str) -> String {
+        let body = serde_json::json!({
+            "content": [{ "type": "text", "text": text }],
+            "usage": { "input_tokens": 100, "output_tokens": 10 },
+        });
+        http_response(200, &[], &body.to_string())
+    }
+
+    fn segments<'a>(texts: &[&'a str], minutes_apart: i64) -> Vec<TranscriptSegment<'a>> {
+        let start = Utc::now();
+        texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| TranscriptSegment { timestamp: start + Duration::minutes(i as i64 * minutes_apart), text })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_chunks_by_time_and_tokens() {
+        let config = SummaryConfig { chunk_tokens: 10, chunk_minutes: 15, concurrency: 1 };
+
+        // 0, 10 min in the first window, 20 min in the second
+        let chunks = split_transcript(&segments(&["a", "b", "c"], 10), &config);
+        assert_eq!(chunks, vec!["a b", "c"]);
+
+        // Same window, split on the token budget (a 40-character segment is ~11 tokens)
+        let long = "x".repeat(40);
+        let chunks = split_transcript(&segments(&["a", &long, "b"], 0), &config);
+        assert_eq!(chunks.len(), 3);
+        assert!(split_transcript(&[], &config).is_empty());
+    }
+
+    #[test]
+    fn test_group_by_tokens() {
+        let summaries: Vec<String> = ["a".repeat(20), "b".repeat(20), "c".repeat(20)].to_vec();
+        let groups = group_by_tokens(&summaries, 12);
+        assert_eq!(groups.iter().map(|g| g.len()).collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_map_reduce_reuses_cached_chunks() {
+        let config = SummaryConfig { chunk_tokens: 1000, chunk_minutes: 15, concurrency: 2 };
+        let cache = MemoryCache::default();
+
+        // Three 15-minute windows: three chunk summaries and one merge
+        let server = MockServer::start(vec![
+            completion("part"),
+            completion("part"),
+            completion("part"),
+            completion("whole"),
+        ])
+        .await;
+        let provider = AnthropicProvider::new("test-key", &LlmConfig {
+            base_url: Some(server.base_url.clone()),
+            ..Default::default()
+        });
+        let summary = summarize_transcript(&provider, &segments(&["one", "two", "three"], 15), &config, Some(&cache), None)
+            .await
+            .unwrap();
+        assert_eq!(summary, "whole");
+        assert_eq!(server.request_count(), 4);
+
+        // One segment edited: only its chunk is summarized again, stale entry evicted
+        let server = MockServer::start(vec![completion("edited part"), completion("whole again")]).await;
+        let provider = AnthropicProvider::new("test-key", &LlmConfig {
+            base_url: Some(server.base_url.clone()),
+            ..Default::default()
+        });
+        let summary = summarize_transcript(&provider, &segments(&["one", "2", "three"], 15), &config, Some(&cache), None)
+            .await
+            .unwrap();
+        assert_eq!(summary, "whole again");
+        assert_eq!(server.request_count(), 2);
+        assert_eq!(cache.0.lock().unwrap().len(), 3);
+    }
+}
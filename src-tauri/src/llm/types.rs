@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Conversation types (Anthropic message format, used as the common format
+// for every provider and for conversation history)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: String,
+    pub content: Vec<ContentBlock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ContentBlock {
+    #[serde(rename = "text")]
+    Text { text: String },
+    #[serde(rename = "tool_use")]
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    #[serde(rename = "tool_result")]
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
+
+/// One part of the system prompt. Stable parts are cacheable, so providers
+/// with prompt caching reuse them across calls; dynamic parts go after them.
+#[derive(Debug, Clone)]
+pub struct SystemBlock {
+    pub text: String,
+    pub cacheable: bool,
+}
+
+impl SystemBlock {
+    pub fn cached(text: &str) -> Self {
+        Self { text: text.to_string(), cacheable: true }
+    }
+
+    /// Block that changes between requests (date, state), never cached
+    pub fn dynamic(text: &str) -> Self {
+        Self { text: text.to_string(), cacheable: false }
+    }
+}
+
+/// Join system blocks for providers that take a single system string
+pub fn system_text(system: &[SystemBlock]) -> String {
+    system
+        .iter()
+        .map(|b| b.text.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Create a user message with text content
+pub fn user_message(text: &str) -> Message {
+    Message {
+        role: "user".to_string(),
+        content: vec![ContentBlock::Text { text: text.to_string() }],
+    }
+}
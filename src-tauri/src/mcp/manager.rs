@@ -1,6 +1,13 @@
 use anyhow::{anyhow, Result};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::llm::SseDecoder;
+
+/// Times an interrupted SSE response is resumed with `Last-Event-ID`
+const MAX_STREAM_RESUMES: usize = 2;
 
 /// Configuration for an MCP server
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,7 +42,6 @@ struct JsonRpcRequest {
 struct JsonRpcResponse {
     #[allow(dead_code)]
     jsonrpc: String,
-    #[allow(dead_code)]
     id: Option<u32>,
     result: Option<Value>,
     error: Option<JsonRpcError>,
@@ -70,9 +76,11 @@ impl McpClient {
             params,
         };
 
+        // Streamable HTTP servers may answer with an SSE stream instead of JSON
         let response = self
             .client
             .post(&self.url)
+            .header("accept", "application/json, text/event-stream")
             .json(&request)
             .send()
             .await
@@ -82,10 +90,21 @@ impl McpClient {
             return Err(anyhow!("HTTP error: {}", response.status()));
         }
 
-        let rpc_response: JsonRpcResponse = response
-            .json()
-            .await
-            .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
+        let is_stream = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.starts_with("text/event-stream"))
+            .unwrap_or(false);
+
+        let rpc_response: JsonRpcResponse = if is_stream {
+            self.read_stream(response, request.id).await?
+        } else {
+            response
+                .json()
+                .await
+                .map_err(|e| anyhow!("Failed to parse response: {}", e))?
+        };
 
         if let Some(error) = rpc_response.error {
             return Err(anyhow!("RPC error: {}", error.message));
@@ -95,6 +114,62 @@ impl McpClient {
             .result
             .ok_or_else(|| anyhow!("No result in response"))
     }
+
+    /// Read SSE messages until the response to request `id` arrives (notifications
+    /// and server requests are skipped). A stream cut short, or broken by the network,
+    /// is resumed from the last event id, after the delay the server asked for.
+    async fn read_stream(&self, mut response: reqwest::Response, id: u32) -> Result<JsonRpcResponse> {
+        let mut resumes = 0;
+        loop {
+            let mut decoder = SseDecoder::new();
+            let mut stream = response.bytes_stream();
+            let mut interruption = None;
+
+            while let Some(chunk) = stream.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        interruption = Some(e);
+                        break;
+                    }
+                };
+                for event in decoder.feed(&chunk) {
+                    if event.event != "message" {
+                        continue;
+                    }
+                    let message: JsonRpcResponse = match serde_json::from_str(&event.data) {
+                        Ok(message) => message,
+                        Err(_) => continue,
+                    };
+                    if message.id == Some(id) && (message.result.is_some() || message.error.is_some()) {
+                        return Ok(message);
+                    }
+                }
+            }
+
+            let last_event_id = decoder.last_event_id().to_string();
+            if last_event_id.is_empty() || resumes == MAX_STREAM_RESUMES {
+                return Err(match interruption {
+                    Some(e) => anyhow!("Stream interrupted: {}", e),
+                    None => anyhow!("Stream ended without a response"),
+                });
+            }
+            resumes += 1;
+            tokio::time::sleep(decoder.retry().unwrap_or(Duration::from_secs(1))).await;
+
+            response = self
+                .client
+                .get(&self.url)
+                .header("accept", "text/event-stream")
+                .header("last-event-id", last_event_id)
+                .send()
+                .await
+                .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+            if !response.status().is_success() {
+                return Err(anyhow!("HTTP error: {}", response.status()));
+            }
+        }
+    }
 }
 
 /// Manages connections to MCP servers
@@ -237,3 +312,51 @@ pub async fn test_mcp_server(url: &str) -> Result<Vec<String>> {
 
     Ok(tool_names)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::mock_server::MockServer;
+
+    #[tokio::test]
+    async fn test_stream_resumed_after_network_drop() {
+        // The body is shorter than its content-length: the socket closes in the middle
+        // of the second event, which the client sees as a network error
+        let events = "retry: 10\n\
+                      id: 1\n\
+                      data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n\
+                      id: 2\n\
+                      data: {\"jsonrpc\":\"2.0\",\"id\"";
+        let dropped = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\n\r\n{}",
+            events.len() + 100,
+            events
+        );
+        let resumed = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\n\
+                       id: 2\n\
+                       data: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"tools\":[{\"name\":\"search\"}]}}\n\n"
+            .to_string();
+        let server = MockServer::start(vec![dropped, resumed]).await;
+
+        let result = McpClient::new(&server.base_url).call("tools/list", None).await.unwrap();
+
+        assert_eq!(result["tools"][0]["name"], "search");
+        assert_eq!(server.request_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_network_drop_without_event_id_is_reported() {
+        let events = "data: {\"jsonrpc\":\"2.0\",\"id\"";
+        let dropped = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\n\r\n{}",
+            events.len() + 100,
+            events
+        );
+        let server = MockServer::start(vec![dropped]).await;
+
+        let error = McpClient::new(&server.base_url).call("tools/list", None).await.unwrap_err();
+
+        assert!(error.to_string().starts_with("Stream interrupted"), "{}", error);
+        assert_eq!(server.request_count(), 1);
+    }
+}
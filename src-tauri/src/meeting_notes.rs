@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use futures::future::try_join_all;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::Semaphore;
+
+use crate::llm::{extract_structured, split_transcript, LlmProvider, SummaryConfig, ToolDefinition, TranscriptSegment};
+use crate::storage::{ActionItem, MeetingNotes, Recording};
+use crate::usage::UsageRecorder;
+use crate::DbState;
+
+const NOTES_TOOL_NAME: &str = "record_meeting_notes";
+
+/// Output of the extraction tool (one chunk of transcription)
+#[derive(Debug, Default, Deserialize)]
+struct ExtractedNotes {
+    #[serde(default)]
+    decisions: Vec<String>,
+    #[serde(default)]
+    action_items: Vec<ActionItem>,
+    #[serde(default)]
+    open_questions: Vec<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    people: Vec<String>,
+}
+
+/// Tool the model is forced to call; its input schema is the notes format
+fn notes_tool() -> ToolDefinition {
+    let string_list = |description: &str| json!({ "type": "array", "items": { "type": "string" }, "description": description });
+
+    ToolDefinition {
+        name: NOTES_TOOL_NAME.to_string(),
+        description: "Record the structured notes of a meeting transcription".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "decisions": string_list("Decisions that were made"),
+                "action_items": {
+                    "type": "array",
+                    "description": "Tasks someone agreed to do",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "description": { "type": "string" },
+                            "owner": { "type": "string", "description": "Person responsible, if mentioned" },
+                            "due_date": { "type": "string", "description": "YYYY-MM-DD if it can be inferred, otherwise as said" }
+                        },
+                        "required": ["description"]
+                    }
+                },
+                "open_questions": string_list("Questions left unanswered"),
+                "topics": string_list("Main topics discussed"),
+                "people": string_list("People mentioned or taking part")
+            },
+            "required": ["decisions", "action_items", "open_questions", "topics", "people"]
+        }),
+    }
+}
+
+/// Extract notes from a recording's transcription (chunk by chunk for long recordings)
+/// and store them, replacing previous notes
+pub async fn extract_meeting_notes(
+    provider: &dyn LlmProvider,
+    db: &DbState,
+    recording: &Recording,
+    config: &SummaryConfig,
+    usage: Option<&dyn UsageRecorder>,
+) -> Result<MeetingNotes> {
+    let transcriptions = {
+        let db = db.lock().map_err(|e| anyhow!("Database lock error: {}", e))?;
+        db.get_transcriptions(recording.id)?
+    };
+    let segments: Vec<TranscriptSegment> = transcriptions
+        .iter()
+        .map(|t| TranscriptSegment { timestamp: t.timestamp, text: &t.text })
+        .collect();
+
+    let chunks = split_transcript(&segments, config);
+    if chunks.is_empty() {
+        return Err(anyhow!("Recording '{}' has no transcriptions yet", recording.name));
+    }
+
+    let tool = notes_tool();
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let limit = Semaphore::new(config.concurrency.max(1));
+    let parts = try_join_all(chunks.iter().enumerate().map(|(i, chunk)| {
+        let prompt = format!(
+            "Extract structured notes from this meeting transcription (part {} of {}, recorded on {}; \
+            today is {}). Only include what is actually said, keep the language of the transcription, \
+            and leave lists empty when there is nothing to report. Record them with the {} tool.\n\n{}",
+            i + 1,
+            chunks.len(),
+            recording.created_at.format("%Y-%m-%d"),
+            today,
+            NOTES_TOOL_NAME,
+            chunk
+        );
+        let tool = &tool;
+        let limit = &limit;
+        async move {
+            let _permit = limit.acquire().await?;
+            let input = extract_structured(provider, &prompt, tool, 4096, usage).await?;
+            serde_json::from_value::<ExtractedNotes>(input).map_err(|e| anyhow!("Invalid meeting notes: {}", e))
+        }
+    }))
+    .await?;
+
+    let merged = merge_notes(parts);
+    let notes = MeetingNotes {
+        recording_id: recording.id,
+        decisions: merged.decisions,
+        action_items: merged.action_items,
+        open_questions: merged.open_questions,
+        topics: merged.topics,
+        people: merged.people,
+        model: provider.model().to_string(),
+        created_at: Utc::now(),
+    };
+
+    let db = db.lock().map_err(|e| anyhow!("Database lock error: {}", e))?;
+    db.save_meeting_notes(&notes)?;
+    Ok(notes)
+}
+
+/// Concatenate notes of consecutive chunks, dropping duplicates (case-insensitive)
+fn merge_notes(parts: Vec<ExtractedNotes>) -> ExtractedNotes {
+    fn push_unique(target: &mut Vec<String>, items: Vec<String>) {
+        for item in items {
+            let item = item.trim().to_string();
+            if !item.is_empty() && !target.iter().any(|t| t.to_lowercase() == item.to_lowercase()) {
+                target.push(item);
+            }
+        }
+    }
+
+    let mut merged = ExtractedNotes::default();
+    for part in parts {
+        push_unique(&mut merged.decisions, part.decisions);
+        push_unique(&mut merged.open_questions, part.open_questions);
+        push_unique(&mut merged.topics, part.topics);
+        push_unique(&mut merged.people, part.people);
+        for item in part.action_items {
+            let duplicate = merged
+                .action_items
+                .iter()
+                .any(|a| a.description.to_lowercase() == item.description.trim().to_lowercase());
+            if !duplicate && !item.description.trim().is_empty() {
+                merged.action_items.push(item);
+            }
+        }
+    }
+    merged
+}
+
+/// Markdown export of meeting notes (also used as tool result)
+pub fn notes_to_markdown(notes: &MeetingNotes, recording: &Recording) -> String {
+    fn section(out: &mut String, title: &str, items: &[String]) {
+        if items.is_empty() {
+            return;
+        }
+        out.push_str(&format!("\n## {}\n\n", title));
+        for item in items {
+            out.push_str(&format!("- {}\n", item));
+        }
+    }
+
+    let mut out = format!(
+        "# {}\n\n_{}_\n",
+        recording.name,
+        recording.created_at.format("%Y-%m-%d %H:%M")
+    );
+    section(&mut out, "Topics", &notes.topics);
+    section(&mut out, "Decisions", &notes.decisions);
+
+    if !notes.action_items.is_empty() {
+        out.push_str("\n## Action items\n\n");
+        for item in &notes.action_items {
+            let mut line = format!("- [ ] {}", item.description);
+            if let Some(owner) = &item.owner {
+                line.push_str(&format!(" — {}", owner));
+            }
+            if let Some(due) = &item.due_date {
+                line.push_str(&format!(" (due {})", due));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+
+    section(&mut out, "Open questions", &notes.open_questions);
+    section(&mut out, "People", &notes.people);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(decisions: &[&str], actions: &[&str]) -> ExtractedNotes {
+        ExtractedNotes {
+            decisions: decisions.iter().map(|s| s.to_string()).collect(),
+            action_items: actions
+                .iter()
+                .map(|a| ActionItem { description: a.to_string(), owner: Some("Alice".to_string()), due_date: None })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_notes_drops_duplicates() {
+        let merged = merge_notes(vec![
+            part(&["Ship v2 in March"], &["Send the budget"]),
+            part(&["ship v2 in march ", "Hire a designer"], &["send the budget", "Book the room"]),
+        ]);
+        assert_eq!(merged.decisions, vec!["Ship v2 in March", "Hire a designer"]);
+        assert_eq!(merged.action_items.len(), 2);
+        assert_eq!(merged.action_items[1].description, "Book the room");
+    }
+
+    #[test]
+    fn test_notes_tool_schema_matches_extracted_notes() {
+        // Every required field of the schema is read back from the tool input
+        let tool = notes_tool();
+        let input = json!({
+            "decisions": ["A"],
+            "action_items": [{ "description": "B", "owner": "Bob", "due_date": "2026-01-31" }],
+            "open_questions": [],
+            "topics": ["C"],
+            "people": ["Bob"]
+        });
+        for field in tool.input_schema["required"].as_array().unwrap() {
+            assert!(input.get(field.as_str().unwrap()).is_some());
+        }
+        let notes: ExtractedNotes = serde_json::from_value(input).unwrap();
+        assert_eq!(notes.action_items[0].owner.as_deref(), Some("Bob"));
+        assert_eq!(notes.topics, vec!["C"]);
+    }
+}
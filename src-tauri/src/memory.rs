@@ -0,0 +1,118 @@
+use anyhow::Result;
+
+use crate::intent::normalize;
+use crate::storage::{Database, Memory};
+
+/// Memories matching a command that are added to the system prompt
+pub const MAX_PROMPT_MEMORIES: usize = 5;
+
+/// Candidates considered when looking a memory up to forget it
+const MAX_FIND_MEMORIES: usize = 20;
+
+/// Common EN/FR words that say nothing about what a memory is about
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "was", "that", "this", "with", "you", "your", "what", "who", "when", "where",
+    "how", "about", "from", "have", "has", "call", "called", "remember", "know", "tell", "please", "robert",
+    "les", "des", "une", "est", "que", "qui", "quoi", "dans", "pour", "avec", "mon", "mes", "mais", "sur",
+    "pas", "son", "ses", "tout", "appelle", "souviens", "rappelle", "retiens", "dis", "moi",
+];
+
+/// Normalized words used to index and search memories (accents folded, short
+/// and common words dropped, plural "s" removed)
+pub fn keywords(text: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for word in normalize(text).split_whitespace() {
+        if word.chars().count() < 3 || STOP_WORDS.contains(&word) {
+            continue;
+        }
+        let word = match word.strip_suffix('s') {
+            Some(stem) if stem.len() >= 3 => stem,
+            _ => word,
+        };
+        if !keywords.iter().any(|k| k == word) {
+            keywords.push(word.to_string());
+        }
+    }
+    keywords
+}
+
+/// Store a fact; the same fact said again only refreshes it
+pub fn remember(db: &Database, content: &str) -> Result<Memory> {
+    let content = content.trim();
+    let words = keywords(content);
+    let existing = db
+        .search_memories(&words, MAX_PROMPT_MEMORIES)?
+        .into_iter()
+        .find(|m| normalize(&m.content) == normalize(content));
+
+    match existing {
+        Some(memory) => {
+            db.update_memory(memory.id, content, &words)?;
+            Ok(memory)
+        }
+        None => db.add_memory(content, &words),
+    }
+}
+
+/// Memories matching `query`, best first (all memories if the query has no keywords)
+pub fn recall(db: &Database, query: &str, limit: usize) -> Result<Vec<Memory>> {
+    let words = keywords(query);
+    if words.is_empty() {
+        let mut memories = db.list_memories()?;
+        memories.truncate(limit);
+        return Ok(memories);
+    }
+    db.search_memories(&words, limit)
+}
+
+/// Memories matching every keyword of `query` (or a word starting with it), best first
+pub fn find(db: &Database, query: &str) -> Result<Vec<Memory>> {
+    let words = keywords(query);
+    let memories = db.search_memories(&words, MAX_FIND_MEMORIES)?;
+    Ok(memories
+        .into_iter()
+        .filter(|m| {
+            let found = keywords(&m.content);
+            words.iter().all(|w| found.iter().any(|k| k.starts_with(w.as_str())))
+        })
+        .collect())
+}
+
+/// Memories relevant to a command, as given to the model
+pub fn prompt_memories(db: &Database, command: &str) -> Vec<String> {
+    let words = keywords(command);
+    match db.search_memories(&words, MAX_PROMPT_MEMORIES) {
+        Ok(memories) => memories.into_iter().map(|m| m.content).collect(),
+        Err(e) => {
+            eprintln!("[Memory] Search failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords() {
+        assert_eq!(keywords("My manager is Alice"), vec!["manager", "alice"]);
+        assert_eq!(keywords("Call the weekly sync 'Standup'"), vec!["weekly", "sync", "standup"]);
+        assert_eq!(keywords("Mes réunions d'équipe"), vec!["reunion", "equipe"]);
+        assert_eq!(keywords("Who is my manager?"), vec!["manager"]);
+        assert!(keywords("is it ok?").is_empty());
+    }
+
+    #[test]
+    fn test_find_needs_every_keyword() {
+        let db = Database::in_memory().unwrap();
+        remember(&db, "My manager is Alice").unwrap();
+        remember(&db, "Alice likes tea").unwrap();
+
+        assert_eq!(find(&db, "alice").unwrap().len(), 2);
+        let found = find(&db, "Alice's manager").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].content, "My manager is Alice");
+        assert!(find(&db, "budget").unwrap().is_empty());
+    }
+}
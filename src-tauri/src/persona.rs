@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PERSONA_ID: &str = "robert";
+
+const DEFAULT_WAKE_PATTERNS: &[&str] = &["ok robert", "okay robert", "hey robert", "robert,", "robert "];
+
+const DEFAULT_SYSTEM_PROMPT: &str = "You are Robert, a voice assistant that helps users manage their meeting recordings. \
+You can start/stop recordings, list them, summarize them, get their content and meeting notes (decisions, action items), rename them, and delete them. \
+You can also remember facts the user tells you (remember_fact), look them up (recall_facts) and forget them (forget_fact). \
+When the user confirms an action (like 'yes', 'go ahead', 'do it', 'tu peux y aller'), execute the action discussed. \
+Always respond in the same language the user speaks.
+
+Current context:
+- Date and time: {{weekday}} {{date}} {{time}} ({{timezone}})
+- User: {{user_name}} (locale {{locale}})
+- Recording in progress: {{active_recording}}
+- Saved recordings: {{recording_count}}
+- Connected MCP servers: {{mcp_servers}}";
+
+/// An assistant persona: its own wake words, system prompt, tools and history
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Persona {
+    pub id: String,
+    pub name: String,
+    pub wake_words: Vec<String>,
+    pub system_prompt: String,
+    /// Tool names this persona may use; a trailing `*` matches a prefix
+    /// (e.g. "panorama_*" for all tools of an MCP server). None = all tools.
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+impl Persona {
+    /// The built-in Robert persona, used when no personas are configured
+    pub fn default_persona(extra_wake_words: &[String]) -> Self {
+        let mut wake_words: Vec<String> = DEFAULT_WAKE_PATTERNS.iter().map(|w| w.to_string()).collect();
+        for word in extra_wake_words {
+            let word = word.trim().to_lowercase();
+            if !word.is_empty() && !wake_words.contains(&word) {
+                wake_words.push(word);
+            }
+        }
+
+        Self {
+            id: DEFAULT_PERSONA_ID.to_string(),
+            name: "Robert".to_string(),
+            wake_words,
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            allowed_tools: None,
+        }
+    }
+
+    pub fn allows_tool(&self, tool_name: &str) -> bool {
+        match &self.allowed_tools {
+            None => true,
+            Some(allowed) => allowed.iter().any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => tool_name.starts_with(prefix),
+                None => pattern == tool_name,
+            }),
+        }
+    }
+}
+
+/// A wake word found in a transcription
+pub struct WakeMatch<'a> {
+    pub persona: &'a Persona,
+    /// Text following the wake word (None if nothing was said after it)
+    pub command: Option<String>,
+}
+
+/// Find the persona whose wake word appears first in the text
+pub fn match_wake_word<'a>(personas: &'a [Persona], text: &str) -> Option<WakeMatch<'a>> {
+    let text_lower = text.to_lowercase();
+
+    // (position, pattern length, persona) for every occurrence
+    let mut matches: Vec<(usize, usize, &Persona)> = personas
+        .iter()
+        .flat_map(|persona| {
+            let text_lower = &text_lower;
+            persona.wake_words.iter().filter_map(move |pattern| {
+                let pattern = pattern.to_lowercase();
+                if pattern.trim().is_empty() {
+                    return None;
+                }
+                text_lower.find(&pattern).map(|pos| (pos, pattern.len(), persona))
+            })
+        })
+        .collect();
+
+    // Earliest match wins; on ties the longest pattern ("ok robert" over "robert ")
+    matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    let first_persona = matches.first()?.2;
+
+    // Prefer a match of that persona that is followed by a command
+    let command = matches
+        .iter()
+        .filter(|(_, _, persona)| persona.id == first_persona.id)
+        .find_map(|(pos, len, _)| {
+            // Lowercasing can change byte offsets for some characters
+            let command_text = text.get(pos + len..)?
+                .trim()
+                .trim_start_matches(|c: char| c.is_ascii_punctuation())
+                .trim()
+                .to_string();
+            (!command_text.is_empty()).then_some(command_text)
+        });
+
+    Some(WakeMatch {
+        persona: first_persona,
+        command,
+    })
+}
+
+/// Phrases that interrupt the current command ("Robert, stop")
+const STOP_COMMANDS: &[&str] = &[
+    "stop", "stop it", "cancel", "never mind", "nevermind", "be quiet", "shut up", "enough",
+    "arrête", "arrete", "arrête-toi", "stoppe", "annule", "tais-toi", "laisse tomber", "ça suffit",
+];
+
+/// Phrases that start a fresh conversation
+const NEW_CONVERSATION_COMMANDS: &[&str] = &[
+    "new conversation", "start a new conversation", "start a new session", "new session", "start over",
+    "nouvelle conversation", "commence une nouvelle conversation", "nouvelle session", "on recommence",
+];
+
+/// Answers to a tool confirmation
+const YES_ANSWERS: &[&str] = &[
+    "yes", "yeah", "yep", "sure", "ok", "okay", "go ahead", "do it", "confirm", "confirmed", "yes do it",
+    "yes go ahead", "of course", "oui", "ouais", "vas-y", "vas y", "d'accord", "ok vas-y", "confirme",
+    "je confirme", "tu peux y aller", "c'est bon", "oui vas-y", "bien sûr",
+];
+const NO_ANSWERS: &[&str] = &[
+    "no", "nope", "don't", "do not", "don't do it", "no don't", "not now",
+    "non", "pas question", "ne le fais pas", "surtout pas", "non ne le fais pas",
+];
+
+/// Lowercase words of a command, without punctuation
+fn command_words(command: &str) -> Vec<String> {
+    command
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '\'' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// Whether a command is only a request to stop (ignores case, punctuation and repetition)
+pub fn is_stop_command(command: &str) -> bool {
+    let words = command_words(command);
+    if words.is_empty() {
+        return false;
+    }
+
+    // "stop stop stop" is still a stop
+    let mut unique = words.clone();
+    unique.dedup();
+    STOP_COMMANDS.contains(&words.join(" ").as_str()) || STOP_COMMANDS.contains(&unique.join(" ").as_str())
+}
+
+/// Whether a command asks to start a new conversation ("new conversation please")
+pub fn is_new_conversation_command(command: &str) -> bool {
+    let words: Vec<String> = command_words(command)
+        .into_iter()
+        .filter(|w| !matches!(w.as_str(), "please" | "let's" | "stp" | "s'il" | "te" | "plaît" | "plait"))
+        .collect();
+    NEW_CONVERSATION_COMMANDS.contains(&words.join(" ").as_str())
+}
+
+/// Yes or no to a confirmation question (None if the reply is something else)
+pub fn parse_confirmation(reply: &str) -> Option<bool> {
+    // "yes yes", "oui merci", "no, please"
+    let mut words: Vec<String> = command_words(reply)
+        .into_iter()
+        .filter(|w| !matches!(w.as_str(), "please" | "thanks" | "stp" | "s'il" | "te" | "plaît" | "plait" | "merci"))
+        .collect();
+    words.dedup();
+    let reply = words.join(" ");
+
+    if YES_ANSWERS.contains(&reply.as_str()) {
+        Some(true)
+    } else if NO_ANSWERS.contains(&reply.as_str()) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scribe() -> Persona {
+        Persona {
+            id: "scribe".to_string(),
+            name: "Scribe".to_string(),
+            wake_words: vec!["hey scribe".to_string()],
+            system_prompt: "You take notes.".to_string(),
+            allowed_tools: Some(vec!["list_recordings".to_string(), "panorama_*".to_string()]),
+        }
+    }
+
+    #[test]
+    fn test_routes_to_matching_persona() {
+        let personas = vec![Persona::default_persona(&[]), scribe()];
+
+        let m = match_wake_word(&personas, "Hey Scribe, take a note").unwrap();
+        assert_eq!(m.persona.id, "scribe");
+        assert_eq!(m.command.as_deref(), Some("take a note"));
+
+        let m = match_wake_word(&personas, "OK Robert, start recording").unwrap();
+        assert_eq!(m.persona.id, DEFAULT_PERSONA_ID);
+        assert_eq!(m.command.as_deref(), Some("start recording"));
+
+        assert!(match_wake_word(&personas, "nothing to see here").is_none());
+    }
+
+    #[test]
+    fn test_wake_word_without_command() {
+        let personas = vec![Persona::default_persona(&[])];
+        let m = match_wake_word(&personas, "Hey Robert.").unwrap();
+        assert!(m.command.is_none());
+    }
+
+    #[test]
+    fn test_allowed_tools() {
+        let persona = scribe();
+        assert!(persona.allows_tool("list_recordings"));
+        assert!(persona.allows_tool("panorama_tool_tasksFilter"));
+        assert!(!persona.allows_tool("delete_recording"));
+        assert!(Persona::default_persona(&[]).allows_tool("delete_recording"));
+    }
+
+    #[test]
+    fn test_stop_commands() {
+        assert!(is_stop_command("Stop."));
+        assert!(is_stop_command("stop, stop"));
+        assert!(is_stop_command("Arrête !"));
+        assert!(is_stop_command("never mind"));
+        assert!(!is_stop_command("stop the recording"));
+        assert!(!is_stop_command(""));
+    }
+
+    #[test]
+    fn test_new_conversation_commands() {
+        assert!(is_new_conversation_command("New conversation, please."));
+        assert!(is_new_conversation_command("Let's start over"));
+        assert!(is_new_conversation_command("Nouvelle conversation s'il te plaît"));
+        assert!(!is_new_conversation_command("summarize the new conversation recording"));
+    }
+
+    #[test]
+    fn test_confirmation_answers() {
+        assert_eq!(parse_confirmation("Yes, please."), Some(true));
+        assert_eq!(parse_confirmation("Oui, vas-y !"), Some(true));
+        // The wake word alone starts a command, it never approves one
+        assert_eq!(parse_confirmation("OK Robert"), None);
+        assert_eq!(parse_confirmation("Hey Robert"), None);
+        assert_eq!(parse_confirmation("No no no"), Some(false));
+        assert_eq!(parse_confirmation("Non merci"), Some(false));
+        assert_eq!(parse_confirmation("Delete the other one instead"), None);
+        assert_eq!(parse_confirmation(""), None);
+    }
+}
@@ -0,0 +1,213 @@
+use chrono::{DateTime, FixedOffset, Local};
+use std::collections::HashMap;
+
+use crate::llm::{summary_block, SystemBlock};
+use crate::mcp::McpServerConfig;
+use crate::state::AppState;
+use crate::tools::ToolSource;
+use crate::DbState;
+
+/// Variables available in system prompt templates, as `{{name}}`
+pub const PROMPT_VARIABLES: &[&str] = &[
+    "date",
+    "time",
+    "weekday",
+    "timezone",
+    "locale",
+    "user_name",
+    "active_recording",
+    "recording_count",
+    "mcp_servers",
+];
+
+/// Values filled into the system prompt at request time
+#[derive(Debug, Clone)]
+pub struct PromptContext {
+    pub now: DateTime<FixedOffset>,
+    pub timezone: String,
+    pub locale: String,
+    pub user_name: Option<String>,
+    pub active_recording: Option<String>,
+    pub recording_count: usize,
+    /// Names of the MCP servers whose tools were listed for this request
+    pub mcp_servers: Vec<String>,
+    /// Stored memories relevant to the command
+    pub memories: Vec<String>,
+}
+
+impl PromptContext {
+    pub fn gather(state: &AppState, db: Option<&DbState>, mcp_servers: Vec<String>) -> Self {
+        let now = Local::now();
+        let recording_count = db
+            .and_then(|db| db.lock().ok())
+            .and_then(|db| db.list_recordings().ok())
+            .map(|recordings| recordings.len())
+            .unwrap_or(0);
+
+        Self {
+            now: now.fixed_offset(),
+            timezone: std::env::var("TZ")
+                .ok()
+                .filter(|tz| !tz.is_empty())
+                .unwrap_or_else(|| now.format("UTC%:z").to_string()),
+            locale: state.settings.locale.clone().unwrap_or_else(system_locale),
+            user_name: state.settings.user_name.clone().filter(|n| !n.trim().is_empty()),
+            active_recording: state.active_recording.as_ref().map(|r| r.name.clone()),
+            recording_count,
+            mcp_servers,
+            memories: Vec::new(),
+        }
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "date" => self.now.format("%Y-%m-%d").to_string(),
+            "time" => self.now.format("%H:%M").to_string(),
+            "weekday" => self.now.format("%A").to_string(),
+            "timezone" => self.timezone.clone(),
+            "locale" => self.locale.clone(),
+            "user_name" => self.user_name.clone().unwrap_or_else(|| "unknown".to_string()),
+            "active_recording" => self.active_recording.clone().unwrap_or_else(|| "none".to_string()),
+            "recording_count" => self.recording_count.to_string(),
+            "mcp_servers" if self.mcp_servers.is_empty() => "none".to_string(),
+            "mcp_servers" => self.mcp_servers.join(", "),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Locale from the environment ("fr_FR.UTF-8" -> "fr-FR"), "en-US" if unset
+fn system_locale() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|value| value.split(['.', '@']).next().unwrap_or("").replace('_', "-"))
+        .find(|locale| !locale.is_empty() && locale != "C" && locale != "POSIX")
+        .unwrap_or_else(|| "en-US".to_string())
+}
+
+/// Names of the MCP servers that provided tools
+pub fn connected_servers(configs: &[McpServerConfig], routing: &HashMap<String, ToolSource>) -> Vec<String> {
+    configs
+        .iter()
+        .filter(|config| {
+            routing
+                .values()
+                .any(|source| matches!(source, ToolSource::Mcp { server_id, .. } if *server_id == config.id))
+        })
+        .map(|config| config.name.clone())
+        .collect()
+}
+
+/// Replace known `{{variables}}`; unknown ones are left as written
+pub fn render_template(template: &str, context: &PromptContext) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        out.push_str(&rest[..start]);
+        match context.value(rest[start + 2..end].trim()) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// System blocks for a request. Lines before the first variable don't change between
+/// commands and are cached; the rest is rendered as an uncached block, placed after the
+/// conversation summary so the cached prefix stays as long as possible. Relevant
+/// memories come last.
+pub fn system_blocks(template: &str, context: &PromptContext, summary: Option<&str>) -> Vec<SystemBlock> {
+    let first_variable = PROMPT_VARIABLES
+        .iter()
+        .filter_map(|name| {
+            template.match_indices("{{").find(|(i, _)| {
+                template[i + 2..].split("}}").next().map(str::trim) == Some(*name)
+            })
+        })
+        .map(|(i, _)| i)
+        .min();
+    let split = first_variable
+        .map(|i| template[..i].rfind('\n').map(|n| n + 1).unwrap_or(0))
+        .unwrap_or(template.len());
+    let (stable, dynamic) = template.split_at(split);
+
+    let mut blocks = Vec::new();
+    if !stable.trim().is_empty() {
+        blocks.push(SystemBlock::cached(stable.trim_end()));
+    }
+    if let Some(summary) = summary {
+        blocks.push(summary_block(summary));
+    }
+    if !dynamic.trim().is_empty() {
+        blocks.push(SystemBlock::dynamic(render_template(dynamic, context).trim()));
+    }
+    if !context.memories.is_empty() {
+        let list: Vec<String> = context.memories.iter().map(|m| format!("- {}", m)).collect();
+        blocks.push(SystemBlock::dynamic(&format!(
+            "What you remember about the user (from remember_fact):\n{}",
+            list.join("\n")
+        )));
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn context() -> PromptContext {
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        PromptContext {
+            now: offset.with_ymd_and_hms(2026, 3, 12, 9, 5, 0).unwrap(),
+            timezone: "Europe/Paris".to_string(),
+            locale: "fr-FR".to_string(),
+            user_name: Some("Camille".to_string()),
+            active_recording: None,
+            recording_count: 3,
+            mcp_servers: vec!["Panorama".to_string()],
+            memories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let rendered = render_template(
+            "Today is {{weekday}} {{ date }} at {{time}} ({{timezone}}). User: {{user_name}}, {{locale}}. \
+            Recording: {{active_recording}}, {{recording_count}} saved. Servers: {{mcp_servers}}. {{unknown}} {{",
+            &context(),
+        );
+        assert_eq!(
+            rendered,
+            "Today is Thursday 2026-03-12 at 09:05 (Europe/Paris). User: Camille, fr-FR. \
+            Recording: none, 3 saved. Servers: Panorama. {{unknown}} {{"
+        );
+    }
+
+    #[test]
+    fn test_system_blocks_cache_static_part() {
+        let template = "You are Robert.\nBe brief.\n\nNow: {{date}} {{time}}\nUser: {{user_name}}";
+        let blocks = system_blocks(template, &context(), Some("Earlier: listed recordings"));
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].text, "You are Robert.\nBe brief.");
+        assert!(blocks[0].cacheable);
+        assert!(blocks[1].cacheable);
+        assert_eq!(blocks[2].text, "Now: 2026-03-12 09:05\nUser: Camille");
+        assert!(!blocks[2].cacheable);
+
+        // No variables: a single cached block
+        let blocks = system_blocks("You are Robert. {{unknown}}", &context(), None);
+        assert_eq!(blocks.len(), 1);
+        assert!(blocks[0].cacheable);
+    }
+}
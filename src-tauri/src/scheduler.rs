@@ -0,0 +1,227 @@
+use chrono::{DateTime, Local, Utc};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::sync::Notify;
+
+use crate::state::CommandControl;
+use crate::dates;
+use crate::storage::{Reminder, ReminderKind};
+use crate::{CommandState, CopilotState, DbState, SchedulerState};
+
+/// Longest sleep between checks, so reminders stay on time after the computer slept
+const MAX_SLEEP: Duration = Duration::from_secs(30);
+
+/// Timers and reminders can't be set further ahead than this
+const MAX_DELAY: chrono::Duration = chrono::Duration::days(366);
+
+/// Reminders this late (the app was closed when they were due) say so
+const LATE_AFTER: chrono::Duration = chrono::Duration::minutes(1);
+
+/// Wakes the scheduler when reminders are added or cancelled
+#[derive(Default)]
+pub struct Scheduler {
+    changed: Notify,
+}
+
+impl Scheduler {
+    /// Call after changing the reminders table
+    pub fn reschedule(&self) {
+        self.changed.notify_one();
+    }
+}
+
+/// When a reminder set for `when` ("in 20 minutes", "demain à 9h") is due
+pub fn due_at(when: &str) -> Result<DateTime<Utc>, String> {
+    let due = dates::parse_due(when, &Local::now()).ok_or_else(|| {
+        format!(
+            "Couldn't understand the time '{}' (try 'in 20 minutes', 'at 15:30' or 'tomorrow at 9am')",
+            when
+        )
+    })?;
+    if due <= Utc::now() {
+        return Err(format!("{} has already passed", due.with_timezone(&Local).format("%Y-%m-%d %H:%M")));
+    }
+    if due - Utc::now() > MAX_DELAY {
+        return Err(format!("{} is more than a year away", due.with_timezone(&Local).format("%Y-%m-%d %H:%M")));
+    }
+    Ok(due)
+}
+
+/// When a timer of `duration` ends
+pub fn ends_after(duration: chrono::Duration) -> Result<DateTime<Utc>, String> {
+    if duration > MAX_DELAY {
+        return Err("Timers can't last more than a year".to_string());
+    }
+    Utc::now()
+        .checked_add_signed(duration)
+        .ok_or_else(|| "Timers can't last more than a year".to_string())
+}
+
+/// "15:50 (in 20 min)", with the day when it isn't today
+pub fn describe_due(due: DateTime<Utc>) -> String {
+    let local = due.with_timezone(&Local);
+    let time = if local.date_naive() == Local::now().date_naive() {
+        local.format("%H:%M").to_string()
+    } else {
+        local.format("%a %Y-%m-%d %H:%M").to_string()
+    };
+    let minutes = (due - Utc::now()).num_minutes();
+    match minutes {
+        m if m < 1 => format!("{} (in less than a minute)", time),
+        m if m < 60 => format!("{} (in {} min)", time, m),
+        m => format!("{} (in {} h {:02} min)", time, m / 60, m % 60),
+    }
+}
+
+/// Text shown in the copilot window when a reminder fires
+pub fn reminder_text(reminder: &Reminder) -> String {
+    let mut text = match reminder.kind {
+        ReminderKind::Timer => format!("**Timer done**: {}", reminder.message),
+        ReminderKind::Reminder => format!("**Reminder**: {}", reminder.message),
+    };
+    if Utc::now() - reminder.due_at > LATE_AFTER {
+        text.push_str(&format!(
+            "\n\n_Was due at {}_",
+            reminder.due_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ));
+    }
+    text
+}
+
+/// Pending reminders (soonest first) split into those due at `now` and the later ones
+fn split_due(pending: Vec<Reminder>, now: DateTime<Utc>) -> (Vec<Reminder>, Vec<Reminder>) {
+    pending.into_iter().partition(|r| r.due_at <= now)
+}
+
+/// Fire reminders when they are due, until the app exits. Those that came due while the
+/// app was closed fire at startup.
+pub async fn run(
+    app: tauri::AppHandle,
+    db: DbState,
+    copilot_state: CopilotState,
+    command_state: CommandState,
+    scheduler: SchedulerState,
+) {
+    loop {
+        let pending = match db.lock() {
+            Ok(db) => db.list_pending_reminders().unwrap_or_else(|e| {
+                eprintln!("[Scheduler] Could not load reminders: {}", e);
+                Vec::new()
+            }),
+            Err(_) => return,
+        };
+
+        let (due, later) = split_due(pending, Utc::now());
+        if !due.is_empty() {
+            fire(&app, &db, &copilot_state, &command_state, &due).await;
+        }
+
+        let wait = match later.first() {
+            Some(next) => (next.due_at - Utc::now()).to_std().unwrap_or(Duration::ZERO).min(MAX_SLEEP),
+            None => MAX_SLEEP,
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = scheduler.changed.notified() => {}
+        }
+    }
+}
+
+/// Show the due reminders together once the command in flight (if any) is done
+async fn fire(
+    app: &tauri::AppHandle,
+    db: &DbState,
+    copilot_state: &CopilotState,
+    command_state: &CommandControl,
+    due: &[Reminder],
+) {
+    // No command starts while the reminders take over the copilot window
+    let _idle = command_state.idle().await;
+
+    // That command may have cancelled some of them
+    let due: Vec<Reminder> = match db.lock() {
+        Ok(db) => {
+            let pending = db.list_pending_reminders().unwrap_or_else(|e| {
+                eprintln!("[Scheduler] Could not load reminders: {}", e);
+                Vec::new()
+            });
+            let due: Vec<Reminder> = pending.into_iter().filter(|r| due.iter().any(|d| d.id == r.id)).collect();
+            for reminder in &due {
+                if let Err(e) = db.mark_reminder_fired(reminder.id) {
+                    eprintln!("[Scheduler] Could not mark reminder fired: {}", e);
+                }
+            }
+            due
+        }
+        Err(_) => return,
+    };
+    if due.is_empty() {
+        return;
+    }
+    for reminder in &due {
+        println!("[Scheduler] {} fired: {}", reminder.kind.as_str(), reminder.message);
+        let _ = app.emit("reminder", reminder);
+    }
+
+    {
+        let mut copilot = copilot_state.write().unwrap();
+        copilot.visible = true;
+        copilot.state = "responding".to_string();
+        copilot.response_text = due.iter().map(reminder_text).collect::<Vec<_>>().join("\n\n");
+        copilot.heard_text.clear();
+        copilot.follow_up = false;
+        copilot.error = None;
+        copilot.should_close = true;
+    }
+    if let Some(window) = app.get_webview_window("copilot") {
+        let _ = window.show();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn reminder(message: &str, due_at: DateTime<Utc>) -> Reminder {
+        Reminder {
+            id: Uuid::new_v4(),
+            kind: ReminderKind::Reminder,
+            message: message.to_string(),
+            due_at,
+            created_at: due_at - chrono::Duration::hours(1),
+            fired_at: None,
+        }
+    }
+
+    #[test]
+    fn test_split_due() {
+        let now = Utc::now();
+        let pending = vec![
+            reminder("overdue", now - chrono::Duration::hours(2)),
+            reminder("now", now),
+            reminder("soon", now + chrono::Duration::seconds(1)),
+            reminder("tomorrow", now + chrono::Duration::days(1)),
+        ];
+
+        let (due, later) = split_due(pending, now);
+        let messages = |reminders: &[Reminder]| reminders.iter().map(|r| r.message.clone()).collect::<Vec<_>>();
+        assert_eq!(messages(&due), ["overdue", "now"]);
+        assert_eq!(messages(&later), ["soon", "tomorrow"]);
+
+        let (due, later) = split_due(Vec::new(), now);
+        assert!(due.is_empty() && later.is_empty());
+    }
+
+    #[test]
+    fn test_delays_capped_at_a_year() {
+        let week = dates::parse_duration("1 week").unwrap();
+        assert!(ends_after(week).unwrap() > Utc::now());
+        let absurd = dates::parse_duration("100000000 weeks").unwrap();
+        assert_eq!(ends_after(absurd).unwrap_err(), "Timers can't last more than a year");
+
+        assert!(due_at("in 20 minutes").is_ok());
+        assert!(due_at("in 100 weeks").unwrap_err().contains("more than a year away"));
+        assert!(due_at("in 100000000 weeks").unwrap_err().starts_with("Couldn't understand"));
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+
+use crate::storage::{Database, TranscriptFilter, TranscriptMatch};
+
+/// FTS5 query for what the user typed: every word must appear, words of three letters
+/// or more also match as a prefix ("budget" finds "budgets"), and "quoted phrases"
+/// must appear as written. None if there is nothing to search for.
+pub fn fts_query(text: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    // Odd parts are between quotes
+    for (i, part) in text.split('"').enumerate() {
+        let words: Vec<&str> = part.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+        if words.is_empty() {
+            continue;
+        }
+        if i % 2 == 1 {
+            terms.push(format!("\"{}\"", words.join(" ")));
+        } else {
+            for word in words {
+                let prefix = if word.chars().count() >= 3 { "*" } else { "" };
+                terms.push(format!("\"{}\"{}", word, prefix));
+            }
+        }
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Transcription segments matching `query`, best first
+pub fn search_transcripts(
+    db: &Database,
+    query: &str,
+    filter: &TranscriptFilter,
+    limit: usize,
+) -> Result<Vec<TranscriptMatch>> {
+    match fts_query(query) {
+        Some(fts_query) => db.search_transcripts(&fts_query, filter, limit),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("budget freeze").as_deref(), Some("\"budget\"* \"freeze\"*"));
+        assert_eq!(
+            fts_query("\"budget freeze\" Q3 OR"),
+            Some("\"budget freeze\" \"Q3\" \"OR\"".to_string())
+        );
+        // FTS5 syntax is taken literally
+        assert_eq!(fts_query("l'équipe: NEAR(a*)").as_deref(), Some("\"l\" \"équipe\"* \"NEAR\"* \"a\""));
+        assert_eq!(fts_query("  \"\" ?! "), None);
+    }
+}
@@ -1,7 +1,12 @@
-use crate::llm::Message;
+use crate::llm::{CancellationToken, LlmConfig, LlmErrorKind, Message, SummaryConfig};
 use crate::mcp::McpServerConfig;
+use crate::persona::Persona;
+use crate::tools::ToolPolicy;
+use crate::usage::UsageSettings;
+use chrono::{DateTime, Utc};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use uuid::Uuid;
 
@@ -9,7 +14,12 @@ use uuid::Uuid;
 pub struct AppState {
     pub settings: Settings,
     pub active_recording: Option<ActiveRecording>,
-    pub conversation_history: Vec<Message>,
+    /// Conversation history per persona id
+    pub conversation_histories: HashMap<String, Vec<Message>>,
+    /// Running summary of the turns compacted out of each persona's history
+    pub conversation_summaries: HashMap<String, String>,
+    /// Conversation continued by each persona (persisted in the database)
+    pub active_conversations: HashMap<String, ActiveConversation>,
 }
 
 impl AppState {
@@ -17,7 +27,9 @@ impl AppState {
         Self {
             settings: Settings::load().unwrap_or_default(),
             active_recording: None,
-            conversation_history: Vec::new(),
+            conversation_histories: HashMap::new(),
+            conversation_summaries: HashMap::new(),
+            active_conversations: HashMap::new(),
         }
     }
 }
@@ -28,6 +40,12 @@ pub struct ActiveRecording {
     pub name: String,
 }
 
+#[derive(Clone, Debug)]
+pub struct ActiveConversation {
+    pub id: Uuid,
+    pub last_activity: DateTime<Utc>,
+}
+
 #[derive(Clone, Serialize, Default)]
 pub struct CopilotUIState {
     pub visible: bool,
@@ -35,6 +53,12 @@ pub struct CopilotUIState {
     pub response_text: String,
     pub should_close: bool,
     pub heard_text: String,
+    /// Listening for a reply without wake word after the last response
+    pub follow_up: bool,
+    /// Category of the LLM error that ended the last command, if any
+    pub error: Option<LlmErrorKind>,
+    /// Question of the tool call waiting for the user's yes or no
+    pub confirmation: Option<String>,
 }
 
 impl CopilotUIState {
@@ -45,6 +69,9 @@ impl CopilotUIState {
             response_text: String::new(),
             should_close: false,
             heard_text: String::new(),
+            follow_up: false,
+            error: None,
+            confirmation: None,
         }
     }
 
@@ -54,6 +81,97 @@ impl CopilotUIState {
         self.response_text.clear();
         self.should_close = false;
         self.heard_text.clear();
+        self.follow_up = false;
+        self.error = None;
+        self.confirmation = None;
+    }
+}
+
+/// The command being processed, so a new one (or "stop") can interrupt it
+pub struct CommandControl {
+    /// Token of the latest command
+    current: std::sync::Mutex<CancellationToken>,
+    /// Held while a command runs, so the next one starts once history is saved
+    running: tokio::sync::Mutex<()>,
+    /// Where the answer to the pending tool confirmation goes
+    confirmation: std::sync::Mutex<Option<tokio::sync::oneshot::Sender<bool>>>,
+    /// Held while a confirmation is asked (parallel tool calls ask one at a time)
+    confirming: tokio::sync::Mutex<()>,
+    /// Persona asking for a confirmation, so the audio loop listens for a yes or no
+    confirmation_tx: crossbeam_channel::Sender<Persona>,
+    confirmation_rx: crossbeam_channel::Receiver<Persona>,
+}
+
+impl Default for CommandControl {
+    fn default() -> Self {
+        let (confirmation_tx, confirmation_rx) = crossbeam_channel::unbounded();
+        Self {
+            current: Default::default(),
+            running: Default::default(),
+            confirmation: Default::default(),
+            confirming: Default::default(),
+            confirmation_tx,
+            confirmation_rx,
+        }
+    }
+}
+
+impl CommandControl {
+    /// Interrupt the in-flight command (no-op if it already finished)
+    pub fn cancel(&self) {
+        self.current.lock().unwrap().cancel();
+    }
+
+    /// Cancel the previous command and wait for it to wind down.
+    /// The returned token may already be cancelled if yet another command arrived meanwhile.
+    pub async fn begin(&self) -> (CancellationToken, tokio::sync::MutexGuard<'_, ()>) {
+        let token = CancellationToken::new();
+        {
+            let mut current = self.current.lock().unwrap();
+            current.cancel();
+            *current = token.clone();
+        }
+        let guard = self.running.lock().await;
+        (token, guard)
+    }
+
+    /// Wait for the command in flight to finish; none starts until the guard is dropped
+    pub async fn idle(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.running.lock().await
+    }
+
+    /// Wait for our turn to ask a confirmation
+    pub async fn confirmation_turn(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.confirming.lock().await
+    }
+
+    /// Start waiting for the user's answer (voice or copilot button)
+    pub fn await_confirmation(&self, persona: Persona) -> tokio::sync::oneshot::Receiver<bool> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        *self.confirmation.lock().unwrap() = Some(tx);
+        let _ = self.confirmation_tx.send(persona);
+        rx
+    }
+
+    pub fn confirmation_pending(&self) -> bool {
+        self.confirmation
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|tx| !tx.is_closed())
+            .unwrap_or(false)
+    }
+
+    /// Answer the pending confirmation; false if none is waiting
+    pub fn answer_confirmation(&self, confirmed: bool) -> bool {
+        match self.confirmation.lock().unwrap().take() {
+            Some(tx) => tx.send(confirmed).is_ok(),
+            None => false,
+        }
+    }
+
+    pub fn confirmation_requests(&self) -> &crossbeam_channel::Receiver<Persona> {
+        &self.confirmation_rx
     }
 }
 
@@ -66,8 +184,78 @@ pub struct Settings {
     pub mic_device: Option<String>,
     pub system_audio_device: Option<String>,
     pub anthropic_api_key: Option<String>,
+    /// LLM backend (Anthropic or an OpenAI-compatible server), model and limits
+    #[serde(default)]
+    pub llm: LlmConfig,
+    /// Price tables and optional monthly budget for LLM usage
+    #[serde(default)]
+    pub usage: UsageSettings,
+    /// How long recordings are chunked for map-reduce summarization
+    #[serde(default)]
+    pub summary: SummaryConfig,
     #[serde(default)]
     pub mcp_servers: Vec<McpServerConfig>,
+    /// Gate streaming Whisper behind the MFCC/DTW keyword spotter (needs enrolled samples)
+    #[serde(default)]
+    pub keyword_spotting_enabled: bool,
+    /// Global shortcut held to speak a command without the wake word (empty = disabled)
+    #[serde(default = "default_push_to_talk_shortcut")]
+    pub push_to_talk_shortcut: String,
+    /// Time after a response during which a reply needs no wake word (0 = disabled)
+    #[serde(default = "default_follow_up_window_ms")]
+    pub follow_up_window_ms: u64,
+    /// The follow-up window ends early after this much silence
+    #[serde(default = "default_follow_up_silence_ms")]
+    pub follow_up_silence_ms: u64,
+    /// Assistant personas, each bound to its own wake words (empty = built-in Robert)
+    #[serde(default)]
+    pub personas: Vec<Persona>,
+    /// A new conversation starts after this long without commands (0 = never)
+    #[serde(default = "default_conversation_idle_timeout_minutes")]
+    pub conversation_idle_timeout_minutes: u64,
+    /// Estimated tokens of history sent with each command; older turns are summarized (0 = no limit)
+    #[serde(default = "default_history_token_budget")]
+    pub history_token_budget: usize,
+    /// Run common commands (start/stop/list recordings) locally, without the LLM
+    #[serde(default = "default_true")]
+    pub local_intents_enabled: bool,
+    /// Policy per tool name or `prefix*` pattern (safe, confirm or forbidden),
+    /// overriding the defaults (delete_recording and quit need a confirmation)
+    #[serde(default)]
+    pub tool_policies: HashMap<String, ToolPolicy>,
+    /// System prompt template of the built-in persona, with `{{variables}}` (None = default)
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// How the assistant should call the user (`{{user_name}}`)
+    #[serde(default)]
+    pub user_name: Option<String>,
+    /// Locale given to the assistant (`{{locale}}`, None = from the system)
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+fn default_push_to_talk_shortcut() -> String {
+    "Alt+Space".to_string()
+}
+
+fn default_follow_up_window_ms() -> u64 {
+    8000
+}
+
+fn default_follow_up_silence_ms() -> u64 {
+    3000
+}
+
+fn default_conversation_idle_timeout_minutes() -> u64 {
+    30
+}
+
+fn default_history_token_budget() -> usize {
+    8000
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for Settings {
@@ -80,7 +268,22 @@ impl Default for Settings {
             mic_device: None,
             system_audio_device: None,
             anthropic_api_key: None,
+            llm: LlmConfig::default(),
+            usage: UsageSettings::default(),
+            summary: SummaryConfig::default(),
             mcp_servers: Vec::new(),
+            keyword_spotting_enabled: false,
+            push_to_talk_shortcut: default_push_to_talk_shortcut(),
+            follow_up_window_ms: default_follow_up_window_ms(),
+            follow_up_silence_ms: default_follow_up_silence_ms(),
+            personas: Vec::new(),
+            conversation_idle_timeout_minutes: default_conversation_idle_timeout_minutes(),
+            history_token_budget: default_history_token_budget(),
+            local_intents_enabled: true,
+            tool_policies: HashMap::new(),
+            system_prompt: None,
+            user_name: None,
+            locale: None,
         }
     }
 }
@@ -97,6 +300,19 @@ impl Settings {
         serde_json::from_str(&content).ok()
     }
 
+    /// Configured personas, or the built-in Robert persona if none are set
+    pub fn resolved_personas(&self) -> Vec<Persona> {
+        if self.personas.is_empty() {
+            let mut persona = Persona::default_persona(&self.wake_words);
+            if let Some(template) = self.system_prompt.as_ref().filter(|t| !t.trim().is_empty()) {
+                persona.system_prompt = template.clone();
+            }
+            vec![persona]
+        } else {
+            self.personas.clone()
+        }
+    }
+
     pub fn save(&self) -> Result<(), String> {
         let path = Self::settings_path().ok_or("Could not determine settings path")?;
 
@@ -2,10 +2,19 @@ use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use directories::ProjectDirs;
 use rusqlite::{params, Connection};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-use super::models::{AudioSource, Recording, Transcription};
+use super::models::{
+    ActionItem, AudioSource, Conversation, ConversationMessage, MeetingNotes, Memory, Note, Recording,
+    RecordingActionItem, Reminder, ReminderKind, Todo, TranscriptFilter, TranscriptMatch, Transcription, UsagePeriod, UsageRecord, UsageTotals,
+};
+
+/// Kinds of single-text meeting note items
+const NOTE_DECISION: &str = "decision";
+const NOTE_OPEN_QUESTION: &str = "open_question";
+const NOTE_TOPIC: &str = "topic";
+const NOTE_PERSON: &str = "person";
 
 pub struct Database {
     conn: Connection,
@@ -17,12 +26,22 @@ impl Database {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        let conn = Connection::open(&path)?;
+        Self::open(&path)
+    }
+
+    /// Open (and migrate) the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
         let db = Self { conn };
         db.run_migrations()?;
         Ok(db)
     }
 
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Self> {
+        Self::open(Path::new(":memory:"))
+    }
+
     fn db_path() -> Result<PathBuf> {
         let dirs = ProjectDirs::from("com", "robert", "Robert")
             .ok_or_else(|| anyhow!("Could not find app directories"))?;
@@ -51,8 +70,163 @@ impl Database {
 
             CREATE INDEX IF NOT EXISTS idx_transcriptions_recording
             ON transcriptions(recording_id);
+
+            CREATE TABLE IF NOT EXISTS usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                command TEXT NOT NULL,
+                model TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_read_tokens INTEGER NOT NULL DEFAULT 0
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_usage_timestamp
+            ON usage(timestamp);
+
+            CREATE TABLE IF NOT EXISTS conversations (
+                id TEXT PRIMARY KEY,
+                persona_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS conversation_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                conversation_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (conversation_id) REFERENCES conversations(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_conversation_messages_conversation
+            ON conversation_messages(conversation_id);
+
+            CREATE TABLE IF NOT EXISTS summary_chunks (
+                key TEXT PRIMARY KEY,
+                recording_id TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_summary_chunks_recording
+            ON summary_chunks(recording_id);
+
+            CREATE TABLE IF NOT EXISTS meeting_notes (
+                recording_id TEXT PRIMARY KEY,
+                model TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS meeting_note_items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recording_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                text TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_meeting_note_items_recording
+            ON meeting_note_items(recording_id);
+
+            CREATE TABLE IF NOT EXISTS action_items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recording_id TEXT NOT NULL,
+                description TEXT NOT NULL,
+                owner TEXT,
+                due_date TEXT,
+                position INTEGER NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_action_items_recording
+            ON action_items(recording_id);
+
+            CREATE TABLE IF NOT EXISTS memories (
+                id TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                keywords TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS notes (
+                id TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS todos (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                done INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                completed_at TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS reminders (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                message TEXT NOT NULL,
+                due_at TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                fired_at TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_reminders_due
+            ON reminders(due_at);
+
+            -- Full-text index of transcriptions (rowid = transcriptions.rowid), kept in
+            -- sync by the triggers below; the recording name is indexed too
+            CREATE VIRTUAL TABLE IF NOT EXISTS transcriptions_fts USING fts5(
+                text,
+                recording_name,
+                recording_id UNINDEXED,
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
+
+            CREATE TRIGGER IF NOT EXISTS transcriptions_fts_insert AFTER INSERT ON transcriptions BEGIN
+                INSERT INTO transcriptions_fts (rowid, text, recording_name, recording_id)
+                VALUES (
+                    new.rowid,
+                    new.text,
+                    COALESCE((SELECT name FROM recordings WHERE id = new.recording_id), ''),
+                    new.recording_id
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS transcriptions_fts_update AFTER UPDATE OF text ON transcriptions BEGIN
+                UPDATE transcriptions_fts SET text = new.text WHERE rowid = new.rowid;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS transcriptions_fts_delete AFTER DELETE ON transcriptions BEGIN
+                DELETE FROM transcriptions_fts WHERE rowid = old.rowid;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS recordings_fts_rename AFTER UPDATE OF name ON recordings BEGIN
+                UPDATE transcriptions_fts SET recording_name = new.name WHERE recording_id = new.id;
+            END;
             "#,
         )?;
+
+        // Index transcriptions stored before the full-text index existed
+        let indexed: i64 = self.conn.query_row("SELECT COUNT(*) FROM transcriptions_fts", [], |row| row.get(0))?;
+        let stored: i64 = self.conn.query_row("SELECT COUNT(*) FROM transcriptions", [], |row| row.get(0))?;
+        if indexed != stored {
+            self.conn.execute_batch(
+                "DELETE FROM transcriptions_fts;
+                 INSERT INTO transcriptions_fts (rowid, text, recording_name, recording_id)
+                 SELECT t.rowid, t.text, COALESCE(r.name, ''), t.recording_id
+                 FROM transcriptions t LEFT JOIN recordings r ON r.id = t.recording_id;",
+            )?;
+        }
         Ok(())
     }
 
@@ -116,12 +290,23 @@ impl Database {
     }
 
     pub fn list_recordings(&self) -> Result<Vec<Recording>> {
+        self.list_recordings_between(None, None)
+    }
+
+    /// Recordings started in [`since`, `until`), most recent first
+    pub fn list_recordings_between(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Recording>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, created_at, ended_at, is_active FROM recordings ORDER BY created_at DESC",
+            "SELECT id, name, created_at, ended_at, is_active FROM recordings
+             WHERE (?1 IS NULL OR created_at >= ?1) AND (?2 IS NULL OR created_at < ?2)
+             ORDER BY created_at DESC",
         )?;
 
         let recordings = stmt
-            .query_map([], |row| {
+            .query_map(params![since.map(|t| t.to_rfc3339()), until.map(|t| t.to_rfc3339())], |row| {
                 let id: String = row.get(0)?;
                 let name: String = row.get(1)?;
                 let created_at: String = row.get(2)?;
@@ -254,6 +439,11 @@ impl Database {
             "DELETE FROM transcriptions WHERE recording_id = ?1",
             [id.to_string()],
         )?;
+        self.conn.execute(
+            "DELETE FROM summary_chunks WHERE recording_id = ?1",
+            [id.to_string()],
+        )?;
+        self.delete_meeting_notes(id)?;
         self.conn.execute(
             "DELETE FROM recordings WHERE id = ?1",
             [id.to_string()],
@@ -261,6 +451,732 @@ impl Database {
         Ok(())
     }
 
+    pub fn add_usage(&self, record: &UsageRecord) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO usage (timestamp, command, model, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                record.timestamp.to_rfc3339(),
+                record.command,
+                record.model,
+                record.input_tokens as i64,
+                record.output_tokens as i64,
+                record.cache_creation_tokens as i64,
+                record.cache_read_tokens as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Usage per period and model since `since` (periods are UTC days or months)
+    pub fn get_usage_totals(&self, period: UsagePeriod, since: DateTime<Utc>) -> Result<Vec<UsageTotals>> {
+        // RFC 3339 timestamps: "YYYY-MM-DD" is the first 10 chars, "YYYY-MM" the first 7
+        let prefix_len = match period {
+            UsagePeriod::Day => 10,
+            UsagePeriod::Month => 7,
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT substr(timestamp, 1, ?1) AS period, model, COUNT(*),
+                    SUM(input_tokens), SUM(output_tokens), SUM(cache_creation_tokens), SUM(cache_read_tokens)
+             FROM usage WHERE timestamp >= ?2
+             GROUP BY period, model ORDER BY period DESC, model ASC",
+        )?;
+
+        let totals = stmt
+            .query_map(params![prefix_len, since.to_rfc3339()], |row| {
+                Ok(UsageTotals {
+                    period: row.get(0)?,
+                    model: row.get(1)?,
+                    calls: row.get::<_, i64>(2)? as u64,
+                    input_tokens: row.get::<_, i64>(3)? as u64,
+                    output_tokens: row.get::<_, i64>(4)? as u64,
+                    cache_creation_tokens: row.get::<_, i64>(5)? as u64,
+                    cache_read_tokens: row.get::<_, i64>(6)? as u64,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(totals)
+    }
+
+    pub fn create_conversation(&self, persona_id: &str, title: &str) -> Result<Conversation> {
+        let now = Utc::now();
+        let conversation = Conversation {
+            id: Uuid::new_v4(),
+            persona_id: persona_id.to_string(),
+            title: title.to_string(),
+            created_at: now,
+            updated_at: now,
+            message_count: 0,
+        };
+
+        self.conn.execute(
+            "INSERT INTO conversations (id, persona_id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                conversation.id.to_string(),
+                conversation.persona_id,
+                conversation.title,
+                conversation.created_at.to_rfc3339(),
+                conversation.updated_at.to_rfc3339()
+            ],
+        )?;
+
+        Ok(conversation)
+    }
+
+    /// Append a message and mark the conversation as updated
+    pub fn add_conversation_message(
+        &self,
+        conversation_id: Uuid,
+        role: &str,
+        content: &serde_json::Value,
+    ) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            "INSERT INTO conversation_messages (conversation_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![conversation_id.to_string(), role, content.to_string(), now],
+        )?;
+        self.conn.execute(
+            "UPDATE conversations SET updated_at = ?1 WHERE id = ?2",
+            params![now, conversation_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    pub fn list_conversations(&self) -> Result<Vec<Conversation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT c.id, c.persona_id, c.title, c.created_at, c.updated_at, COUNT(m.id)
+             FROM conversations c LEFT JOIN conversation_messages m ON m.conversation_id = c.id
+             GROUP BY c.id ORDER BY c.updated_at DESC",
+        )?;
+
+        let conversations = stmt
+            .query_map([], Self::row_to_conversation)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(conversations)
+    }
+
+    pub fn get_conversation(&self, id: Uuid) -> Result<Option<Conversation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT c.id, c.persona_id, c.title, c.created_at, c.updated_at, COUNT(m.id)
+             FROM conversations c LEFT JOIN conversation_messages m ON m.conversation_id = c.id
+             WHERE c.id = ?1 GROUP BY c.id",
+        )?;
+
+        let mut rows = stmt.query_map([id.to_string()], Self::row_to_conversation)?;
+        Ok(rows.next().transpose()?)
+    }
+
+    /// Most recently updated conversation of a persona
+    pub fn get_latest_conversation(&self, persona_id: &str) -> Result<Option<Conversation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT c.id, c.persona_id, c.title, c.created_at, c.updated_at, COUNT(m.id)
+             FROM conversations c LEFT JOIN conversation_messages m ON m.conversation_id = c.id
+             WHERE c.persona_id = ?1 GROUP BY c.id ORDER BY c.updated_at DESC LIMIT 1",
+        )?;
+
+        let mut rows = stmt.query_map([persona_id], Self::row_to_conversation)?;
+        Ok(rows.next().transpose()?)
+    }
+
+    fn row_to_conversation(row: &rusqlite::Row) -> rusqlite::Result<Conversation> {
+        let id: String = row.get(0)?;
+        let created_at: String = row.get(3)?;
+        let updated_at: String = row.get(4)?;
+
+        Ok(Conversation {
+            id: Uuid::parse_str(&id).unwrap_or_default(),
+            persona_id: row.get(1)?,
+            title: row.get(2)?,
+            created_at: DateTime::parse_from_rfc3339(&created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: DateTime::parse_from_rfc3339(&updated_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            message_count: row.get::<_, i64>(5)? as u32,
+        })
+    }
+
+    pub fn get_conversation_messages(&self, conversation_id: Uuid) -> Result<Vec<ConversationMessage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, conversation_id, role, content, created_at FROM conversation_messages
+             WHERE conversation_id = ?1 ORDER BY id ASC",
+        )?;
+
+        let messages = stmt
+            .query_map([conversation_id.to_string()], |row| {
+                let conversation_id: String = row.get(1)?;
+                let content: String = row.get(3)?;
+                let created_at: String = row.get(4)?;
+
+                Ok(ConversationMessage {
+                    id: row.get(0)?,
+                    conversation_id: Uuid::parse_str(&conversation_id).unwrap_or_default(),
+                    role: row.get(2)?,
+                    content: serde_json::from_str(&content).unwrap_or(serde_json::Value::Null),
+                    created_at: DateTime::parse_from_rfc3339(&created_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(messages)
+    }
+
+    pub fn delete_conversation(&self, id: Uuid) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM conversation_messages WHERE conversation_id = ?1",
+            [id.to_string()],
+        )?;
+        self.conn.execute(
+            "DELETE FROM conversations WHERE id = ?1",
+            [id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Cached summary of a transcription chunk (map-reduce summarization)
+    pub fn get_chunk_summary(&self, key: &str) -> Result<Option<String>> {
+        let mut stmt = self.conn.prepare("SELECT summary FROM summary_chunks WHERE key = ?1")?;
+        let mut rows = stmt.query([key])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(row.get(0)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn put_chunk_summary(&self, recording_id: Uuid, key: &str, summary: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO summary_chunks (key, recording_id, summary, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![key, recording_id.to_string(), summary, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Drop a recording's chunk summaries whose chunk no longer exists
+    pub fn retain_chunk_summaries(&self, recording_id: Uuid, keys: &[String]) -> Result<()> {
+        let mut stmt = self.conn.prepare("SELECT key FROM summary_chunks WHERE recording_id = ?1")?;
+        let stale: Vec<String> = stmt
+            .query_map([recording_id.to_string()], |row| row.get::<_, String>(0))?
+            .filter_map(|k| k.ok())
+            .filter(|k| !keys.contains(k))
+            .collect();
+        for key in stale {
+            self.conn.execute("DELETE FROM summary_chunks WHERE key = ?1", [key])?;
+        }
+        Ok(())
+    }
+
+    /// Replace the meeting notes of a recording
+    pub fn save_meeting_notes(&self, notes: &MeetingNotes) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.delete_meeting_notes(notes.recording_id)?;
+
+        let recording_id = notes.recording_id.to_string();
+        self.conn.execute(
+            "INSERT INTO meeting_notes (recording_id, model, created_at) VALUES (?1, ?2, ?3)",
+            params![recording_id, notes.model, notes.created_at.to_rfc3339()],
+        )?;
+
+        let items = [
+            (NOTE_DECISION, &notes.decisions),
+            (NOTE_OPEN_QUESTION, &notes.open_questions),
+            (NOTE_TOPIC, &notes.topics),
+            (NOTE_PERSON, &notes.people),
+        ];
+        for (kind, texts) in items {
+            for (position, text) in texts.iter().enumerate() {
+                self.conn.execute(
+                    "INSERT INTO meeting_note_items (recording_id, kind, text, position) VALUES (?1, ?2, ?3, ?4)",
+                    params![recording_id, kind, text, position as i64],
+                )?;
+            }
+        }
+
+        for (position, item) in notes.action_items.iter().enumerate() {
+            self.conn.execute(
+                "INSERT INTO action_items (recording_id, description, owner, due_date, position)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![recording_id, item.description, item.owner, item.due_date, position as i64],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_meeting_notes(&self, recording_id: Uuid) -> Result<Option<MeetingNotes>> {
+        let id = recording_id.to_string();
+        let mut stmt = self.conn.prepare("SELECT model, created_at FROM meeting_notes WHERE recording_id = ?1")?;
+        let mut rows = stmt.query([&id])?;
+        let (model, created_at): (String, String) = match rows.next()? {
+            Some(row) => (row.get(0)?, row.get(1)?),
+            None => return Ok(None),
+        };
+
+        let mut notes = MeetingNotes {
+            recording_id,
+            decisions: Vec::new(),
+            action_items: Vec::new(),
+            open_questions: Vec::new(),
+            topics: Vec::new(),
+            people: Vec::new(),
+            model,
+            created_at: DateTime::parse_from_rfc3339(&created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT kind, text FROM meeting_note_items WHERE recording_id = ?1 ORDER BY kind, position",
+        )?;
+        let items = stmt
+            .query_map([&id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .filter_map(|r| r.ok());
+        for (kind, text) in items {
+            match kind.as_str() {
+                NOTE_DECISION => notes.decisions.push(text),
+                NOTE_OPEN_QUESTION => notes.open_questions.push(text),
+                NOTE_TOPIC => notes.topics.push(text),
+                NOTE_PERSON => notes.people.push(text),
+                _ => {}
+            }
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT description, owner, due_date FROM action_items WHERE recording_id = ?1 ORDER BY position",
+        )?;
+        notes.action_items = stmt
+            .query_map([&id], |row| {
+                Ok(ActionItem {
+                    description: row.get(0)?,
+                    owner: row.get(1)?,
+                    due_date: row.get(2)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(Some(notes))
+    }
+
+    pub fn delete_meeting_notes(&self, recording_id: Uuid) -> Result<()> {
+        let id = recording_id.to_string();
+        self.conn.execute("DELETE FROM action_items WHERE recording_id = ?1", [&id])?;
+        self.conn.execute("DELETE FROM meeting_note_items WHERE recording_id = ?1", [&id])?;
+        self.conn.execute("DELETE FROM meeting_notes WHERE recording_id = ?1", [&id])?;
+        Ok(())
+    }
+
+    /// Action items of all recordings, most recent recording first.
+    /// `owner` matches case-insensitively on part of the name, `since` and `until`
+    /// bound when the recording started.
+    pub fn list_action_items(
+        &self,
+        owner: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<Vec<RecordingActionItem>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.recording_id, r.name, a.description, a.owner, a.due_date
+             FROM action_items a JOIN recordings r ON r.id = a.recording_id
+             WHERE (?1 IS NULL OR a.owner LIKE '%' || ?1 || '%')
+               AND (?2 IS NULL OR r.created_at >= ?2)
+               AND (?3 IS NULL OR r.created_at < ?3)
+             ORDER BY r.created_at DESC, a.position",
+        )?;
+
+        let items = stmt
+            .query_map(params![owner, since.map(|t| t.to_rfc3339()), until.map(|t| t.to_rfc3339())], |row| {
+                let recording_id: String = row.get(0)?;
+                Ok(RecordingActionItem {
+                    recording_id: Uuid::parse_str(&recording_id).unwrap_or_default(),
+                    recording_name: row.get(1)?,
+                    item: ActionItem {
+                        description: row.get(2)?,
+                        owner: row.get(3)?,
+                        due_date: row.get(4)?,
+                    },
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(items)
+    }
+
+    /// `keywords` are the normalized words searched by `search_memories`
+    pub fn add_memory(&self, content: &str, keywords: &[String]) -> Result<Memory> {
+        let now = Utc::now();
+        let memory = Memory {
+            id: Uuid::new_v4(),
+            content: content.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.conn.execute(
+            "INSERT INTO memories (id, content, keywords, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                memory.id.to_string(),
+                memory.content,
+                keywords.join(" "),
+                memory.created_at.to_rfc3339(),
+                memory.updated_at.to_rfc3339()
+            ],
+        )?;
+
+        Ok(memory)
+    }
+
+    /// Returns false if no memory has this id
+    pub fn update_memory(&self, id: Uuid, content: &str, keywords: &[String]) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE memories SET content = ?1, keywords = ?2, updated_at = ?3 WHERE id = ?4",
+            params![content, keywords.join(" "), Utc::now().to_rfc3339(), id.to_string()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Returns false if no memory has this id
+    pub fn delete_memory(&self, id: Uuid) -> Result<bool> {
+        let deleted = self.conn.execute("DELETE FROM memories WHERE id = ?1", [id.to_string()])?;
+        Ok(deleted > 0)
+    }
+
+    pub fn get_memory(&self, id: Uuid) -> Result<Option<Memory>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, content, created_at, updated_at FROM memories WHERE id = ?1")?;
+        let mut rows = stmt.query_map([id.to_string()], Self::row_to_memory)?;
+        Ok(rows.next().and_then(|r| r.ok()))
+    }
+
+    /// Most recently updated first
+    pub fn list_memories(&self) -> Result<Vec<Memory>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, content, created_at, updated_at FROM memories ORDER BY updated_at DESC")?;
+
+        let memories = stmt
+            .query_map([], Self::row_to_memory)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(memories)
+    }
+
+    /// Memories sharing at least one keyword (or keyword prefix), most keywords in common first
+    pub fn search_memories(&self, keywords: &[String], limit: usize) -> Result<Vec<Memory>> {
+        if keywords.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // One "word starts with keyword" test per keyword, summed into a score
+        let score = (1..=keywords.len())
+            .map(|i| format!("((' ' || keywords) LIKE '% ' || ?{} || '%')", i))
+            .collect::<Vec<_>>()
+            .join(" + ");
+        let sql = format!(
+            "SELECT id, content, created_at, updated_at, {score} AS score FROM memories
+             WHERE score > 0 ORDER BY score DESC, updated_at DESC LIMIT {limit}",
+            score = score,
+            limit = limit
+        );
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let memories = stmt
+            .query_map(rusqlite::params_from_iter(keywords), Self::row_to_memory)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(memories)
+    }
+
+    pub fn add_note(&self, content: &str) -> Result<Note> {
+        let now = Utc::now();
+        let note = Note {
+            id: Uuid::new_v4(),
+            content: content.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.conn.execute(
+            "INSERT INTO notes (id, content, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![note.id.to_string(), note.content, note.created_at.to_rfc3339(), note.updated_at.to_rfc3339()],
+        )?;
+
+        Ok(note)
+    }
+
+    /// Returns false if no note has this id
+    pub fn update_note(&self, id: Uuid, content: &str) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE notes SET content = ?1, updated_at = ?2 WHERE id = ?3",
+            params![content, Utc::now().to_rfc3339(), id.to_string()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Returns false if no note has this id
+    pub fn delete_note(&self, id: Uuid) -> Result<bool> {
+        let deleted = self.conn.execute("DELETE FROM notes WHERE id = ?1", [id.to_string()])?;
+        Ok(deleted > 0)
+    }
+
+    /// Most recent first
+    pub fn list_notes(&self) -> Result<Vec<Note>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, content, created_at, updated_at FROM notes ORDER BY created_at DESC")?;
+
+        let notes = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let created_at: String = row.get(2)?;
+                let updated_at: String = row.get(3)?;
+
+                Ok(Note {
+                    id: Uuid::parse_str(&id).unwrap_or_default(),
+                    content: row.get(1)?,
+                    created_at: DateTime::parse_from_rfc3339(&created_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                    updated_at: DateTime::parse_from_rfc3339(&updated_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(notes)
+    }
+
+    pub fn add_todo(&self, title: &str) -> Result<Todo> {
+        let todo = Todo {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            done: false,
+            created_at: Utc::now(),
+            completed_at: None,
+        };
+
+        self.conn.execute(
+            "INSERT INTO todos (id, title, created_at) VALUES (?1, ?2, ?3)",
+            params![todo.id.to_string(), todo.title, todo.created_at.to_rfc3339()],
+        )?;
+
+        Ok(todo)
+    }
+
+    /// Returns false if no to-do has this id
+    pub fn update_todo(&self, id: Uuid, title: &str) -> Result<bool> {
+        let updated = self
+            .conn
+            .execute("UPDATE todos SET title = ?1 WHERE id = ?2", params![title, id.to_string()])?;
+        Ok(updated > 0)
+    }
+
+    /// Mark a to-do done (or open again). Returns false if no to-do has this id
+    pub fn set_todo_done(&self, id: Uuid, done: bool) -> Result<bool> {
+        let completed_at = done.then(|| Utc::now().to_rfc3339());
+        let updated = self.conn.execute(
+            "UPDATE todos SET done = ?1, completed_at = ?2 WHERE id = ?3",
+            params![done as i32, completed_at, id.to_string()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Returns false if no to-do has this id
+    pub fn delete_todo(&self, id: Uuid) -> Result<bool> {
+        let deleted = self.conn.execute("DELETE FROM todos WHERE id = ?1", [id.to_string()])?;
+        Ok(deleted > 0)
+    }
+
+    /// Open to-dos oldest first, then (with `include_done`) the done ones, last completed first
+    pub fn list_todos(&self, include_done: bool) -> Result<Vec<Todo>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, done, created_at, completed_at FROM todos
+             WHERE ?1 OR done = 0
+             ORDER BY done, completed_at DESC, created_at",
+        )?;
+
+        let todos = stmt
+            .query_map([include_done], |row| {
+                let id: String = row.get(0)?;
+                let done: i32 = row.get(2)?;
+                let created_at: String = row.get(3)?;
+                let completed_at: Option<String> = row.get(4)?;
+
+                Ok(Todo {
+                    id: Uuid::parse_str(&id).unwrap_or_default(),
+                    title: row.get(1)?,
+                    done: done != 0,
+                    created_at: DateTime::parse_from_rfc3339(&created_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                    completed_at: completed_at.and_then(|s| {
+                        DateTime::parse_from_rfc3339(&s)
+                            .map(|dt| dt.with_timezone(&Utc))
+                            .ok()
+                    }),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(todos)
+    }
+
+    pub fn add_reminder(&self, kind: ReminderKind, message: &str, due_at: DateTime<Utc>) -> Result<Reminder> {
+        let reminder = Reminder {
+            id: Uuid::new_v4(),
+            kind,
+            message: message.to_string(),
+            due_at,
+            created_at: Utc::now(),
+            fired_at: None,
+        };
+
+        self.conn.execute(
+            "INSERT INTO reminders (id, kind, message, due_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                reminder.id.to_string(),
+                reminder.kind.as_str(),
+                reminder.message,
+                reminder.due_at.to_rfc3339(),
+                reminder.created_at.to_rfc3339()
+            ],
+        )?;
+
+        Ok(reminder)
+    }
+
+    /// Reminders that haven't fired yet, soonest first
+    pub fn list_pending_reminders(&self) -> Result<Vec<Reminder>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, kind, message, due_at, created_at, fired_at FROM reminders
+             WHERE fired_at IS NULL ORDER BY due_at",
+        )?;
+
+        let reminders = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let kind: String = row.get(1)?;
+                let due_at: String = row.get(3)?;
+                let created_at: String = row.get(4)?;
+                let fired_at: Option<String> = row.get(5)?;
+
+                Ok(Reminder {
+                    id: Uuid::parse_str(&id).unwrap_or_default(),
+                    kind: ReminderKind::from_str(&kind),
+                    message: row.get(2)?,
+                    due_at: DateTime::parse_from_rfc3339(&due_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                    created_at: DateTime::parse_from_rfc3339(&created_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                    fired_at: fired_at.and_then(|s| {
+                        DateTime::parse_from_rfc3339(&s)
+                            .map(|dt| dt.with_timezone(&Utc))
+                            .ok()
+                    }),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(reminders)
+    }
+
+    pub fn mark_reminder_fired(&self, id: Uuid) -> Result<()> {
+        self.conn.execute(
+            "UPDATE reminders SET fired_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Returns false if no reminder has this id
+    pub fn delete_reminder(&self, id: Uuid) -> Result<bool> {
+        let deleted = self.conn.execute("DELETE FROM reminders WHERE id = ?1", [id.to_string()])?;
+        Ok(deleted > 0)
+    }
+
+    fn row_to_memory(row: &rusqlite::Row) -> rusqlite::Result<Memory> {
+        let id: String = row.get(0)?;
+        let created_at: String = row.get(2)?;
+        let updated_at: String = row.get(3)?;
+
+        Ok(Memory {
+            id: Uuid::parse_str(&id).unwrap_or_default(),
+            content: row.get(1)?,
+            created_at: DateTime::parse_from_rfc3339(&created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: DateTime::parse_from_rfc3339(&updated_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+
+    /// Transcription segments matching an FTS5 query, best match first
+    pub fn search_transcripts(&self, fts_query: &str, filter: &TranscriptFilter, limit: usize) -> Result<Vec<TranscriptMatch>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.recording_id, r.name, t.id, t.timestamp,
+                    snippet(transcriptions_fts, 0, '**', '**', '…', 16)
+             FROM transcriptions_fts f
+             JOIN transcriptions t ON t.rowid = f.rowid
+             JOIN recordings r ON r.id = t.recording_id
+             WHERE transcriptions_fts MATCH ?1
+               AND (?2 IS NULL OR t.recording_id = ?2)
+               AND (?3 IS NULL OR t.timestamp >= ?3)
+               AND (?4 IS NULL OR t.timestamp < ?4)
+             ORDER BY bm25(transcriptions_fts, 1.0, 0.3)
+             LIMIT ?5",
+        )?;
+
+        let matches = stmt
+            .query_map(
+                params![
+                    fts_query,
+                    filter.recording_id.map(|id| id.to_string()),
+                    filter.since.map(|t| t.to_rfc3339()),
+                    filter.until.map(|t| t.to_rfc3339()),
+                    limit as i64
+                ],
+                |row| {
+                    let recording_id: String = row.get(0)?;
+                    let transcription_id: String = row.get(2)?;
+                    let timestamp: String = row.get(3)?;
+
+                    Ok(TranscriptMatch {
+                        recording_id: Uuid::parse_str(&recording_id).unwrap_or_default(),
+                        recording_name: row.get(1)?,
+                        transcription_id: Uuid::parse_str(&transcription_id).unwrap_or_default(),
+                        timestamp: DateTime::parse_from_rfc3339(&timestamp)
+                            .map(|dt| dt.with_timezone(&Utc))
+                            .unwrap_or_else(|_| Utc::now()),
+                        snippet: row.get(4)?,
+                    })
+                },
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(matches)
+    }
+
     pub fn get_full_transcription_text(&self, recording_id: Uuid) -> Result<String> {
         let transcriptions = self.get_transcriptions(recording_id)?;
         let text = transcriptions
@@ -271,3 +1187,165 @@ impl Database {
         Ok(text)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_search_memories_ranks_by_keywords_in_common() {
+        let db = Database::in_memory().unwrap();
+        db.add_memory("Alice likes tea", &words("alice like tea")).unwrap();
+        db.add_memory("My manager is Alice", &words("manager alice")).unwrap();
+        db.add_memory("The weekly sync is Standup", &words("weekly sync standup")).unwrap();
+
+        let found = db.search_memories(&words("manager alice"), 10).unwrap();
+        let contents: Vec<&str> = found.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["My manager is Alice", "Alice likes tea"]);
+
+        // Keywords match word prefixes
+        let found = db.search_memories(&words("stand"), 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].content, "The weekly sync is Standup");
+
+        assert_eq!(db.search_memories(&words("manager alice"), 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_memories_without_match() {
+        let db = Database::in_memory().unwrap();
+        db.add_memory("My manager is Alice", &words("manager alice")).unwrap();
+
+        assert!(db.search_memories(&words("budget"), 10).unwrap().is_empty());
+        // Only at word starts: "ice" is not in "alice"
+        assert!(db.search_memories(&words("ice"), 10).unwrap().is_empty());
+        assert!(db.search_memories(&[], 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_transcript_index_follows_recordings() {
+        let db = Database::in_memory().unwrap();
+        let all = TranscriptFilter::default();
+        let budget = db.create_recording("Budget review").unwrap();
+        db.add_transcription(budget.id, "We agreed on a budget freeze until March", AudioSource::Microphone).unwrap();
+        let standup = db.create_recording("Standup").unwrap();
+        db.add_transcription(standup.id, "Le gel du budget a été annoncé", AudioSource::System).unwrap();
+
+        // Accent-insensitive, matched words in bold
+        let found = db.search_transcripts("\"freeze\"", &all, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].recording_id, budget.id);
+        assert_eq!(found[0].recording_name, "Budget review");
+        assert_eq!(found[0].snippet, "We agreed on a budget **freeze** until March");
+        let found = db.search_transcripts("\"ete\"", &all, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].snippet, "Le gel du budget a **été** annoncé");
+
+        let found = db.search_transcripts("\"budget\"", &all, 10).unwrap();
+        assert_eq!(found.len(), 2);
+        let only_standup = TranscriptFilter { recording_id: Some(standup.id), ..Default::default() };
+        assert_eq!(db.search_transcripts("\"budget\"", &only_standup, 10).unwrap().len(), 1);
+
+        // Renaming updates the indexed recording name
+        db.rename_recording(standup.id, "Weekly sync").unwrap();
+        let found = db.search_transcripts("\"weekly\"", &all, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].recording_name, "Weekly sync");
+        assert_eq!(found[0].snippet, "Le gel du budget a été annoncé");
+        assert!(db.search_transcripts("\"standup\"", &all, 10).unwrap().is_empty());
+
+        // Deleting removes its transcriptions from the index
+        db.delete_recording(budget.id).unwrap();
+        assert!(db.search_transcripts("\"freeze\"", &all, 10).unwrap().is_empty());
+        let found = db.search_transcripts("\"budget\"", &all, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].recording_id, standup.id);
+    }
+
+    #[test]
+    fn test_pending_reminders_soonest_first() {
+        let db = Database::in_memory().unwrap();
+        let now = Utc::now();
+        db.add_reminder(ReminderKind::Reminder, "call Paul", now + chrono::Duration::hours(2)).unwrap();
+        let timer = db.add_reminder(ReminderKind::Timer, "tea", now + chrono::Duration::minutes(5)).unwrap();
+        db.add_reminder(ReminderKind::Reminder, "stand up", now + chrono::Duration::days(1)).unwrap();
+
+        let messages = |db: &Database| {
+            db.list_pending_reminders().unwrap().into_iter().map(|r| r.message).collect::<Vec<_>>()
+        };
+        assert_eq!(messages(&db), ["tea", "call Paul", "stand up"]);
+
+        db.mark_reminder_fired(timer.id).unwrap();
+        assert_eq!(messages(&db), ["call Paul", "stand up"]);
+    }
+
+    #[test]
+    fn test_pending_reminders_survive_reopen() {
+        let path = std::env::temp_dir().join(format!("robert-{}.db", Uuid::new_v4()));
+        let due_at = Utc::now() + chrono::Duration::minutes(20);
+        let (fired, pending) = {
+            let db = Database::open(&path).unwrap();
+            let fired = db.add_reminder(ReminderKind::Timer, "pasta", due_at).unwrap();
+            let pending = db.add_reminder(ReminderKind::Reminder, "join the call", due_at).unwrap();
+            db.mark_reminder_fired(fired.id).unwrap();
+            (fired, pending)
+        };
+
+        let db = Database::open(&path).unwrap();
+        let reminders = db.list_pending_reminders().unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].id, pending.id);
+        assert_eq!(reminders[0].kind, ReminderKind::Reminder);
+        assert_eq!(reminders[0].message, "join the call");
+        assert_eq!(reminders[0].due_at.timestamp(), due_at.timestamp());
+        assert!(reminders.iter().all(|r| r.id != fired.id));
+
+        drop(db);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_todos_filtering_and_order() {
+        let db = Database::in_memory().unwrap();
+        let first = db.add_todo("first").unwrap();
+        let second = db.add_todo("second").unwrap();
+        db.add_todo("third").unwrap();
+        let fourth = db.add_todo("fourth").unwrap();
+
+        let titles = |include_done| {
+            db.list_todos(include_done).unwrap().into_iter().map(|t| t.title).collect::<Vec<_>>()
+        };
+        assert_eq!(titles(false), ["first", "second", "third", "fourth"]);
+
+        // Open ones oldest first, then the done ones last completed first
+        db.set_todo_done(second.id, true).unwrap();
+        db.set_todo_done(first.id, true).unwrap();
+        assert_eq!(titles(false), ["third", "fourth"]);
+        assert_eq!(titles(true), ["third", "fourth", "first", "second"]);
+
+        db.delete_todo(fourth.id).unwrap();
+        assert_eq!(titles(true), ["third", "first", "second"]);
+    }
+
+    #[test]
+    fn test_set_todo_done() {
+        let db = Database::in_memory().unwrap();
+        let todo = db.add_todo("Review PR 42").unwrap();
+        assert!(!todo.done && todo.completed_at.is_none());
+
+        assert!(db.set_todo_done(todo.id, true).unwrap());
+        let done = db.list_todos(true).unwrap().remove(0);
+        assert!(done.done);
+        assert!(done.completed_at.is_some_and(|at| at >= todo.created_at));
+
+        assert!(db.set_todo_done(todo.id, false).unwrap());
+        let open = db.list_todos(false).unwrap().remove(0);
+        assert!(!open.done && open.completed_at.is_none());
+
+        assert!(!db.set_todo_done(Uuid::new_v4(), true).unwrap());
+    }
+}
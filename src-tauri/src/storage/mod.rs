@@ -2,4 +2,8 @@ mod database;
 mod models;
 
 pub use database::Database;
-pub use models::{AudioSource, Recording, Transcription};
+pub use models::{
+    ActionItem, AudioSource, Conversation, ConversationMessage, MeetingNotes, Memory, Note, Recording,
+    RecordingActionItem, Reminder, ReminderKind, Todo, TranscriptFilter, TranscriptMatch, Transcription, UsagePeriod,
+    UsageRecord,
+};
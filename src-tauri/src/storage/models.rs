@@ -20,6 +20,176 @@ pub struct Transcription {
     pub source: AudioSource,
 }
 
+/// Restricts a transcript search (dates are on the transcription timestamp)
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptFilter {
+    pub recording_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// A transcription segment matching a full-text search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptMatch {
+    pub recording_id: Uuid,
+    pub recording_name: String,
+    pub transcription_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    /// Text around the match, matched words in **bold**
+    pub snippet: String,
+}
+
+/// A conversation with one persona (a session of commands and responses)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: Uuid,
+    pub persona_id: String,
+    /// First command of the conversation
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub message_count: u32,
+}
+
+/// One message of a conversation; `content` is the JSON array of content blocks
+/// (text, tool_use, tool_result)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationMessage {
+    pub id: i64,
+    pub conversation_id: Uuid,
+    pub role: String,
+    pub content: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A fact the user asked the assistant to remember
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Memory {
+    pub id: Uuid,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A quick note taken by voice ("note that the deploy is Thursday")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Note {
+    pub id: Uuid,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An entry of the to-do list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Todo {
+    pub id: Uuid,
+    pub title: String,
+    pub done: bool,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A timer or reminder; pending until `fired_at` is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: Uuid,
+    pub kind: ReminderKind,
+    /// What to remind the user of, or the timer's label
+    pub message: String,
+    pub due_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub fired_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderKind {
+    Timer,
+    Reminder,
+}
+
+impl ReminderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderKind::Timer => "timer",
+            ReminderKind::Reminder => "reminder",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "timer" => ReminderKind::Timer,
+            _ => ReminderKind::Reminder,
+        }
+    }
+}
+
+/// Task agreed on in a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionItem {
+    pub description: String,
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// ISO date when one could be inferred, otherwise as spoken
+    #[serde(default)]
+    pub due_date: Option<String>,
+}
+
+/// Structured notes extracted from a recording's transcription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingNotes {
+    pub recording_id: Uuid,
+    pub decisions: Vec<String>,
+    pub action_items: Vec<ActionItem>,
+    pub open_questions: Vec<String>,
+    pub topics: Vec<String>,
+    pub people: Vec<String>,
+    /// Model that extracted the notes
+    pub model: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An action item with the recording it comes from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingActionItem {
+    pub recording_id: Uuid,
+    pub recording_name: String,
+    #[serde(flatten)]
+    pub item: ActionItem,
+}
+
+/// Token usage of one LLM API call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Command that triggered the call (spoken text)
+    pub command: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+}
+
+/// Usage summed over a period (day "YYYY-MM-DD" or month "YYYY-MM") for one model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub period: String,
+    pub model: String,
+    pub calls: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UsagePeriod {
+    Day,
+    Month,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum AudioSource {
     Microphone,
@@ -0,0 +1,45 @@
+use uuid::Uuid;
+
+use crate::llm::ChunkSummaryCache;
+use crate::DbState;
+
+/// Chunk summaries of one recording, stored in the database
+pub struct RecordingSummaryCache {
+    db: DbState,
+    recording_id: Uuid,
+}
+
+impl RecordingSummaryCache {
+    pub fn new(db: DbState, recording_id: Uuid) -> Self {
+        Self { db, recording_id }
+    }
+
+    /// Keys are scoped to the recording, so identical chunks of two recordings don't collide
+    fn storage_key(&self, key: &str) -> String {
+        format!("{}:{}", self.recording_id, key)
+    }
+}
+
+impl ChunkSummaryCache for RecordingSummaryCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let db = self.db.lock().ok()?;
+        db.get_chunk_summary(&self.storage_key(key)).ok().flatten()
+    }
+
+    fn put(&self, key: &str, summary: &str) {
+        if let Ok(db) = self.db.lock() {
+            if let Err(e) = db.put_chunk_summary(self.recording_id, &self.storage_key(key), summary) {
+                eprintln!("[Summary] Failed to cache chunk summary: {}", e);
+            }
+        }
+    }
+
+    fn retain(&self, keys: &[String]) {
+        let keys: Vec<String> = keys.iter().map(|k| self.storage_key(k)).collect();
+        if let Ok(db) = self.db.lock() {
+            if let Err(e) = db.retain_chunk_summaries(self.recording_id, &keys) {
+                eprintln!("[Summary] Failed to prune chunk summaries: {}", e);
+            }
+        }
+    }
+}
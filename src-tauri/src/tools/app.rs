@@ -0,0 +1,26 @@
+use crate::tools::{NoInput, Tool, ToolContext, ToolResult};
+use futures::future::BoxFuture;
+use serde_json::json;
+
+pub struct Quit;
+
+impl Tool for Quit {
+    type Input = NoInput;
+
+    fn name(&self) -> &'static str {
+        "quit"
+    }
+
+    fn description(&self) -> &'static str {
+        "Close and quit the Robert application"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({})]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, _input: NoInput) -> BoxFuture<'a, ToolResult> {
+        ctx.app_handle.exit(0);
+        Box::pin(async { ToolResult::Exit })
+    }
+}
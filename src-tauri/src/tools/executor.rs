@@ -1,13 +1,11 @@
-use crate::llm::summarize;
 use crate::mcp::McpManager;
 use crate::state::AppState;
-use crate::tools::ToolSource;
+use crate::tools::{confirmation_question, local_tools, tool_policy, Confirmer, ToolContext, ToolPolicy, ToolSource};
+use crate::usage::UsageRecorder;
 use crate::DbState;
-use chrono::Utc;
-use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 use tokio::sync::RwLock;
 
 pub enum ToolResult {
@@ -16,35 +14,15 @@ pub enum ToolResult {
     Exit,
 }
 
-#[derive(Deserialize)]
-struct RecordingSelector {
-    recording_name: Option<String>,
-    recording_index: Option<i32>,
-}
-
-#[derive(Deserialize)]
-struct StartRecordingInput {
-    name: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct RenameInput {
-    recording_name: Option<String>,
-    recording_index: Option<i32>,
-    new_name: String,
-}
-
 #[derive(Clone)]
 pub struct ToolExecutor {
-    app_handle: AppHandle,
-    state: Arc<RwLock<AppState>>,
-    db: Option<DbState>,
+    context: ToolContext,
     mcp_manager: Option<Arc<McpManager>>,
     routing: Arc<HashMap<String, ToolSource>>,
+    /// Asks the user before confirm-tagged tools (without it they are refused)
+    confirmer: Option<Arc<dyn Confirmer>>,
 }
 
-use crate::storage::Recording;
-
 impl ToolExecutor {
     pub fn new(
         app_handle: AppHandle,
@@ -54,55 +32,27 @@ impl ToolExecutor {
         routing: HashMap<String, ToolSource>,
     ) -> Self {
         Self {
-            app_handle,
-            state,
-            db,
+            context: ToolContext {
+                app_handle,
+                state,
+                db,
+                usage: None,
+            },
             mcp_manager,
             routing: Arc::new(routing),
+            confirmer: None,
         }
     }
 
-    /// Resolve a recording from either name or index
-    fn resolve_recording(
-        db: &crate::storage::Database,
-        name: Option<&str>,
-        index: Option<i32>,
-    ) -> Result<Recording, String> {
-        if let Some(name) = name {
-            match db.get_recording_by_name(name) {
-                Ok(Some(r)) => return Ok(r),
-                Ok(None) => return Err(format!("Recording '{}' not found", name)),
-                Err(e) => return Err(format!("Database error: {}", e)),
-            }
-        }
-
-        if let Some(idx) = index {
-            let recordings = db.list_recordings().map_err(|e| e.to_string())?;
-            if recordings.is_empty() {
-                return Err("No recordings found".to_string());
-            }
-
-            // Handle negative indices (-1 = last, -2 = second to last)
-            let actual_idx = if idx < 0 {
-                (recordings.len() as i32 + idx) as usize
-            } else if idx == 0 {
-                return Err("Index must be non-zero (1 = first, -1 = last)".to_string());
-            } else {
-                (idx - 1) as usize // Convert 1-based to 0-based
-            };
-
-            if actual_idx >= recordings.len() {
-                return Err(format!(
-                    "Index {} out of range. You have {} recording(s).",
-                    idx,
-                    recordings.len()
-                ));
-            }
-
-            return Ok(recordings[actual_idx].clone());
-        }
+    /// Account LLM calls made by tools (summaries) to the current command
+    pub fn with_usage_recorder(mut self, usage: Arc<dyn UsageRecorder>) -> Self {
+        self.context.usage = Some(usage);
+        self
+    }
 
-        Err("Please specify either a recording name or index (e.g., 'first', 'second', 'last')".to_string())
+    pub fn with_confirmer(mut self, confirmer: Arc<dyn Confirmer>) -> Self {
+        self.confirmer = Some(confirmer);
+        self
     }
 
     pub async fn execute(&self, tool_name: &str, input: serde_json::Value) -> ToolResult {
@@ -112,8 +62,41 @@ impl ToolExecutor {
             None => return ToolResult::Error(format!("Unknown tool: {}", tool_name)),
         };
 
+        // Enforced here, whatever the model was told
+        let policy = tool_policy(&self.context.state.read().await.settings.tool_policies, tool_name);
+        match policy {
+            ToolPolicy::Safe => {}
+            ToolPolicy::Forbidden => {
+                return ToolResult::Error(format!("'{}' is forbidden by the user's tool policy", tool_name));
+            }
+            ToolPolicy::Confirm => {
+                let confirmer = match &self.confirmer {
+                    Some(c) => c,
+                    None => return ToolResult::Error(format!("'{}' needs the user's confirmation", tool_name)),
+                };
+                let question = match source {
+                    ToolSource::Local => local_tools().confirmation_question(&self.context, tool_name, &input),
+                    ToolSource::Mcp { .. } => None,
+                }
+                .unwrap_or_else(|| confirmation_question(tool_name, &input));
+                println!("[Tool Confirm] {}", question);
+                match confirmer.confirm(question).await {
+                    Some(true) => {}
+                    Some(false) => {
+                        return ToolResult::Success(format!(
+                            "The user declined: {} was not run. Don't retry unless they ask again.",
+                            tool_name
+                        ));
+                    }
+                    None => {
+                        return ToolResult::Error(format!("No confirmation from the user: {} was not run", tool_name));
+                    }
+                }
+            }
+        }
+
         match source {
-            ToolSource::Local => self.execute_local(tool_name, input).await,
+            ToolSource::Local => local_tools().execute(&self.context, tool_name, input).await,
             ToolSource::Mcp {
                 server_id,
                 original_name,
@@ -121,21 +104,6 @@ impl ToolExecutor {
         }
     }
 
-    /// Execute a local tool
-    async fn execute_local(&self, tool_name: &str, input: serde_json::Value) -> ToolResult {
-        match tool_name {
-            "quit" => self.execute_quit(),
-            "list_recordings" => self.execute_list_recordings().await,
-            "summarize_recording" => self.execute_summarize(input).await,
-            "start_recording" => self.execute_start_recording(input).await,
-            "stop_recording" => self.execute_stop_recording().await,
-            "get_recording_content" => self.execute_get_content(input).await,
-            "rename_recording" => self.execute_rename(input).await,
-            "delete_recording" => self.execute_delete(input).await,
-            _ => ToolResult::Error(format!("Unknown local tool: {}", tool_name)),
-        }
-    }
-
     /// Execute a tool on an MCP server
     async fn execute_mcp(
         &self,
@@ -153,307 +121,4 @@ impl ToolExecutor {
             Err(e) => ToolResult::Error(format!("MCP tool error: {}", e)),
         }
     }
-
-    fn execute_quit(&self) -> ToolResult {
-        self.app_handle.exit(0);
-        ToolResult::Exit
-    }
-
-    async fn execute_list_recordings(&self) -> ToolResult {
-        let db = match &self.db {
-            Some(db) => db,
-            None => return ToolResult::Error("Database not initialized".to_string()),
-        };
-
-        let db = match db.lock() {
-            Ok(db) => db,
-            Err(e) => return ToolResult::Error(format!("Database lock error: {}", e)),
-        };
-
-        match db.list_recordings() {
-            Ok(recordings) => {
-                if recordings.is_empty() {
-                    return ToolResult::Success("No recordings found.".to_string());
-                }
-                let summary = recordings
-                    .iter()
-                    .map(|r| {
-                        let status = if r.is_active { " (active)" } else { "" };
-                        format!(
-                            "- {} ({}){}",
-                            r.name,
-                            r.created_at.format("%Y-%m-%d %H:%M"),
-                            status
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                ToolResult::Success(format!("Recordings:\n{}", summary))
-            }
-            Err(e) => ToolResult::Error(format!("Failed to list recordings: {}", e)),
-        }
-    }
-
-    async fn execute_summarize(&self, input: serde_json::Value) -> ToolResult {
-        let input: RecordingSelector = match serde_json::from_value(input) {
-            Ok(i) => i,
-            Err(e) => return ToolResult::Error(format!("Invalid input: {}", e)),
-        };
-
-        let db = match &self.db {
-            Some(db) => db,
-            None => return ToolResult::Error("Database not initialized".to_string()),
-        };
-
-        let api_key = {
-            let state = self.state.read().await;
-            match &state.settings.anthropic_api_key {
-                Some(key) => key.clone(),
-                None => return ToolResult::Error("Anthropic API key not configured".to_string()),
-            }
-        };
-
-        let (recording, text) = {
-            let db = match db.lock() {
-                Ok(db) => db,
-                Err(e) => return ToolResult::Error(format!("Database lock error: {}", e)),
-            };
-
-            // Find recording by name or index
-            let recording = match Self::resolve_recording(
-                &db,
-                input.recording_name.as_deref(),
-                input.recording_index,
-            ) {
-                Ok(r) => r,
-                Err(e) => return ToolResult::Error(e),
-            };
-
-            // Get full transcription text
-            let text = match db.get_full_transcription_text(recording.id) {
-                Ok(t) if t.is_empty() => {
-                    return ToolResult::Error(format!(
-                        "Recording '{}' has no transcriptions yet",
-                        recording.name
-                    ))
-                }
-                Ok(t) => t,
-                Err(e) => return ToolResult::Error(format!("Failed to get transcription: {}", e)),
-            };
-
-            (recording, text)
-        };
-
-        // Call Anthropic to summarize
-        match summarize(&api_key, &text).await {
-            Ok(summary) => ToolResult::Success(format!("Summary of '{}':\n\n{}", recording.name, summary)),
-            Err(e) => ToolResult::Error(format!("Failed to summarize: {}", e)),
-        }
-    }
-
-    async fn execute_start_recording(&self, input: serde_json::Value) -> ToolResult {
-        let input: StartRecordingInput = serde_json::from_value(input).unwrap_or(StartRecordingInput { name: None });
-
-        let db = match &self.db {
-            Some(db) => db,
-            None => return ToolResult::Error("Database not initialized".to_string()),
-        };
-
-        let mut state = self.state.write().await;
-
-        // Check if already recording
-        if state.active_recording.is_some() {
-            return ToolResult::Error("A recording is already in progress".to_string());
-        }
-
-        let name = input
-            .name
-            .unwrap_or_else(|| format!("Recording {}", Utc::now().format("%Y-%m-%d %H:%M")));
-
-        let db = match db.lock() {
-            Ok(db) => db,
-            Err(e) => return ToolResult::Error(format!("Database lock error: {}", e)),
-        };
-
-        match db.create_recording(&name) {
-            Ok(recording) => {
-                let id = recording.id;
-                state.active_recording = Some(crate::state::ActiveRecording {
-                    id,
-                    name: recording.name.clone(),
-                });
-
-                // Emit event
-                let _ = self.app_handle.emit("recording-started", &recording.name);
-
-                ToolResult::Success(format!("Started recording: {}", recording.name))
-            }
-            Err(e) => ToolResult::Error(format!("Failed to start recording: {}", e)),
-        }
-    }
-
-    async fn execute_stop_recording(&self) -> ToolResult {
-        let db = match &self.db {
-            Some(db) => db,
-            None => return ToolResult::Error("Database not initialized".to_string()),
-        };
-
-        let mut state = self.state.write().await;
-
-        let active = match state.active_recording.take() {
-            Some(a) => a,
-            None => return ToolResult::Error("No recording in progress".to_string()),
-        };
-
-        let db = match db.lock() {
-            Ok(db) => db,
-            Err(e) => return ToolResult::Error(format!("Database lock error: {}", e)),
-        };
-
-        match db.end_recording(active.id) {
-            Ok(_) => {
-                // Emit event
-                let _ = self.app_handle.emit("recording-stopped", &active.name);
-
-                ToolResult::Success(format!("Stopped recording: {}", active.name))
-            }
-            Err(e) => ToolResult::Error(format!("Failed to stop recording: {}", e)),
-        }
-    }
-
-    async fn execute_get_content(&self, input: serde_json::Value) -> ToolResult {
-        let input: RecordingSelector = match serde_json::from_value(input) {
-            Ok(i) => i,
-            Err(e) => return ToolResult::Error(format!("Invalid input: {}", e)),
-        };
-
-        let db = match &self.db {
-            Some(db) => db,
-            None => return ToolResult::Error("Database not initialized".to_string()),
-        };
-
-        let db = match db.lock() {
-            Ok(db) => db,
-            Err(e) => return ToolResult::Error(format!("Database lock error: {}", e)),
-        };
-
-        // Find recording by name or index
-        let recording = match Self::resolve_recording(
-            &db,
-            input.recording_name.as_deref(),
-            input.recording_index,
-        ) {
-            Ok(r) => r,
-            Err(e) => return ToolResult::Error(e),
-        };
-
-        // Get full transcription text
-        match db.get_full_transcription_text(recording.id) {
-            Ok(text) if text.is_empty() => {
-                ToolResult::Success(format!("Recording '{}' has no transcriptions yet.", recording.name))
-            }
-            Ok(text) => {
-                ToolResult::Success(format!("Content of '{}':\n\n{}", recording.name, text))
-            }
-            Err(e) => ToolResult::Error(format!("Failed to get transcription: {}", e)),
-        }
-    }
-
-    async fn execute_rename(&self, input: serde_json::Value) -> ToolResult {
-        let input: RenameInput = match serde_json::from_value(input) {
-            Ok(i) => i,
-            Err(e) => return ToolResult::Error(format!("Invalid input: {}", e)),
-        };
-
-        let db = match &self.db {
-            Some(db) => db,
-            None => return ToolResult::Error("Database not initialized".to_string()),
-        };
-
-        let db = match db.lock() {
-            Ok(db) => db,
-            Err(e) => return ToolResult::Error(format!("Database lock error: {}", e)),
-        };
-
-        // Find recording by name or index
-        let recording = match Self::resolve_recording(
-            &db,
-            input.recording_name.as_deref(),
-            input.recording_index,
-        ) {
-            Ok(r) => r,
-            Err(e) => return ToolResult::Error(e),
-        };
-
-        let old_name = recording.name.clone();
-
-        // Rename the recording
-        match db.rename_recording(recording.id, &input.new_name) {
-            Ok(_) => {
-                ToolResult::Success(format!(
-                    "Renamed '{}' to '{}'",
-                    old_name, input.new_name
-                ))
-            }
-            Err(e) => ToolResult::Error(format!("Failed to rename recording: {}", e)),
-        }
-    }
-
-    async fn execute_delete(&self, input: serde_json::Value) -> ToolResult {
-        let input: RecordingSelector = match serde_json::from_value(input) {
-            Ok(i) => i,
-            Err(e) => return ToolResult::Error(format!("Invalid input: {}", e)),
-        };
-
-        let db = match &self.db {
-            Some(db) => db,
-            None => return ToolResult::Error("Database not initialized".to_string()),
-        };
-
-        // Find recording (release lock before await)
-        let recording = {
-            let db = match db.lock() {
-                Ok(db) => db,
-                Err(e) => return ToolResult::Error(format!("Database lock error: {}", e)),
-            };
-
-            match Self::resolve_recording(
-                &db,
-                input.recording_name.as_deref(),
-                input.recording_index,
-            ) {
-                Ok(r) => r,
-                Err(e) => return ToolResult::Error(e),
-            }
-        }; // db lock released here
-
-        // Check if recording is active (now safe to await)
-        {
-            let state = self.state.read().await;
-            if let Some(active) = &state.active_recording {
-                if active.id == recording.id {
-                    return ToolResult::Error(format!(
-                        "Cannot delete '{}' - recording is currently active. Stop it first.",
-                        recording.name
-                    ));
-                }
-            }
-        }
-
-        let name = recording.name.clone();
-
-        // Re-acquire lock for deletion
-        let db = match db.lock() {
-            Ok(db) => db,
-            Err(e) => return ToolResult::Error(format!("Database lock error: {}", e)),
-        };
-
-        // Delete the recording
-        match db.delete_recording(recording.id) {
-            Ok(_) => {
-                ToolResult::Success(format!("Deleted recording '{}'", name))
-            }
-            Err(e) => ToolResult::Error(format!("Failed to delete recording: {}", e)),
-        }
-    }
 }
@@ -0,0 +1,154 @@
+use crate::memory;
+use crate::tools::{single_match, Tool, ToolContext, ToolResult};
+use futures::future::BoxFuture;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RememberInput {
+    /// The fact to remember, as a full sentence
+    fact: String,
+}
+
+pub struct RememberFact;
+
+impl Tool for RememberFact {
+    type Input = RememberInput;
+
+    fn name(&self) -> &'static str {
+        "remember_fact"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remember a fact or preference about the user for future conversations (e.g. 'My manager is Alice', 'The weekly sync is called Standup'). Store one self-contained fact per call, phrased so it makes sense on its own."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "fact": "The user's manager is Alice" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: RememberInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            if input.fact.trim().is_empty() {
+                return ToolResult::Error("The fact to remember is empty".to_string());
+            }
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match memory::remember(&db, &input.fact) {
+                Ok(_) => ToolResult::Success(format!("Remembered: {}", input.fact.trim())),
+                Err(e) => ToolResult::Error(format!("Failed to remember: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RecallInput {
+    /// Keywords to search for (e.g. 'manager')
+    query: Option<String>,
+}
+
+pub struct RecallFacts;
+
+impl Tool for RecallFacts {
+    type Input = RecallInput;
+
+    fn name(&self) -> &'static str {
+        "recall_facts"
+    }
+
+    fn description(&self) -> &'static str {
+        "Search the facts remembered about the user by keywords. Without a query, list the most recent ones."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({}), json!({ "query": "manager" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: RecallInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match memory::recall(&db, input.query.as_deref().unwrap_or(""), 20) {
+                Ok(memories) if memories.is_empty() => ToolResult::Success("No matching facts remembered.".to_string()),
+                Ok(memories) => {
+                    let list = memories
+                        .iter()
+                        .map(|m| format!("- {} (id: {})", m.content, m.id))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ToolResult::Success(format!("Remembered facts:\n{}", list))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to search memories: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ForgetInput {
+    /// Id of the fact, as returned by recall_facts
+    memory_id: Option<String>,
+    /// Keywords of the fact to forget (several matching facts are listed instead)
+    query: Option<String>,
+}
+
+pub struct ForgetFact;
+
+impl Tool for ForgetFact {
+    type Input = ForgetInput;
+
+    fn name(&self) -> &'static str {
+        "forget_fact"
+    }
+
+    fn description(&self) -> &'static str {
+        "Forget a remembered fact, by id (from recall_facts) or by keywords matching only that fact"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "memory_id": "0b0e7c2e-8a7d-4c1e-9f3a-5d2b6c1a4e90" }), json!({ "query": "manager" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: ForgetInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let id = match input.memory_id.as_deref().map(uuid::Uuid::parse_str).transpose() {
+                Ok(id) => id,
+                Err(_) => return ToolResult::Error("Invalid memory_id, use an id returned by recall_facts".to_string()),
+            };
+            if id.is_none() && input.query.is_none() {
+                return ToolResult::Error("Please specify the memory_id or a query".to_string());
+            }
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let found = match (id, input.query.as_deref()) {
+                (Some(id), _) => db.get_memory(id).map(|m| m.into_iter().collect()),
+                (None, query) => memory::find(&db, query.unwrap_or("")),
+            };
+            let memory = match found {
+                Ok(found) => match single_match(found, "fact", |m| format!("{} (id: {})", m.content, m.id)) {
+                    Ok(memory) => memory,
+                    Err(e) => return ToolResult::Error(e),
+                },
+                Err(e) => return ToolResult::Error(format!("Failed to search memories: {}", e)),
+            };
+
+            match db.delete_memory(memory.id) {
+                Ok(_) => ToolResult::Success(format!("Forgot: {}", memory.content)),
+                Err(e) => ToolResult::Error(format!("Failed to forget: {}", e)),
+            }
+        })
+    }
+}
@@ -1,7 +1,16 @@
-mod definitions;
+mod app;
 mod executor;
+mod memories;
+mod notes;
+mod policy;
 mod provider;
+mod quick_notes;
+mod recordings;
+mod reminders;
+mod todos;
+mod tool;
 
-pub use definitions::get_tool_definitions;
 pub use executor::{ToolExecutor, ToolResult};
+pub use policy::{confirmation_question, tool_policy, Confirmer, ToolPolicy};
 pub use provider::{get_merged_tools, ToolSource};
+pub use tool::{local_tools, single_match, NoInput, Tool, ToolContext};
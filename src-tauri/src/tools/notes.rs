@@ -0,0 +1,140 @@
+use crate::llm::create_provider;
+use crate::meeting_notes::{extract_meeting_notes, notes_to_markdown};
+use crate::tools::recordings::{RecordingRange, RecordingSelector};
+use crate::tools::{Tool, ToolContext, ToolResult};
+use futures::future::BoxFuture;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct MeetingNotesInput {
+    #[serde(flatten)]
+    recording: RecordingSelector,
+    /// Extract the notes again even if they already exist
+    #[serde(default)]
+    refresh: bool,
+}
+
+pub struct GetMeetingNotes;
+
+impl Tool for GetMeetingNotes {
+    type Input = MeetingNotesInput;
+
+    fn name(&self) -> &'static str {
+        "get_meeting_notes"
+    }
+
+    fn description(&self) -> &'static str {
+        "Get the structured notes of a recording/meeting: decisions, action items (owner, due date), open questions, topics and people. Notes are extracted on first use. Use either recording_name OR recording_index."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "recording_name": "Weekly sync" }), json!({ "recording_index": 1, "refresh": true })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: MeetingNotesInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let (recording, stored) = {
+                let db = match ctx.lock_db() {
+                    Ok(db) => db,
+                    Err(e) => return ToolResult::Error(e),
+                };
+                let recording = match input.recording.resolve(&db) {
+                    Ok(r) => r,
+                    Err(e) => return ToolResult::Error(e),
+                };
+                let stored = db.get_meeting_notes(recording.id).ok().flatten();
+                (recording, stored)
+            };
+
+            let notes = match stored.filter(|_| !input.refresh) {
+                Some(notes) => notes,
+                None => {
+                    let (provider, config) = {
+                        let state = ctx.state.read().await;
+                        match create_provider(&state.settings) {
+                            Ok(provider) => (provider, state.settings.summary.clone()),
+                            Err(e) => return ToolResult::Error(e.to_string()),
+                        }
+                    };
+                    match extract_meeting_notes(provider.as_ref(), db, &recording, &config, ctx.usage.as_deref()).await {
+                        Ok(notes) => notes,
+                        Err(e) => return ToolResult::Error(format!("Failed to extract meeting notes: {}", e)),
+                    }
+                }
+            };
+
+            ToolResult::Success(notes_to_markdown(&notes, &recording))
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ActionItemsInput {
+    /// Name (or part of the name) of the person responsible
+    owner: Option<String>,
+    /// Only meetings recorded in this period
+    #[serde(flatten)]
+    period: RecordingRange,
+}
+
+pub struct ListActionItems;
+
+impl Tool for ListActionItems {
+    type Input = ActionItemsInput;
+
+    fn name(&self) -> &'static str {
+        "list_action_items"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the action items extracted from all recordings/meetings, optionally only those of one person or of the meetings of a period"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({}), json!({ "owner": "Alice" }), json!({ "when": "last week" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: ActionItemsInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let (since, until) = match input.period.resolve() {
+                Ok(range) => range,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.list_action_items(input.owner.as_deref(), since, until) {
+                Ok(items) if items.is_empty() => {
+                    ToolResult::Success("No action items found (meeting notes may not have been extracted yet).".to_string())
+                }
+                Ok(items) => {
+                    let list = items
+                        .iter()
+                        .map(|a| {
+                            format!(
+                                "- {} (owner: {}, due: {}, from '{}')",
+                                a.item.description,
+                                a.item.owner.as_deref().unwrap_or("unassigned"),
+                                a.item.due_date.as_deref().unwrap_or("none"),
+                                a.recording_name
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ToolResult::Success(format!("Action items:\n{}", list))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to list action items: {}", e)),
+            }
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// What happens when the model calls a tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPolicy {
+    /// Run directly
+    Safe,
+    /// Run only once the user said yes
+    Confirm,
+    /// Never run (and not offered to the model)
+    Forbidden,
+}
+
+/// Built-in tools that need the user's confirmation
+const CONFIRM_TOOLS: &[&str] = &[
+    "cancel_reminder",
+    "delete_note",
+    "delete_recording",
+    "delete_todo",
+    "export_recording",
+    "forget_fact",
+    "quit",
+];
+
+/// Policy of a tool: the user's override for its exact name, else the longest matching
+/// `prefix*` pattern (e.g. "panorama_*" for all tools of an MCP server), else the default
+pub fn tool_policy(overrides: &HashMap<String, ToolPolicy>, tool_name: &str) -> ToolPolicy {
+    if let Some(policy) = overrides.get(tool_name) {
+        return *policy;
+    }
+
+    let pattern = overrides
+        .iter()
+        .filter_map(|(pattern, policy)| pattern.strip_suffix('*').map(|prefix| (prefix, policy)))
+        .filter(|(prefix, _)| tool_name.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len());
+    match pattern {
+        Some((_, policy)) => *policy,
+        None if CONFIRM_TOOLS.contains(&tool_name) => ToolPolicy::Confirm,
+        None => ToolPolicy::Safe,
+    }
+}
+
+/// Question asked before running a confirm-tagged tool, when the tool has no question
+/// of its own (local tools that act on a recording name it, see `Tool::confirmation_question`)
+pub fn confirmation_question(tool_name: &str, input: &serde_json::Value) -> String {
+    match tool_name {
+        "forget_fact" if input["query"].is_string() => {
+            format!("Forget what you know about '{}'?", input["query"].as_str().unwrap_or(""))
+        }
+        "cancel_reminder" if input["query"].is_string() => {
+            format!("Cancel the reminder '{}'?", input["query"].as_str().unwrap_or(""))
+        }
+        "delete_note" if input["query"].is_string() => {
+            format!("Delete the note about '{}'?", input["query"].as_str().unwrap_or(""))
+        }
+        "delete_todo" if input["query"].is_string() => {
+            format!("Remove '{}' from your to-do list?", input["query"].as_str().unwrap_or(""))
+        }
+        "quit" => "Quit Robert?".to_string(),
+        _ => {
+            let args = input
+                .as_object()
+                .map(|args| {
+                    args.iter()
+                        .map(|(key, value)| match value.as_str() {
+                            Some(s) => format!("{}: {}", key, s),
+                            None => format!("{}: {}", key, value),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            if args.is_empty() {
+                format!("Run {}?", tool_name)
+            } else {
+                format!("Run {} ({})?", tool_name, args)
+            }
+        }
+    }
+}
+
+/// Asks the user to approve a tool call
+pub trait Confirmer: Send + Sync {
+    /// The user's answer, or None if none came in time
+    fn confirm(&self, question: String) -> BoxFuture<'static, Option<bool>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Database;
+    use serde_json::json;
+
+    #[test]
+    fn test_tool_policy_resolution() {
+        let overrides = HashMap::from([
+            ("panorama_*".to_string(), ToolPolicy::Confirm),
+            ("panorama_tasks*".to_string(), ToolPolicy::Safe),
+            ("panorama_tasksDelete".to_string(), ToolPolicy::Forbidden),
+            ("quit".to_string(), ToolPolicy::Safe),
+        ]);
+
+        assert_eq!(tool_policy(&overrides, "panorama_tasksDelete"), ToolPolicy::Forbidden);
+        assert_eq!(tool_policy(&overrides, "panorama_tasksFilter"), ToolPolicy::Safe);
+        assert_eq!(tool_policy(&overrides, "panorama_projects"), ToolPolicy::Confirm);
+        assert_eq!(tool_policy(&overrides, "quit"), ToolPolicy::Safe);
+        assert_eq!(tool_policy(&overrides, "delete_recording"), ToolPolicy::Confirm);
+        assert_eq!(tool_policy(&overrides, "export_recording"), ToolPolicy::Confirm);
+        assert_eq!(tool_policy(&HashMap::new(), "list_recordings"), ToolPolicy::Safe);
+    }
+
+    #[test]
+    fn test_destructive_tools_need_confirmation() {
+        let definitions = crate::tools::local_tools().definitions();
+        let destructive: Vec<&str> = definitions
+            .iter()
+            .map(|d| d.name.as_str())
+            .filter(|name| ["delete_", "forget_", "cancel_"].iter().any(|prefix| name.starts_with(prefix)))
+            .collect();
+        assert!(destructive.len() >= 5, "{:?}", destructive);
+        for name in destructive {
+            assert_eq!(tool_policy(&HashMap::new(), name), ToolPolicy::Confirm, "{}", name);
+        }
+
+        // Every default-confirmed tool exists
+        for name in CONFIRM_TOOLS {
+            assert!(definitions.iter().any(|d| d.name == *name), "unknown tool {}", name);
+        }
+    }
+
+    #[test]
+    fn test_confirmation_question() {
+        // Recording tools ask their own question, with the recording resolved
+        let db = Database::in_memory().unwrap();
+        let budget = db.create_recording("Budget").unwrap();
+        db.create_recording("Standup").unwrap();
+        let date = budget.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+        let registry = crate::tools::local_tools();
+        assert_eq!(
+            registry.question(&db, "delete_recording", &json!({ "recording_index": 2 })),
+            Some(format!("Delete the recording 'Budget' of {}?", date))
+        );
+        let export = json!({ "recording_name": "Budget", "format": "md", "path": "~/Desktop/b.md" });
+        assert_eq!(
+            registry.question(&db, "export_recording", &export),
+            Some(format!("Export the recording 'Budget' of {} to ~/Desktop/b.md?", date))
+        );
+        assert_eq!(registry.question(&db, "export_recording", &json!({ "when": "today", "format": "text" })), None);
+        // Unknown recordings fall back to the generic question
+        assert_eq!(registry.question(&db, "delete_recording", &json!({ "recording_index": 3 })), None);
+        assert_eq!(
+            confirmation_question("delete_recording", &json!({ "recording_index": 3 })),
+            "Run delete_recording (recording_index: 3)?"
+        );
+        assert_eq!(
+            confirmation_question("cancel_reminder", &json!({ "query": "call" })),
+            "Cancel the reminder 'call'?"
+        );
+        assert_eq!(
+            confirmation_question("delete_todo", &json!({ "query": "PR 42" })),
+            "Remove 'PR 42' from your to-do list?"
+        );
+        assert_eq!(
+            confirmation_question("forget_fact", &json!({ "query": "manager" })),
+            "Forget what you know about 'manager'?"
+        );
+        assert_eq!(
+            confirmation_question("panorama_tasksDelete", &json!({ "id": "T-12", "force": true })),
+            "Run panorama_tasksDelete (force: true, id: T-12)?"
+        );
+    }
+}
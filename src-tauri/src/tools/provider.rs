@@ -23,7 +23,7 @@ pub async fn get_merged_tools(
     let mut routing = HashMap::new();
 
     // 1. Add local tools
-    for tool in super::get_tool_definitions() {
+    for tool in super::local_tools().definitions() {
         routing.insert(tool.name.clone(), ToolSource::Local);
         tools.push(tool);
     }
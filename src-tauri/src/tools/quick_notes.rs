@@ -0,0 +1,161 @@
+use crate::intent::mentions;
+use crate::storage::Note;
+use crate::tools::{single_match, Tool, ToolContext, ToolResult};
+use futures::future::BoxFuture;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct TakeNoteInput {
+    /// The note, in the user's words (e.g. "the deploy is Thursday")
+    content: String,
+}
+
+pub struct TakeNote;
+
+impl Tool for TakeNote {
+    type Input = TakeNoteInput;
+
+    fn name(&self) -> &'static str {
+        "take_note"
+    }
+
+    fn description(&self) -> &'static str {
+        "Write down a quick note the user dictates ('note that the deploy is Thursday'). Notes are shown in the settings window; use remember_fact instead for facts about the user to reuse in conversations."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "content": "The deploy is Thursday" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: TakeNoteInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            if input.content.trim().is_empty() {
+                return ToolResult::Error("The note is empty".to_string());
+            }
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.add_note(input.content.trim()) {
+                Ok(note) => ToolResult::Success(format!("Noted: {}", note.content)),
+                Err(e) => ToolResult::Error(format!("Failed to save the note: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListNotesInput {
+    /// Only notes containing these words
+    query: Option<String>,
+}
+
+pub struct ListNotes;
+
+impl Tool for ListNotes {
+    type Input = ListNotesInput;
+
+    fn name(&self) -> &'static str {
+        "list_notes"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the user's quick notes, most recent first, optionally only those containing some words"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({}), json!({ "query": "deploy" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: ListNotesInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+            let notes = match db.list_notes() {
+                Ok(notes) => notes,
+                Err(e) => return ToolResult::Error(format!("Failed to list notes: {}", e)),
+            };
+
+            let notes: Vec<_> = match input.query.as_deref().filter(|q| !q.trim().is_empty()) {
+                Some(query) => notes.into_iter().filter(|n| mentions(&n.content, query)).collect(),
+                None => notes,
+            };
+            if notes.is_empty() {
+                return ToolResult::Success("No matching notes.".to_string());
+            }
+
+            let list = notes
+                .iter()
+                .map(|n| format!("- {} ({}, id: {})", n.content, n.created_at.format("%Y-%m-%d"), n.id))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ToolResult::Success(format!("Notes:\n{}", list))
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DeleteNoteInput {
+    /// Id of the note, as returned by list_notes
+    note_id: Option<String>,
+    /// Words of the note
+    query: Option<String>,
+}
+
+pub struct DeleteNote;
+
+impl Tool for DeleteNote {
+    type Input = DeleteNoteInput;
+
+    fn name(&self) -> &'static str {
+        "delete_note"
+    }
+
+    fn description(&self) -> &'static str {
+        "Delete a quick note, by id (from list_notes) or by words of the note"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "note_id": "0b0e7c2e-8a7d-4c1e-9f3a-5d2b6c1a4e90" }), json!({ "query": "deploy" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: DeleteNoteInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let id = match input.note_id.as_deref().map(|id| Uuid::parse_str(id.trim())).transpose() {
+                Ok(id) => id,
+                Err(_) => return ToolResult::Error("Invalid note_id, use an id returned by list_notes".to_string()),
+            };
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+            let notes = match db.list_notes() {
+                Ok(notes) => notes,
+                Err(e) => return ToolResult::Error(format!("Failed to list notes: {}", e)),
+            };
+
+            let found: Vec<Note> = match (id, &input.query) {
+                (Some(id), _) => notes.into_iter().filter(|n| n.id == id).collect(),
+                (None, Some(query)) => notes.into_iter().filter(|n| mentions(&n.content, query)).collect(),
+                (None, None) => return ToolResult::Error("Please specify the note_id or words of the note".to_string()),
+            };
+            let note = match single_match(found, "note", |n| format!("{} (id: {})", n.content, n.id)) {
+                Ok(note) => note,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.delete_note(note.id) {
+                Ok(_) => ToolResult::Success(format!("Deleted the note: {}", note.content)),
+                Err(e) => ToolResult::Error(format!("Failed to delete the note: {}", e)),
+            }
+        })
+    }
+}
@@ -0,0 +1,799 @@
+use crate::dates::{self, DateRange};
+use crate::export::{self, ExportFormat};
+use crate::llm::{create_provider, summarize_transcript, LlmProvider, SummaryConfig, TranscriptSegment};
+use crate::search;
+use crate::state::ActiveRecording;
+use crate::storage::{Database, Recording, TranscriptFilter};
+use crate::summary_cache::RecordingSummaryCache;
+use crate::tools::{NoInput, Tool, ToolContext, ToolResult};
+use chrono::{Local, Utc};
+use futures::future::BoxFuture;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use tauri::Emitter;
+
+/// Recording a tool applies to, by name or by position
+#[derive(Deserialize, JsonSchema)]
+pub struct RecordingSelector {
+    /// The exact name of the recording
+    recording_name: Option<String>,
+    /// The position of the recording (1 = first/most recent, 2 = second, -1 = last/oldest)
+    recording_index: Option<i32>,
+}
+
+impl RecordingSelector {
+    pub fn is_set(&self) -> bool {
+        self.recording_name.is_some() || self.recording_index.is_some()
+    }
+
+    /// Resolve the recording from either name or index
+    pub fn resolve(&self, db: &Database) -> Result<Recording, String> {
+        if let Some(name) = &self.recording_name {
+            match db.get_recording_by_name(name) {
+                Ok(Some(r)) => return Ok(r),
+                Ok(None) => return Err(format!("Recording '{}' not found", name)),
+                Err(e) => return Err(format!("Database error: {}", e)),
+            }
+        }
+
+        if let Some(idx) = self.recording_index {
+            let recordings = db.list_recordings().map_err(|e| e.to_string())?;
+            if recordings.is_empty() {
+                return Err("No recordings found".to_string());
+            }
+
+            // Handle negative indices (-1 = last, -2 = second to last)
+            let actual_idx = if idx < 0 {
+                (recordings.len() as i32 + idx) as usize
+            } else if idx == 0 {
+                return Err("Index must be non-zero (1 = first, -1 = last)".to_string());
+            } else {
+                (idx - 1) as usize // Convert 1-based to 0-based
+            };
+
+            if actual_idx >= recordings.len() {
+                return Err(format!(
+                    "Index {} out of range. You have {} recording(s).",
+                    idx,
+                    recordings.len()
+                ));
+            }
+
+            return Ok(recordings[actual_idx].clone());
+        }
+
+        Err("Please specify either a recording name or index (e.g., 'first', 'second', 'last')".to_string())
+    }
+}
+
+/// Recordings made in a period; `when` and the bounds can be combined
+#[derive(Deserialize, JsonSchema)]
+pub struct RecordingRange {
+    /// Period in English or French, e.g. "yesterday", "last Tuesday afternoon", "this week", "la semaine dernière", "il y a 3 jours", "2026-03-12" or "2026-03-01..2026-03-15"
+    when: Option<String>,
+    /// First day, inclusive (YYYY-MM-DD or relative like "last monday")
+    from_date: Option<String>,
+    /// Last day, inclusive (YYYY-MM-DD or relative like "yesterday")
+    to_date: Option<String>,
+}
+
+impl RecordingRange {
+    pub fn is_set(&self) -> bool {
+        self.when.is_some() || self.from_date.is_some() || self.to_date.is_some()
+    }
+
+    /// Parse the period against the current local time
+    pub fn resolve(&self) -> Result<DateRange, String> {
+        dates::resolve_range(self.when.as_deref(), self.from_date.as_deref(), self.to_date.as_deref())
+    }
+}
+
+/// "'Name' of 2026-03-12 09:05" in local time, for confirmation questions
+fn name_and_date(recording: &Recording) -> String {
+    format!("'{}' of {}", recording.name, recording.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"))
+}
+
+/// "- Name (2026-03-12 09:05, 45 min)" in local time
+fn describe_recording(recording: &Recording) -> String {
+    let started = recording.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+    match (recording.is_active, recording.ended_at) {
+        (true, _) => format!("- {} ({}, active)", recording.name, started),
+        (false, Some(ended)) => format!(
+            "- {} ({}, {} min)",
+            recording.name,
+            started,
+            (ended - recording.created_at).num_minutes().max(1)
+        ),
+        (false, None) => format!("- {} ({})", recording.name, started),
+    }
+}
+
+pub struct ListRecordings;
+
+impl Tool for ListRecordings {
+    type Input = NoInput;
+
+    fn name(&self) -> &'static str {
+        "list_recordings"
+    }
+
+    fn description(&self) -> &'static str {
+        "List all saved recordings/meetings with their names and dates. Note: 'recording' and 'meeting' refer to the same thing."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({})]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, _input: NoInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.list_recordings() {
+                Ok(recordings) => {
+                    if recordings.is_empty() {
+                        return ToolResult::Success("No recordings found.".to_string());
+                    }
+                    let summary = recordings
+                        .iter()
+                        .map(|r| {
+                            let status = if r.is_active { " (active)" } else { "" };
+                            format!(
+                                "- {} ({}){}",
+                                r.name,
+                                r.created_at.format("%Y-%m-%d %H:%M"),
+                                status
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ToolResult::Success(format!("Recordings:\n{}", summary))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to list recordings: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct FindRecordingsInput {
+    #[serde(flatten)]
+    period: RecordingRange,
+    /// Only recordings whose name contains this text
+    name_contains: Option<String>,
+}
+
+pub struct FindRecordings;
+
+impl Tool for FindRecordings {
+    type Input = FindRecordingsInput;
+
+    fn name(&self) -> &'static str {
+        "find_recordings"
+    }
+
+    fn description(&self) -> &'static str {
+        "Find the recordings/meetings made in a period (\"yesterday\", \"last Tuesday afternoon\", \"la semaine dernière\", ISO dates or ranges), with their start time and duration, most recent first. Pass the user's own words as `when`."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![
+            json!({ "when": "yesterday" }),
+            json!({ "when": "mardi dernier après-midi" }),
+            json!({ "from_date": "2026-03-01", "to_date": "2026-03-15", "name_contains": "sync" }),
+        ]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: FindRecordingsInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let (since, until) = match input.period.resolve() {
+                Ok(range) => range,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let recordings = match db.list_recordings_between(since, until) {
+                Ok(recordings) => recordings,
+                Err(e) => return ToolResult::Error(format!("Failed to list recordings: {}", e)),
+            };
+            let name = input.name_contains.as_deref().map(str::to_lowercase);
+            let found: Vec<String> = recordings
+                .iter()
+                .filter(|r| name.as_ref().is_none_or(|n| r.name.to_lowercase().contains(n)))
+                .map(describe_recording)
+                .collect();
+
+            if found.is_empty() {
+                ToolResult::Success("No recordings found in that period.".to_string())
+            } else {
+                ToolResult::Success(format!("{} recording(s):\n{}", found.len(), found.join("\n")))
+            }
+        })
+    }
+}
+
+/// Most recordings summarized by one call over a period
+const MAX_PERIOD_SUMMARIES: usize = 10;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SummarizeInput {
+    #[serde(flatten)]
+    recording: RecordingSelector,
+    /// Without a name or index: summarize every recording of this period
+    #[serde(flatten)]
+    period: RecordingRange,
+}
+
+/// Summary of one recording (map-reduce for long ones, chunk summaries cached)
+async fn summarize_one(
+    ctx: &ToolContext,
+    provider: &dyn LlmProvider,
+    config: &SummaryConfig,
+    recording: &Recording,
+) -> Result<String, String> {
+    let db = ctx.db()?;
+
+    // Get transcription segments (timestamps drive chunking)
+    let transcriptions = {
+        let db = ctx.lock_db()?;
+        match db.get_transcriptions(recording.id) {
+            Ok(t) if t.iter().all(|t| t.text.trim().is_empty()) => {
+                return Err(format!("Recording '{}' has no transcriptions yet", recording.name))
+            }
+            Ok(t) => t,
+            Err(e) => return Err(format!("Failed to get transcription: {}", e)),
+        }
+    };
+
+    let segments: Vec<TranscriptSegment> = transcriptions
+        .iter()
+        .map(|t| TranscriptSegment { timestamp: t.timestamp, text: &t.text })
+        .collect();
+    let cache = RecordingSummaryCache::new(db.clone(), recording.id);
+
+    summarize_transcript(provider, &segments, config, Some(&cache), ctx.usage.as_deref())
+        .await
+        .map_err(|e| format!("Failed to summarize: {}", e))
+}
+
+pub struct SummarizeRecording;
+
+impl Tool for SummarizeRecording {
+    type Input = SummarizeInput;
+
+    fn name(&self) -> &'static str {
+        "summarize_recording"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generate a summary of a specific recording's transcription. Use either recording_name OR recording_index, or a period (e.g. when: \"yesterday\") to summarize every recording made then."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![
+            json!({ "recording_name": "Weekly sync" }),
+            json!({ "recording_index": 1 }),
+            json!({ "when": "hier" }),
+        ]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: SummarizeInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let (provider, config) = {
+                let state = ctx.state.read().await;
+                match create_provider(&state.settings) {
+                    Ok(provider) => (provider, state.settings.summary.clone()),
+                    Err(e) => return ToolResult::Error(e.to_string()),
+                }
+            };
+
+            if input.recording.is_set() || !input.period.is_set() {
+                let recording = {
+                    let db = match ctx.lock_db() {
+                        Ok(db) => db,
+                        Err(e) => return ToolResult::Error(e),
+                    };
+                    match input.recording.resolve(&db) {
+                        Ok(r) => r,
+                        Err(e) => return ToolResult::Error(e),
+                    }
+                };
+
+                return match summarize_one(ctx, provider.as_ref(), &config, &recording).await {
+                    Ok(summary) => ToolResult::Success(format!("Summary of '{}':\n\n{}", recording.name, summary)),
+                    Err(e) => ToolResult::Error(e),
+                };
+            }
+
+            let recordings = {
+                let (since, until) = match input.period.resolve() {
+                    Ok(range) => range,
+                    Err(e) => return ToolResult::Error(e),
+                };
+                let db = match ctx.lock_db() {
+                    Ok(db) => db,
+                    Err(e) => return ToolResult::Error(e),
+                };
+                match db.list_recordings_between(since, until) {
+                    Ok(recordings) => recordings,
+                    Err(e) => return ToolResult::Error(format!("Failed to list recordings: {}", e)),
+                }
+            };
+            if recordings.is_empty() {
+                return ToolResult::Success("No recordings found in that period.".to_string());
+            }
+
+            // Oldest first, so the summaries read in order
+            let mut sections = Vec::new();
+            for recording in recordings.iter().take(MAX_PERIOD_SUMMARIES).rev() {
+                let started = recording.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+                match summarize_one(ctx, provider.as_ref(), &config, recording).await {
+                    Ok(summary) => sections.push(format!("Summary of '{}' ({}):\n\n{}", recording.name, started, summary)),
+                    Err(e) => sections.push(format!("'{}' ({}): {}", recording.name, started, e)),
+                }
+            }
+            if recordings.len() > MAX_PERIOD_SUMMARIES {
+                sections.push(format!(
+                    "({} older recordings of that period were not summarized; narrow the period to include them.)",
+                    recordings.len() - MAX_PERIOD_SUMMARIES
+                ));
+            }
+            ToolResult::Success(sections.join("\n\n---\n\n"))
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct StartRecordingInput {
+    /// Optional name for the recording
+    name: Option<String>,
+}
+
+pub struct StartRecording;
+
+impl Tool for StartRecording {
+    type Input = StartRecordingInput;
+
+    fn name(&self) -> &'static str {
+        "start_recording"
+    }
+
+    fn description(&self) -> &'static str {
+        "Start a new meeting recording session"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({}), json!({ "name": "Budget review" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: StartRecordingInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            if let Err(e) = ctx.db() {
+                return ToolResult::Error(e);
+            }
+
+            let mut state = ctx.state.write().await;
+
+            // Check if already recording
+            if state.active_recording.is_some() {
+                return ToolResult::Error("A recording is already in progress".to_string());
+            }
+
+            let name = input
+                .name
+                .unwrap_or_else(|| format!("Recording {}", Utc::now().format("%Y-%m-%d %H:%M")));
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.create_recording(&name) {
+                Ok(recording) => {
+                    state.active_recording = Some(ActiveRecording {
+                        id: recording.id,
+                        name: recording.name.clone(),
+                    });
+
+                    // Emit event
+                    let _ = ctx.app_handle.emit("recording-started", &recording.name);
+
+                    ToolResult::Success(format!("Started recording: {}", recording.name))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to start recording: {}", e)),
+            }
+        })
+    }
+}
+
+pub struct StopRecording;
+
+impl Tool for StopRecording {
+    type Input = NoInput;
+
+    fn name(&self) -> &'static str {
+        "stop_recording"
+    }
+
+    fn description(&self) -> &'static str {
+        "Stop the current recording session"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({})]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, _input: NoInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            if let Err(e) = ctx.db() {
+                return ToolResult::Error(e);
+            }
+
+            let mut state = ctx.state.write().await;
+
+            let active = match state.active_recording.take() {
+                Some(a) => a,
+                None => return ToolResult::Error("No recording in progress".to_string()),
+            };
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.end_recording(active.id) {
+                Ok(_) => {
+                    // Emit event
+                    let _ = ctx.app_handle.emit("recording-stopped", &active.name);
+
+                    ToolResult::Success(format!("Stopped recording: {}", active.name))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to stop recording: {}", e)),
+            }
+        })
+    }
+}
+
+pub struct GetRecordingContent;
+
+impl Tool for GetRecordingContent {
+    type Input = RecordingSelector;
+
+    fn name(&self) -> &'static str {
+        "get_recording_content"
+    }
+
+    fn description(&self) -> &'static str {
+        "Get the full transcription content of a specific recording. Use either recording_name OR recording_index."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "recording_name": "Weekly sync" }), json!({ "recording_index": -1 })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: RecordingSelector) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let recording = match input.resolve(&db) {
+                Ok(r) => r,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            // Get full transcription text
+            match db.get_full_transcription_text(recording.id) {
+                Ok(text) if text.is_empty() => {
+                    ToolResult::Success(format!("Recording '{}' has no transcriptions yet.", recording.name))
+                }
+                Ok(text) => {
+                    ToolResult::Success(format!("Content of '{}':\n\n{}", recording.name, text))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to get transcription: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RenameInput {
+    #[serde(flatten)]
+    recording: RecordingSelector,
+    /// The new name for the recording
+    new_name: String,
+}
+
+pub struct RenameRecording;
+
+impl Tool for RenameRecording {
+    type Input = RenameInput;
+
+    fn name(&self) -> &'static str {
+        "rename_recording"
+    }
+
+    fn description(&self) -> &'static str {
+        "Rename an existing recording/meeting. Use either recording_name OR recording_index to identify it."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![
+            json!({ "recording_name": "Recording 2026-03-12 09:05", "new_name": "Budget review" }),
+            json!({ "recording_index": 1, "new_name": "Standup" }),
+        ]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: RenameInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let recording = match input.recording.resolve(&db) {
+                Ok(r) => r,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.rename_recording(recording.id, &input.new_name) {
+                Ok(_) => ToolResult::Success(format!("Renamed '{}' to '{}'", recording.name, input.new_name)),
+                Err(e) => ToolResult::Error(format!("Failed to rename recording: {}", e)),
+            }
+        })
+    }
+}
+
+pub struct DeleteRecording;
+
+impl Tool for DeleteRecording {
+    type Input = RecordingSelector;
+
+    fn name(&self) -> &'static str {
+        "delete_recording"
+    }
+
+    fn description(&self) -> &'static str {
+        "Delete a recording/meeting permanently. Use either recording_name OR recording_index to identify it. The user is asked to confirm before it runs, so call it directly."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "recording_name": "Test" }), json!({ "recording_index": 2 })]
+    }
+
+    fn confirmation_question(&self, db: &Database, input: &RecordingSelector) -> Option<String> {
+        let recording = input.resolve(db).ok()?;
+        Some(format!("Delete the recording {}?", name_and_date(&recording)))
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: RecordingSelector) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            // Find recording (release lock before await)
+            let recording = {
+                let db = match ctx.lock_db() {
+                    Ok(db) => db,
+                    Err(e) => return ToolResult::Error(e),
+                };
+                match input.resolve(&db) {
+                    Ok(r) => r,
+                    Err(e) => return ToolResult::Error(e),
+                }
+            };
+
+            // Check if recording is active (now safe to await)
+            {
+                let state = ctx.state.read().await;
+                if let Some(active) = &state.active_recording {
+                    if active.id == recording.id {
+                        return ToolResult::Error(format!(
+                            "Cannot delete '{}' - recording is currently active. Stop it first.",
+                            recording.name
+                        ));
+                    }
+                }
+            }
+
+            // Re-acquire lock for deletion
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.delete_recording(recording.id) {
+                Ok(_) => ToolResult::Success(format!("Deleted recording '{}'", recording.name)),
+                Err(e) => ToolResult::Error(format!("Failed to delete recording: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SearchTranscriptsInput {
+    /// Words to find (all must appear), or a "quoted phrase"
+    query: String,
+    /// Only search this recording (by name or position, see recording_index)
+    #[serde(flatten)]
+    recording: RecordingSelector,
+    /// Only what was said in this period
+    #[serde(flatten)]
+    period: RecordingRange,
+    /// Maximum number of results (default 10)
+    limit: Option<u32>,
+}
+
+pub struct SearchTranscripts;
+
+impl Tool for SearchTranscripts {
+    type Input = SearchTranscriptsInput;
+
+    fn name(&self) -> &'static str {
+        "search_transcripts"
+    }
+
+    fn description(&self) -> &'static str {
+        "Full-text search across the transcriptions of all recordings/meetings, best matches first, with the passage around each match. Use it to find which meeting mentioned something before reading a whole transcript."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![
+            json!({ "query": "budget freeze" }),
+            json!({ "query": "\"hiring plan\"", "recording_name": "Weekly sync", "limit": 5 }),
+            json!({ "query": "roadmap", "from_date": "2026-03-01", "to_date": "2026-03-31" }),
+            json!({ "query": "budget", "when": "last week" }),
+        ]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: SearchTranscriptsInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let (since, until) = match input.period.resolve() {
+                Ok(range) => range,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            let recording_id = if input.recording.is_set() {
+                match input.recording.resolve(&db) {
+                    Ok(r) => Some(r.id),
+                    Err(e) => return ToolResult::Error(e),
+                }
+            } else {
+                None
+            };
+            let filter = TranscriptFilter { recording_id, since, until };
+            let limit = input.limit.unwrap_or(10).clamp(1, 50) as usize;
+
+            match search::search_transcripts(&db, &input.query, &filter, limit) {
+                Ok(matches) if matches.is_empty() => {
+                    ToolResult::Success(format!("No transcription mentions '{}'.", input.query))
+                }
+                Ok(matches) => {
+                    let list = matches
+                        .iter()
+                        .map(|m| {
+                            format!(
+                                "- '{}' ({}): {}",
+                                m.recording_name,
+                                m.timestamp.format("%Y-%m-%d %H:%M"),
+                                m.snippet
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ToolResult::Success(format!("Matches for '{}':\n{}", input.query, list))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to search transcriptions: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ExportInput {
+    /// A single recording (by name or position)
+    #[serde(flatten)]
+    recording: RecordingSelector,
+    /// Or every recording of a period
+    #[serde(flatten)]
+    period: RecordingRange,
+    /// File format
+    format: ExportFormat,
+    /// File or folder to write to (default: ~/Documents/Robert)
+    path: Option<String>,
+}
+
+pub struct ExportRecording;
+
+impl Tool for ExportRecording {
+    type Input = ExportInput;
+
+    fn name(&self) -> &'static str {
+        "export_recording"
+    }
+
+    fn description(&self) -> &'static str {
+        "Export the transcription of a recording to a file: markdown (headings and timestamps), srt or vtt subtitles, json (all metadata and meeting notes) or plain text. Use recording_name OR recording_index for one recording, or a period (e.g. when: \"last week\") to export each of its recordings into a folder."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![
+            json!({ "recording_index": 1, "format": "markdown" }),
+            json!({ "recording_name": "Weekly sync", "format": "srt", "path": "~/Desktop/weekly.srt" }),
+            json!({ "when": "la semaine dernière", "format": "text", "path": "~/Documents/Meetings" }),
+        ]
+    }
+
+    fn confirmation_question(&self, db: &Database, input: &ExportInput) -> Option<String> {
+        if !input.recording.is_set() {
+            return None;
+        }
+        let recording = input.recording.resolve(db).ok()?;
+        Some(match input.path.as_deref() {
+            Some(path) => format!("Export the recording {} to {}?", name_and_date(&recording), path),
+            None => format!("Export the recording {} to the export folder?", name_and_date(&recording)),
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: ExportInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let path = match input.path.as_deref() {
+                Some(path) => export::expand_home(path),
+                None => match export::default_export_dir() {
+                    Ok(dir) => dir,
+                    Err(e) => return ToolResult::Error(e.to_string()),
+                },
+            };
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            if input.recording.is_set() || !input.period.is_set() {
+                let recording = match input.recording.resolve(&db) {
+                    Ok(r) => r,
+                    Err(e) => return ToolResult::Error(e),
+                };
+                if path.extension().is_none() {
+                    if let Err(e) = std::fs::create_dir_all(&path) {
+                        return ToolResult::Error(format!("Could not create {}: {}", path.display(), e));
+                    }
+                }
+                return match export::export_recording(&db, &recording, input.format, &path) {
+                    Ok(file) => ToolResult::Success(format!("Exported '{}' to {}", recording.name, file.display())),
+                    Err(e) => ToolResult::Error(format!("Failed to export: {:#}", e)),
+                };
+            }
+
+            let recordings = match input.period.resolve().and_then(|(since, until)| {
+                db.list_recordings_between(since, until).map_err(|e| e.to_string())
+            }) {
+                Ok(recordings) if recordings.is_empty() => {
+                    return ToolResult::Success("No recordings found in that period.".to_string())
+                }
+                Ok(recordings) => recordings,
+                Err(e) => return ToolResult::Error(e),
+            };
+            match export::export_recordings(&db, &recordings, input.format, &path) {
+                Ok(files) => ToolResult::Success(format!(
+                    "Exported {} recording(s) to {}",
+                    files.len(),
+                    path.display()
+                )),
+                Err(e) => ToolResult::Error(format!("Failed to export: {:#}", e)),
+            }
+        })
+    }
+}
@@ -0,0 +1,239 @@
+use crate::dates;
+use crate::intent::mentions;
+use crate::scheduler::{describe_due, due_at, ends_after};
+use crate::storage::{Reminder, ReminderKind};
+use crate::tools::{single_match, NoInput, Tool, ToolContext, ToolResult};
+use crate::SchedulerState;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use tauri::Manager;
+use uuid::Uuid;
+
+/// Wake the scheduler so it sees the change
+fn reschedule(ctx: &ToolContext) {
+    if let Some(scheduler) = ctx.app_handle.try_state::<SchedulerState>() {
+        scheduler.reschedule();
+    }
+}
+
+fn store(ctx: &ToolContext, kind: ReminderKind, message: &str, due: chrono::DateTime<Utc>) -> Result<Reminder, String> {
+    let reminder = ctx
+        .lock_db()?
+        .add_reminder(kind, message, due)
+        .map_err(|e| format!("Failed to save the {}: {}", kind.as_str(), e))?;
+    reschedule(ctx);
+    Ok(reminder)
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct TimerInput {
+    /// How long, e.g. "20 minutes", "1h30", "90 seconds", "une demi-heure"
+    duration: String,
+    /// What the timer is for (e.g. "pasta")
+    label: Option<String>,
+}
+
+pub struct SetTimer;
+
+impl Tool for SetTimer {
+    type Input = TimerInput;
+
+    fn name(&self) -> &'static str {
+        "set_timer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Start a countdown timer; the user is notified when it ends. Pass the duration in the user's words."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "duration": "10 minutes" }), json!({ "duration": "1h30", "label": "oven" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: TimerInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let duration = match dates::parse_duration(&input.duration) {
+                Some(d) => d,
+                None => {
+                    return ToolResult::Error(format!(
+                        "Couldn't understand the duration '{}' (try '20 minutes' or '1h30')",
+                        input.duration
+                    ))
+                }
+            };
+            let due = match ends_after(duration) {
+                Ok(due) => due,
+                Err(e) => return ToolResult::Error(e),
+            };
+            let label = input
+                .label
+                .filter(|l| !l.trim().is_empty())
+                .unwrap_or_else(|| format!("{} timer", input.duration.trim()));
+
+            match store(ctx, ReminderKind::Timer, &label, due) {
+                Ok(timer) => ToolResult::Success(format!("Timer '{}' set, ends at {}", timer.message, describe_due(timer.due_at))),
+                Err(e) => ToolResult::Error(e),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ReminderInput {
+    /// What to remind the user of (e.g. "join the call")
+    message: String,
+    /// When, in the user's words: "in 20 minutes", "at 15:30", "tomorrow at 9am", "demain à 14h", "lundi prochain", or "YYYY-MM-DD HH:MM"
+    when: String,
+}
+
+pub struct SetReminder;
+
+impl Tool for SetReminder {
+    type Input = ReminderInput;
+
+    fn name(&self) -> &'static str {
+        "set_reminder"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remind the user of something at a given time or after a delay. Reminders are kept across restarts."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![
+            json!({ "message": "join the call", "when": "in 20 minutes" }),
+            json!({ "message": "appeler Paul", "when": "demain à 9h" }),
+        ]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: ReminderInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            if input.message.trim().is_empty() {
+                return ToolResult::Error("The reminder message is empty".to_string());
+            }
+            let due = match due_at(&input.when) {
+                Ok(due) => due,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match store(ctx, ReminderKind::Reminder, input.message.trim(), due) {
+                Ok(reminder) => ToolResult::Success(format!(
+                    "I'll remind you to {} at {}",
+                    reminder.message,
+                    describe_due(reminder.due_at)
+                )),
+                Err(e) => ToolResult::Error(e),
+            }
+        })
+    }
+}
+
+pub struct ListReminders;
+
+impl Tool for ListReminders {
+    type Input = NoInput;
+
+    fn name(&self) -> &'static str {
+        "list_reminders"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the pending timers and reminders, soonest first"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({})]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, _input: NoInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.list_pending_reminders() {
+                Ok(reminders) if reminders.is_empty() => ToolResult::Success("No pending timers or reminders.".to_string()),
+                Ok(reminders) => {
+                    let list = reminders
+                        .iter()
+                        .map(|r| format!("- {} '{}' at {} (id: {})", r.kind.as_str(), r.message, describe_due(r.due_at), r.id))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ToolResult::Success(format!("Pending:\n{}", list))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to list reminders: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CancelReminderInput {
+    /// Id of the timer or reminder, as returned by list_reminders
+    reminder_id: Option<String>,
+    /// Words of its message or label
+    query: Option<String>,
+}
+
+pub struct CancelReminder;
+
+impl Tool for CancelReminder {
+    type Input = CancelReminderInput;
+
+    fn name(&self) -> &'static str {
+        "cancel_reminder"
+    }
+
+    fn description(&self) -> &'static str {
+        "Cancel a pending timer or reminder, by id (from list_reminders) or by words of its message"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "reminder_id": "0b0e7c2e-8a7d-4c1e-9f3a-5d2b6c1a4e90" }), json!({ "query": "call" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: CancelReminderInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+            let pending = match db.list_pending_reminders() {
+                Ok(pending) => pending,
+                Err(e) => return ToolResult::Error(format!("Failed to list reminders: {}", e)),
+            };
+
+            let found: Vec<Reminder> = match (&input.reminder_id, &input.query) {
+                (Some(id), _) => {
+                    let id = match Uuid::parse_str(id.trim()) {
+                        Ok(id) => id,
+                        Err(_) => return ToolResult::Error(format!("Invalid reminder id: {}", id)),
+                    };
+                    pending.into_iter().filter(|r| r.id == id).collect()
+                }
+                (None, Some(query)) => pending.into_iter().filter(|r| mentions(&r.message, query)).collect(),
+                (None, None) => return ToolResult::Error("Give the reminder_id or words of the reminder".to_string()),
+            };
+            let describe = |r: &Reminder| {
+                format!("{} '{}' at {} (id: {})", r.kind.as_str(), r.message, describe_due(r.due_at), r.id)
+            };
+            let reminder = match single_match(found, "pending reminder", describe) {
+                Ok(r) => r,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.delete_reminder(reminder.id) {
+                Ok(_) => {
+                    drop(db);
+                    reschedule(ctx);
+                    ToolResult::Success(format!("Cancelled the {} '{}'", reminder.kind.as_str(), reminder.message))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to cancel: {}", e)),
+            }
+        })
+    }
+}
@@ -0,0 +1,245 @@
+use crate::intent::mentions;
+use crate::storage::{Database, Todo};
+use crate::tools::{single_match, Tool, ToolContext, ToolResult};
+use futures::future::BoxFuture;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// The to-do given by id, or the one whose title contains the words of `query`: among
+/// several, only the open ones count (the done ones when `prefer_done`), and if more than
+/// one is left the error lists them
+fn find_todo(db: &Database, id: Option<&str>, query: Option<&str>, prefer_done: bool) -> Result<Todo, String> {
+    let id = id
+        .map(|id| Uuid::parse_str(id.trim()))
+        .transpose()
+        .map_err(|_| "Invalid todo_id, use an id returned by list_todos".to_string())?;
+    let todos = db.list_todos(true).map_err(|e| format!("Failed to list to-dos: {}", e))?;
+
+    let found: Vec<Todo> = match (id, query) {
+        (Some(id), _) => todos.into_iter().filter(|t| t.id == id).collect(),
+        (None, Some(query)) => todos.into_iter().filter(|t| mentions(&t.title, query)).collect(),
+        (None, None) => return Err("Please specify the todo_id or words of the to-do".to_string()),
+    };
+    let found = if found.iter().any(|t| t.done == prefer_done) {
+        found.into_iter().filter(|t| t.done == prefer_done).collect()
+    } else {
+        found
+    };
+    single_match(found, "to-do", |t| {
+        format!("[{}] {} (id: {})", if t.done { "x" } else { " " }, t.title, t.id)
+    })
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct AddTodoInput {
+    /// What to do (e.g. "review PR 42")
+    title: String,
+}
+
+pub struct AddTodo;
+
+impl Tool for AddTodo {
+    type Input = AddTodoInput;
+
+    fn name(&self) -> &'static str {
+        "add_todo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add an item to the user's to-do list ('add review PR 42 to my todo list')"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "title": "Review PR 42" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: AddTodoInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            if input.title.trim().is_empty() {
+                return ToolResult::Error("The to-do is empty".to_string());
+            }
+
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.add_todo(input.title.trim()) {
+                Ok(todo) => ToolResult::Success(format!("Added to your to-do list: {}", todo.title)),
+                Err(e) => ToolResult::Error(format!("Failed to add the to-do: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListTodosInput {
+    /// Also list the done items (default false)
+    include_done: Option<bool>,
+}
+
+pub struct ListTodos;
+
+impl Tool for ListTodos {
+    type Input = ListTodosInput;
+
+    fn name(&self) -> &'static str {
+        "list_todos"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the user's to-do list: open items oldest first, then optionally the done ones"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({}), json!({ "include_done": true })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: ListTodosInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.list_todos(input.include_done.unwrap_or(false)) {
+                Ok(todos) if todos.is_empty() => ToolResult::Success("The to-do list is empty.".to_string()),
+                Ok(todos) => {
+                    let list = todos
+                        .iter()
+                        .map(|t| format!("- [{}] {} (id: {})", if t.done { "x" } else { " " }, t.title, t.id))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ToolResult::Success(format!("To-do list:\n{}", list))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to list to-dos: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CompleteTodoInput {
+    /// Id of the to-do, as returned by list_todos
+    todo_id: Option<String>,
+    /// Words of the to-do
+    query: Option<String>,
+    /// false to open the item again (default true)
+    done: Option<bool>,
+}
+
+pub struct CompleteTodo;
+
+impl Tool for CompleteTodo {
+    type Input = CompleteTodoInput;
+
+    fn name(&self) -> &'static str {
+        "complete_todo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Check off a to-do, by id (from list_todos) or by words of it. Pass done: false to uncheck it."
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![
+            json!({ "query": "PR 42" }),
+            json!({ "todo_id": "0b0e7c2e-8a7d-4c1e-9f3a-5d2b6c1a4e90", "done": false }),
+        ]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: CompleteTodoInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let done = input.done.unwrap_or(true);
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+            let todo = match find_todo(&db, input.todo_id.as_deref(), input.query.as_deref(), !done) {
+                Ok(todo) => todo,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.set_todo_done(todo.id, done) {
+                Ok(_) if done => ToolResult::Success(format!("Done: {}", todo.title)),
+                Ok(_) => ToolResult::Success(format!("Back on the list: {}", todo.title)),
+                Err(e) => ToolResult::Error(format!("Failed to update the to-do: {}", e)),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DeleteTodoInput {
+    /// Id of the to-do, as returned by list_todos
+    todo_id: Option<String>,
+    /// Words of the to-do
+    query: Option<String>,
+}
+
+pub struct DeleteTodo;
+
+impl Tool for DeleteTodo {
+    type Input = DeleteTodoInput;
+
+    fn name(&self) -> &'static str {
+        "delete_todo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove a to-do from the list without completing it, by id (from list_todos) or by words of it"
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        vec![json!({ "todo_id": "0b0e7c2e-8a7d-4c1e-9f3a-5d2b6c1a4e90" }), json!({ "query": "PR 42" })]
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: DeleteTodoInput) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let db = match ctx.lock_db() {
+                Ok(db) => db,
+                Err(e) => return ToolResult::Error(e),
+            };
+            let todo = match find_todo(&db, input.todo_id.as_deref(), input.query.as_deref(), false) {
+                Ok(todo) => todo,
+                Err(e) => return ToolResult::Error(e),
+            };
+
+            match db.delete_todo(todo.id) {
+                Ok(_) => ToolResult::Success(format!("Removed from your to-do list: {}", todo.title)),
+                Err(e) => ToolResult::Error(format!("Failed to delete the to-do: {}", e)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_todo() {
+        let db = Database::in_memory().unwrap();
+        let open = db.add_todo("Review PR 42").unwrap();
+        let done = db.add_todo("Review PR 42 tests").unwrap();
+        db.set_todo_done(done.id, true).unwrap();
+        db.add_todo("Book the train").unwrap();
+        db.add_todo("Book the hotel").unwrap();
+
+        // The open match wins, or the done one when reopening
+        assert_eq!(find_todo(&db, None, Some("pr 42"), false).unwrap().id, open.id);
+        assert_eq!(find_todo(&db, None, Some("pr 42"), true).unwrap().id, done.id);
+        // Only a done to-do matches: it's found whatever is preferred
+        assert_eq!(find_todo(&db, None, Some("tests"), false).unwrap().id, done.id);
+        assert_eq!(find_todo(&db, Some(&open.id.to_string()), None, true).unwrap().id, open.id);
+
+        let error = find_todo(&db, None, Some("book"), false).unwrap_err();
+        assert!(error.starts_with("2 to-dos match"), "{}", error);
+        assert!(error.contains("Book the train") && error.contains("Book the hotel"), "{}", error);
+        assert_eq!(find_todo(&db, None, Some("budget"), false).unwrap_err(), "No matching to-do found");
+        assert!(find_todo(&db, Some("42"), None, false).is_err());
+        assert!(find_todo(&db, None, None, false).is_err());
+    }
+}
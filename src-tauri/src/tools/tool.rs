@@ -0,0 +1,258 @@
+use crate::llm::ToolDefinition;
+use crate::state::AppState;
+use crate::storage::Database;
+use crate::tools::ToolResult;
+use crate::usage::UsageRecorder;
+use crate::DbState;
+use futures::future::BoxFuture;
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::sync::{Arc, MutexGuard, OnceLock};
+use tauri::AppHandle;
+use tokio::sync::RwLock;
+
+use super::{app, memories, notes, quick_notes, recordings, reminders, todos};
+
+/// A built-in tool: its name, description and input schema are what the model sees,
+/// and the schema is derived from the type `execute` receives, so they can't drift apart
+pub trait Tool: Send + Sync {
+    /// Arguments of a call (field doc comments become the schema's descriptions)
+    type Input: DeserializeOwned + JsonSchema + Send;
+
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Typical inputs, checked against the schema by the tests
+    #[cfg_attr(not(test), allow(dead_code))]
+    fn examples(&self) -> Vec<serde_json::Value>;
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, input: Self::Input) -> BoxFuture<'a, ToolResult>;
+
+    /// Question asked before a call that needs the user's confirmation, naming what it
+    /// acts on. None falls back to the generic question of the tool policy.
+    fn confirmation_question(&self, _db: &Database, _input: &Self::Input) -> Option<String> {
+        None
+    }
+}
+
+/// Input of tools without arguments
+#[derive(Deserialize, JsonSchema)]
+pub struct NoInput {}
+
+/// What local tools run with
+#[derive(Clone)]
+pub struct ToolContext {
+    pub app_handle: AppHandle,
+    pub state: Arc<RwLock<AppState>>,
+    pub db: Option<DbState>,
+    /// LLM calls made by tools (summaries) are accounted to the current command
+    pub usage: Option<Arc<dyn UsageRecorder>>,
+}
+
+impl ToolContext {
+    pub fn db(&self) -> Result<&DbState, String> {
+        self.db.as_ref().ok_or_else(|| "Database not initialized".to_string())
+    }
+
+    pub fn lock_db(&self) -> Result<MutexGuard<'_, Database>, String> {
+        self.db()?.lock().map_err(|e| format!("Database lock error: {}", e))
+    }
+}
+
+/// The one item a query matched. Several matches are an error listing them, so the
+/// model asks the user which one instead of acting on a guess.
+pub fn single_match<T>(mut matches: Vec<T>, what: &str, describe: impl Fn(&T) -> String) -> Result<T, String> {
+    match matches.len() {
+        0 => Err(format!("No matching {} found", what)),
+        1 => Ok(matches.remove(0)),
+        n => {
+            let list = matches.iter().map(|m| format!("- {}", describe(m))).collect::<Vec<_>>().join("\n");
+            Err(format!("{} {}s match, ask which one and retry with its id:\n{}", n, what, list))
+        }
+    }
+}
+
+/// JSON schema of a tool input, as sent to the model
+fn input_schema<T: JsonSchema>() -> serde_json::Value {
+    let mut schema = SchemaSettings::draft07()
+        .with(|settings| {
+            settings.meta_schema = None;
+            settings.inline_subschemas = true;
+        })
+        .for_deserialize()
+        .into_generator()
+        .into_root_schema_for::<T>();
+    if let Some(root) = schema.as_object_mut() {
+        root.remove("title");
+        root.remove("description");
+    }
+    schema.to_value()
+}
+
+/// Object-safe side of `Tool`, so tools with different inputs share the registry
+trait AnyTool: Send + Sync {
+    fn tool_name(&self) -> &'static str;
+    fn definition(&self) -> ToolDefinition;
+    #[cfg_attr(not(test), allow(dead_code))]
+    fn examples(&self) -> Vec<serde_json::Value>;
+    fn call<'a>(&'a self, ctx: &'a ToolContext, input: serde_json::Value) -> BoxFuture<'a, ToolResult>;
+    fn question(&self, db: &Database, input: &serde_json::Value) -> Option<String>;
+}
+
+impl<T: Tool> AnyTool for T {
+    fn tool_name(&self) -> &'static str {
+        self.name()
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: self.name().to_string(),
+            description: self.description().to_string(),
+            input_schema: input_schema::<T::Input>(),
+        }
+    }
+
+    fn examples(&self) -> Vec<serde_json::Value> {
+        Tool::examples(self)
+    }
+
+    fn call<'a>(&'a self, ctx: &'a ToolContext, input: serde_json::Value) -> BoxFuture<'a, ToolResult> {
+        match serde_json::from_value(input) {
+            Ok(input) => self.execute(ctx, input),
+            Err(e) => Box::pin(async move { ToolResult::Error(format!("Invalid input: {}", e)) }),
+        }
+    }
+
+    fn question(&self, db: &Database, input: &serde_json::Value) -> Option<String> {
+        let input = serde_json::from_value(input.clone()).ok()?;
+        self.confirmation_question(db, &input)
+    }
+}
+
+/// The local tools, in the order they are offered to the model
+pub struct ToolRegistry {
+    tools: Vec<Box<dyn AnyTool>>,
+}
+
+impl ToolRegistry {
+    fn new() -> Self {
+        Self { tools: Vec::new() }
+    }
+
+    fn register(mut self, tool: impl Tool + 'static) -> Self {
+        assert!(self.get(tool.name()).is_none(), "tool {} registered twice", tool.name());
+        self.tools.push(Box::new(tool));
+        self
+    }
+
+    fn get(&self, name: &str) -> Option<&dyn AnyTool> {
+        self.tools.iter().find(|t| t.tool_name() == name).map(|t| t.as_ref())
+    }
+
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        self.tools.iter().map(|t| t.definition()).collect()
+    }
+
+    /// The tool's own confirmation question for this call, if it has one
+    pub fn confirmation_question(&self, ctx: &ToolContext, name: &str, input: &serde_json::Value) -> Option<String> {
+        self.question(&*ctx.lock_db().ok()?, name, input)
+    }
+
+    pub(crate) fn question(&self, db: &Database, name: &str, input: &serde_json::Value) -> Option<String> {
+        self.get(name)?.question(db, input)
+    }
+
+    pub async fn execute(&self, ctx: &ToolContext, name: &str, input: serde_json::Value) -> ToolResult {
+        match self.get(name) {
+            Some(tool) => tool.call(ctx, input).await,
+            None => ToolResult::Error(format!("Unknown local tool: {}", name)),
+        }
+    }
+}
+
+/// Robert's built-in tools
+pub fn local_tools() -> &'static ToolRegistry {
+    static REGISTRY: OnceLock<ToolRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        ToolRegistry::new()
+            .register(app::Quit)
+            .register(recordings::ListRecordings)
+            .register(recordings::FindRecordings)
+            .register(recordings::SummarizeRecording)
+            .register(recordings::StartRecording)
+            .register(recordings::StopRecording)
+            .register(recordings::GetRecordingContent)
+            .register(recordings::RenameRecording)
+            .register(recordings::DeleteRecording)
+            .register(recordings::SearchTranscripts)
+            .register(recordings::ExportRecording)
+            .register(notes::GetMeetingNotes)
+            .register(notes::ListActionItems)
+            .register(memories::RememberFact)
+            .register(memories::RecallFacts)
+            .register(memories::ForgetFact)
+            .register(reminders::SetTimer)
+            .register(reminders::SetReminder)
+            .register(reminders::ListReminders)
+            .register(reminders::CancelReminder)
+            .register(quick_notes::TakeNote)
+            .register(quick_notes::ListNotes)
+            .register(quick_notes::DeleteNote)
+            .register(todos::AddTodo)
+            .register(todos::ListTodos)
+            .register(todos::CompleteTodo)
+            .register(todos::DeleteTodo)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_single_match() {
+        let describe = |s: &&str| s.to_string();
+        assert_eq!(single_match(vec!["a"], "note", describe), Ok("a"));
+        assert_eq!(single_match(Vec::<&str>::new(), "note", describe), Err("No matching note found".to_string()));
+        assert_eq!(
+            single_match(vec!["a", "b"], "note", describe),
+            Err("2 notes match, ask which one and retry with its id:\n- a\n- b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_schemas_accept_their_examples() {
+        for tool in &local_tools().tools {
+            let definition = tool.definition();
+            assert_eq!(definition.input_schema["type"], "object", "{}", definition.name);
+            let validator = jsonschema::validator_for(&definition.input_schema)
+                .unwrap_or_else(|e| panic!("{}: invalid schema: {}", definition.name, e));
+
+            let examples = tool.examples();
+            assert!(!examples.is_empty(), "{} has no examples", definition.name);
+            for example in examples {
+                if let Err(e) = validator.validate(&example) {
+                    panic!("{}: example {} rejected: {}", definition.name, example, e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_schema_from_input_type() {
+        let definitions = local_tools().definitions();
+        let rename = definitions.iter().find(|d| d.name == "rename_recording").unwrap();
+        let schema = &rename.input_schema;
+
+        assert_eq!(schema["required"], json!(["new_name"]));
+        assert_eq!(schema["properties"]["new_name"]["description"], "The new name for the recording");
+        assert!(schema["properties"]["recording_index"].is_object());
+        assert!(schema.get("title").is_none() && schema.get("$schema").is_none());
+        assert!(!jsonschema::is_valid(schema, &json!({ "recording_name": "Budget" })));
+        assert!(!jsonschema::is_valid(schema, &json!({ "recording_index": "two", "new_name": "x" })));
+    }
+}
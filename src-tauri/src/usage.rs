@@ -0,0 +1,342 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Months, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::llm::{LlmError, LlmErrorKind};
+use crate::storage::{Database, UsagePeriod, UsageRecord};
+use crate::DbState;
+
+// ============================================================================
+// Token usage and pricing
+// ============================================================================
+
+/// Tokens billed for one API call (Anthropic field names)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+}
+
+impl Usage {
+    /// Merge counters from a later stream event (values are cumulative)
+    pub fn update(&mut self, other: &Usage) {
+        self.input_tokens = self.input_tokens.max(other.input_tokens);
+        self.output_tokens = self.output_tokens.max(other.output_tokens);
+        self.cache_creation_input_tokens = self.cache_creation_input_tokens.max(other.cache_creation_input_tokens);
+        self.cache_read_input_tokens = self.cache_read_input_tokens.max(other.cache_read_input_tokens);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Usage::default()
+    }
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    #[serde(default)]
+    pub cache_write: f64,
+    #[serde(default)]
+    pub cache_read: f64,
+}
+
+impl ModelPrice {
+    const fn new(input: f64, output: f64, cache_write: f64, cache_read: f64) -> Self {
+        Self { input, output, cache_write, cache_read }
+    }
+
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens as f64 * self.cache_write
+            + usage.cache_read_input_tokens as f64 * self.cache_read)
+            / 1_000_000.0
+    }
+}
+
+/// Built-in prices, matched by model name prefix (local models are free)
+const DEFAULT_PRICES: &[(&str, ModelPrice)] = &[
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0, 18.75, 1.5)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-5-haiku", ModelPrice::new(0.8, 4.0, 1.0, 0.08)),
+    ("claude-haiku-4", ModelPrice::new(1.0, 5.0, 1.25, 0.1)),
+    ("claude-3-haiku", ModelPrice::new(0.25, 1.25, 0.3, 0.03)),
+];
+
+/// Price tables and spending limit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageSettings {
+    /// Per-model price overrides, keyed by model name or prefix
+    #[serde(default)]
+    pub prices: HashMap<String, ModelPrice>,
+    /// LLM calls are refused once this month's spending reaches it
+    #[serde(default)]
+    pub monthly_budget_usd: Option<f64>,
+}
+
+impl UsageSettings {
+    /// Price for a model: longest matching override, then built-in table
+    pub fn price_for(&self, model: &str) -> Option<ModelPrice> {
+        let overridden = self
+            .prices
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| *price);
+
+        overridden.or_else(|| {
+            DEFAULT_PRICES
+                .iter()
+                .find(|(prefix, _)| model.starts_with(prefix))
+                .map(|(_, price)| *price)
+        })
+    }
+
+    pub fn cost(&self, model: &str, usage: &Usage) -> f64 {
+        self.price_for(model).map(|p| p.cost(usage)).unwrap_or(0.0)
+    }
+}
+
+/// Receives the usage of every API call, and may refuse new calls (budget)
+pub trait UsageRecorder: Send + Sync {
+    /// Called before each API call; an error aborts it
+    fn check_budget(&self) -> Result<()>;
+
+    fn record(&self, model: &str, usage: &Usage);
+}
+
+// ============================================================================
+// Stored usage and monthly budget
+// ============================================================================
+
+/// Stores the usage of one command's API calls and enforces the monthly budget
+pub struct CommandUsage {
+    db: DbState,
+    command: String,
+    settings: UsageSettings,
+}
+
+impl CommandUsage {
+    pub fn new(db: DbState, command: &str, settings: UsageSettings) -> Self {
+        Self {
+            db,
+            command: command.to_string(),
+            settings,
+        }
+    }
+}
+
+impl UsageRecorder for CommandUsage {
+    fn check_budget(&self) -> Result<()> {
+        let budget = match self.settings.monthly_budget_usd {
+            Some(budget) => budget,
+            None => return Ok(()),
+        };
+
+        let spent = {
+            let db = self.db.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+            summarize_usage(&db, &self.settings, UsagePeriod::Month, start_of_month(Utc::now()))?
+                .first()
+                .map(|s| s.cost_usd)
+                .unwrap_or(0.0)
+        };
+
+        if spent >= budget {
+            return Err(LlmError::new(
+                LlmErrorKind::BudgetExceeded,
+                format!("spent ${:.2} of ${:.2} this month", spent, budget),
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn record(&self, model: &str, usage: &Usage) {
+        if usage.is_empty() {
+            return;
+        }
+
+        println!(
+            "[Usage] {}: {} in, {} out, {} cache write, {} cache read (${:.4})",
+            model,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_creation_input_tokens,
+            usage.cache_read_input_tokens,
+            self.settings.cost(model, usage)
+        );
+
+        let record = UsageRecord {
+            timestamp: Utc::now(),
+            command: self.command.clone(),
+            model: model.to_string(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_tokens: usage.cache_creation_input_tokens,
+            cache_read_tokens: usage.cache_read_input_tokens,
+        };
+        if let Ok(db) = self.db.lock() {
+            if let Err(e) = db.add_usage(&record) {
+                eprintln!("[Usage] Failed to store usage: {}", e);
+            }
+        }
+    }
+}
+
+/// Usage and cost over one day or month, all models together
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageSummary {
+    pub period: String,
+    pub calls: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// Totals per period since `since`, most recent first, priced with the current tables
+pub fn summarize_usage(
+    db: &Database,
+    settings: &UsageSettings,
+    period: UsagePeriod,
+    since: DateTime<Utc>,
+) -> Result<Vec<UsageSummary>> {
+    let mut summaries: Vec<UsageSummary> = Vec::new();
+
+    // Rows are ordered by period, one per model
+    for totals in db.get_usage_totals(period, since)? {
+        let usage = Usage {
+            input_tokens: totals.input_tokens,
+            output_tokens: totals.output_tokens,
+            cache_creation_input_tokens: totals.cache_creation_tokens,
+            cache_read_input_tokens: totals.cache_read_tokens,
+        };
+
+        if summaries.last().map(|s| s.period != totals.period).unwrap_or(true) {
+            summaries.push(UsageSummary {
+                period: totals.period.clone(),
+                ..Default::default()
+            });
+        }
+        let summary = summaries.last_mut().unwrap();
+        summary.calls += totals.calls;
+        summary.input_tokens += usage.input_tokens;
+        summary.output_tokens += usage.output_tokens;
+        summary.cache_creation_tokens += usage.cache_creation_input_tokens;
+        summary.cache_read_tokens += usage.cache_read_input_tokens;
+        summary.cost_usd += settings.cost(&totals.model, &usage);
+    }
+
+    Ok(summaries)
+}
+
+pub fn start_of_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now)
+}
+
+/// Start of the month `months - 1` months before the current one
+pub fn months_ago(now: DateTime<Utc>, months: u32) -> DateTime<Utc> {
+    let start = start_of_month(now);
+    start
+        .checked_sub_months(Months::new(months.saturating_sub(1)))
+        .unwrap_or(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_price_lookup_and_cost() {
+        let mut settings = UsageSettings::default();
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 1_000_000,
+        };
+
+        // 3 + 1.5 + 0.3
+        let cost = settings.cost("claude-sonnet-4-20250514", &usage);
+        assert!((cost - 4.8).abs() < 1e-9);
+        assert_eq!(settings.cost("llama3.1", &usage), 0.0);
+
+        settings.prices.insert("llama".to_string(), ModelPrice::new(1.0, 0.0, 0.0, 0.0));
+        assert!((settings.cost("llama3.1", &usage) - 1.0).abs() < 1e-9);
+    }
+    fn store(db: &Database, timestamp: &str, model: &str, input_tokens: u64) {
+        db.add_usage(&UsageRecord {
+            timestamp: DateTime::parse_from_rfc3339(timestamp).unwrap().with_timezone(&Utc),
+            command: "test".to_string(),
+            model: model.to_string(),
+            input_tokens,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_daily_and_monthly_totals() {
+        let db = Database::in_memory().unwrap();
+        let settings = UsageSettings::default();
+        store(&db, "2026-02-28T23:00:00+00:00", "claude-sonnet-4", 1_000_000);
+        store(&db, "2026-03-10T09:00:00+00:00", "claude-sonnet-4", 1_000_000);
+        store(&db, "2026-03-10T10:00:00+00:00", "claude-haiku-4", 1_000_000);
+        store(&db, "2026-03-11T08:00:00+00:00", "claude-sonnet-4", 500_000);
+
+        let since = DateTime::parse_from_rfc3339("2026-03-01T00:00:00+00:00").unwrap().with_timezone(&Utc);
+        let days = summarize_usage(&db, &settings, UsagePeriod::Day, since).unwrap();
+        let periods: Vec<&str> = days.iter().map(|d| d.period.as_str()).collect();
+        assert_eq!(periods, ["2026-03-11", "2026-03-10"]);
+        assert_eq!(days[1].calls, 2);
+        assert_eq!(days[1].input_tokens, 2_000_000);
+        // Each model at its own price: 3 + 1
+        assert!((days[1].cost_usd - 4.0).abs() < 1e-9);
+
+        let since = DateTime::parse_from_rfc3339("2026-02-01T00:00:00+00:00").unwrap().with_timezone(&Utc);
+        let months = summarize_usage(&db, &settings, UsagePeriod::Month, since).unwrap();
+        let periods: Vec<&str> = months.iter().map(|m| m.period.as_str()).collect();
+        assert_eq!(periods, ["2026-03", "2026-02"]);
+        assert_eq!(months[0].calls, 3);
+        assert!((months[0].cost_usd - 5.5).abs() < 1e-9);
+        assert_eq!(months[1].calls, 1);
+    }
+
+    #[test]
+    fn test_monthly_budget_blocks_calls() {
+        let db: DbState = Arc::new(Mutex::new(Database::in_memory().unwrap()));
+        let settings = UsageSettings {
+            monthly_budget_usd: Some(5.0),
+            ..Default::default()
+        };
+        let usage = CommandUsage::new(db, "test", settings);
+        let call = Usage {
+            input_tokens: 1_000_000,
+            ..Default::default()
+        };
+
+        usage.record("claude-sonnet-4", &call);
+        assert!(usage.check_budget().is_ok());
+
+        usage.record("claude-sonnet-4", &call);
+        let err = usage.check_budget().unwrap_err();
+        assert_eq!(crate::llm::error_kind(&err), Some(LlmErrorKind::BudgetExceeded));
+    }
+}
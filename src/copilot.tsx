@@ -10,7 +10,7 @@ marked.setOptions({
   async: false,
 });
 
-type CopilotStateType = "listening" | "thinking" | "responding" | "idle";
+type CopilotStateType = "listening" | "thinking" | "confirming" | "responding" | "idle";
 
 interface CopilotUIState {
   visible: boolean;
@@ -18,6 +18,9 @@ interface CopilotUIState {
   response_text: string;
   should_close: boolean;
   heard_text: string;
+  follow_up: boolean;
+  error: string | null;
+  confirmation: string | null;
 }
 
 interface WaveAnimationProps {
@@ -106,6 +109,9 @@ function Copilot() {
   const [state, setState] = useState<CopilotStateType>("idle");
   const [displayedText, setDisplayedText] = useState("");
   const [heardText, setHeardText] = useState("");
+  const [followUp, setFollowUp] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [confirmation, setConfirmation] = useState<string | null>(null);
   const [isHovered, setIsHovered] = useState(false);
 
   const lastResponseTextRef = useRef<string>("");
@@ -155,6 +161,9 @@ function Copilot() {
 
         // Update heard text
         setHeardText(backendState.heard_text || "");
+        setFollowUp(backendState.follow_up);
+        setError(backendState.error);
+        setConfirmation(backendState.confirmation);
 
         // Handle text changes - find new characters to animate
         if (backendState.response_text !== lastResponseTextRef.current) {
@@ -176,8 +185,8 @@ function Copilot() {
           lastResponseTextRef.current = newText;
         }
 
-        // Handle close signal with fade-out
-        if (backendState.should_close && !isClosingRef.current) {
+        // Handle close signal with fade-out (kept open while waiting for a follow-up)
+        if (backendState.should_close && !backendState.follow_up && !isClosingRef.current) {
           isClosingRef.current = true;
           // Start fade after 4.5s
           closeTimeoutRef.current = window.setTimeout(() => {
@@ -205,8 +214,8 @@ function Copilot() {
           }, 4500);
         }
 
-        // Reset closing state if should_close became false (new command started)
-        if (!backendState.should_close && isClosingRef.current) {
+        // Reset closing state if should_close became false (new command started) or a follow-up opened
+        if ((!backendState.should_close || backendState.follow_up) && isClosingRef.current) {
           if (closeTimeoutRef.current) {
             clearTimeout(closeTimeoutRef.current);
             closeTimeoutRef.current = null;
@@ -241,6 +250,8 @@ function Copilot() {
           clearTimeout(fadeTimeoutRef.current);
           fadeTimeoutRef.current = null;
         }
+        // Interrupt the command still running, if any
+        await invoke("cancel_command");
         await invoke("hide_copilot");
         await invoke("set_copilot_alpha", { alpha: 1.0 });
         setState("idle");
@@ -270,6 +281,15 @@ function Copilot() {
     return "";
   };
 
+  const answerConfirmation = async (confirmed: boolean) => {
+    try {
+      await invoke("answer_confirmation", { confirmed });
+    } catch (e) {
+      // Already answered by voice, or timed out
+      console.error("[Copilot] Confirmation error:", e);
+    }
+  };
+
   const handleClose = async () => {
     if (closeTimeoutRef.current) {
       clearTimeout(closeTimeoutRef.current);
@@ -279,6 +299,7 @@ function Copilot() {
       clearTimeout(fadeTimeoutRef.current);
       fadeTimeoutRef.current = null;
     }
+    await invoke("cancel_command");
     await invoke("hide_copilot");
     await invoke("set_copilot_alpha", { alpha: 1.0 });
     setState("idle");
@@ -325,6 +346,20 @@ function Copilot() {
         {(state === "listening" || state === "thinking") && !heardText && (
           <p style={statusTextStyle}>{getStatusText()}</p>
         )}
+        {state === "confirming" && confirmation && (
+          <div style={confirmationStyle}>
+            <p style={statusTextStyle}>{confirmation}</p>
+            <div style={confirmationButtonsStyle}>
+              <button style={confirmButtonStyle} onClick={() => answerConfirmation(true)}>
+                Yes
+              </button>
+              <button style={declineButtonStyle} onClick={() => answerConfirmation(false)}>
+                No
+              </button>
+            </div>
+            <p style={followUpTextStyle}>Say yes or no</p>
+          </div>
+        )}
         {state === "responding" && (
           <div
             className="markdown-response"
@@ -334,6 +369,12 @@ function Copilot() {
             }}
           />
         )}
+        {error && state === "responding" && (
+          <p style={errorTextStyle}>{error.replace(/_/g, " ")}</p>
+        )}
+        {followUp && state === "responding" && (
+          <p style={followUpTextStyle}>Listening for your reply...</p>
+        )}
       </div>
       <style>{`
         .markdown-response p {
@@ -422,6 +463,50 @@ const responseTextStyle: React.CSSProperties = {
   wordBreak: "break-word",
 };
 
+const followUpTextStyle: React.CSSProperties = {
+  color: "#007aff",
+  fontSize: "12px",
+  textAlign: "center",
+  marginTop: "10px",
+  animation: "pulse-dot 1.5s ease-in-out infinite",
+};
+
+const errorTextStyle: React.CSSProperties = {
+  color: "#ff3b30",
+  fontSize: "11px",
+  textAlign: "center",
+  textTransform: "uppercase",
+  letterSpacing: "0.5px",
+  marginTop: "10px",
+};
+
+const confirmationStyle: React.CSSProperties = {
+  display: "flex",
+  flexDirection: "column",
+  alignItems: "center",
+};
+
+const confirmationButtonsStyle: React.CSSProperties = {
+  display: "flex",
+  gap: "12px",
+  marginTop: "4px",
+};
+
+const confirmButtonStyle: React.CSSProperties = {
+  background: "#007aff",
+  color: "#fff",
+  border: "none",
+  borderRadius: "6px",
+  padding: "6px 20px",
+  fontSize: "14px",
+  cursor: "pointer",
+};
+
+const declineButtonStyle: React.CSSProperties = {
+  ...confirmButtonStyle,
+  background: "rgba(255,255,255,0.15)",
+};
+
 const heardTextStyle: React.CSSProperties = {
   color: "#ff9500",
   fontSize: "14px",
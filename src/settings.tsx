@@ -19,6 +19,8 @@ interface Settings {
   system_audio_device: string | null;
   anthropic_api_key: string | null;
   mcp_servers: McpServerConfig[];
+  system_prompt?: string | null;
+  user_name?: string | null;
 }
 
 interface ModelInfo {
@@ -40,6 +42,38 @@ interface Recording {
   is_active: boolean;
 }
 
+interface Note {
+  id: string;
+  content: string;
+  created_at: string;
+  updated_at: string;
+}
+
+interface Todo {
+  id: string;
+  title: string;
+  done: boolean;
+  created_at: string;
+  completed_at: string | null;
+}
+
+interface TranscriptMatch {
+  recording_id: string;
+  recording_name: string;
+  transcription_id: string;
+  timestamp: string;
+  snippet: string;
+}
+
+/** Snippet with the matched words (between **) in bold */
+function Snippet({ text }: { text: string }) {
+  return (
+    <>
+      {text.split("**").map((part, i) => (i % 2 === 1 ? <strong key={i}>{part}</strong> : <span key={i}>{part}</span>))}
+    </>
+  );
+}
+
 function SettingsPage() {
   const [settings, setSettings] = useState<Settings>({
     speech_threshold: 0.006,
@@ -57,9 +91,19 @@ function SettingsPage() {
   const [wakeWordsText, setWakeWordsText] = useState("");
   const [apiKey, setApiKey] = useState("");
   const [saved, setSaved] = useState(false);
+  const [promptPreview, setPromptPreview] = useState<string | null>(null);
   const [editingId, setEditingId] = useState<string | null>(null);
   const [editingName, setEditingName] = useState("");
-  const [activeTab, setActiveTab] = useState<"settings" | "recordings" | "mcp">("settings");
+  const [searchQuery, setSearchQuery] = useState("");
+  const [searchResults, setSearchResults] = useState<TranscriptMatch[] | null>(null);
+  const [exportFormat, setExportFormat] = useState("markdown");
+  const [exportFolder, setExportFolder] = useState("");
+  const [exportStatus, setExportStatus] = useState<string | null>(null);
+  const [notes, setNotes] = useState<Note[]>([]);
+  const [todos, setTodos] = useState<Todo[]>([]);
+  const [newNote, setNewNote] = useState("");
+  const [newTodo, setNewTodo] = useState("");
+  const [activeTab, setActiveTab] = useState<"settings" | "recordings" | "notes" | "mcp">("settings");
 
   // MCP server state
   const [newMcpServer, setNewMcpServer] = useState({ id: "", name: "", url: "" });
@@ -89,6 +133,7 @@ function SettingsPage() {
       const d = await invoke<DeviceInfo[]>("list_audio_devices");
       setDevices(d);
       await loadRecordings();
+      await loadNotes();
     } catch (e) {
       console.error("Failed to load settings:", e);
     }
@@ -103,6 +148,15 @@ function SettingsPage() {
     }
   }
 
+  async function loadNotes() {
+    try {
+      setNotes(await invoke<Note[]>("list_notes"));
+      setTodos(await invoke<Todo[]>("list_todos", { includeDone: true }));
+    } catch (e) {
+      console.error("Failed to load notes:", e);
+    }
+  }
+
   async function saveSettings() {
     try {
       const newSettings: Settings = {
@@ -119,6 +173,55 @@ function SettingsPage() {
     }
   }
 
+  async function previewSystemPrompt() {
+    try {
+      const preview = await invoke<string>("preview_system_prompt", {
+        template: settings.system_prompt || null,
+      });
+      setPromptPreview(preview);
+    } catch (e) {
+      setPromptPreview(`Error: ${e}`);
+    }
+  }
+
+  async function searchTranscripts() {
+    if (!searchQuery.trim()) {
+      setSearchResults(null);
+      return;
+    }
+    try {
+      const results = await invoke<TranscriptMatch[]>("search_transcripts", { query: searchQuery });
+      setSearchResults(results);
+    } catch (e) {
+      console.error("Failed to search transcripts:", e);
+    }
+  }
+
+  async function exportRecording(id: string) {
+    try {
+      const path = await invoke<string>("export_recording", {
+        recordingId: id,
+        format: exportFormat,
+        path: exportFolder.trim() || null,
+      });
+      setExportStatus(`Exported to ${path}`);
+    } catch (e) {
+      setExportStatus(`Export failed: ${e}`);
+    }
+  }
+
+  async function exportAllRecordings() {
+    try {
+      const paths = await invoke<string[]>("export_recordings", {
+        format: exportFormat,
+        folder: exportFolder.trim() || null,
+      });
+      setExportStatus(`Exported ${paths.length} recording(s)`);
+    } catch (e) {
+      setExportStatus(`Export failed: ${e}`);
+    }
+  }
+
   async function renameRecording(id: string, newName: string) {
     try {
       await invoke("rename_recording", { recordingId: id, newName });
@@ -139,6 +242,55 @@ function SettingsPage() {
     }
   }
 
+  async function addNote() {
+    if (!newNote.trim()) return;
+    try {
+      await invoke("add_note", { content: newNote });
+      setNewNote("");
+      await loadNotes();
+    } catch (e) {
+      console.error("Failed to add note:", e);
+    }
+  }
+
+  async function deleteNote(id: string) {
+    try {
+      await invoke("delete_note", { noteId: id });
+      await loadNotes();
+    } catch (e) {
+      console.error("Failed to delete note:", e);
+    }
+  }
+
+  async function addTodo() {
+    if (!newTodo.trim()) return;
+    try {
+      await invoke("add_todo", { title: newTodo });
+      setNewTodo("");
+      await loadNotes();
+    } catch (e) {
+      console.error("Failed to add to-do:", e);
+    }
+  }
+
+  async function setTodoDone(id: string, done: boolean) {
+    try {
+      await invoke("set_todo_done", { todoId: id, done });
+      await loadNotes();
+    } catch (e) {
+      console.error("Failed to update to-do:", e);
+    }
+  }
+
+  async function deleteTodo(id: string) {
+    try {
+      await invoke("delete_todo", { todoId: id });
+      await loadNotes();
+    } catch (e) {
+      console.error("Failed to delete to-do:", e);
+    }
+  }
+
   // MCP Server functions
   async function addMcpServer() {
     if (!newMcpServer.id || !newMcpServer.name || !newMcpServer.url) {
@@ -240,6 +392,15 @@ function SettingsPage() {
         <button style={tabStyle(activeTab === "recordings")} onClick={() => setActiveTab("recordings")}>
           Recordings ({recordings.length})
         </button>
+        <button
+          style={tabStyle(activeTab === "notes")}
+          onClick={() => {
+            setActiveTab("notes");
+            loadNotes();
+          }}
+        >
+          Notes ({todos.filter((t) => !t.done).length})
+        </button>
         <button style={tabStyle(activeTab === "mcp")} onClick={() => setActiveTab("mcp")}>
           MCP Servers ({settings.mcp_servers.filter((s) => s.enabled).length})
         </button>
@@ -264,6 +425,56 @@ function SettingsPage() {
             </label>
           </section>
 
+          <section style={{ marginBottom: "24px" }}>
+            <h2 style={{ fontSize: "18px", marginBottom: "12px" }}>Assistant</h2>
+
+            <label style={{ display: "block", marginBottom: "16px" }}>
+              <span style={{ display: "block", marginBottom: "4px" }}>Your Name</span>
+              <input
+                type="text"
+                value={settings.user_name || ""}
+                onChange={(e) => setSettings({ ...settings, user_name: e.target.value || null })}
+                style={inputStyle}
+              />
+            </label>
+
+            <label style={{ display: "block", marginBottom: "16px" }}>
+              <span style={{ display: "block", marginBottom: "4px" }}>System Prompt</span>
+              <textarea
+                value={settings.system_prompt || ""}
+                onChange={(e) => setSettings({ ...settings, system_prompt: e.target.value || null })}
+                placeholder="Leave empty for the default prompt"
+                rows={8}
+                style={{ ...inputStyle, fontFamily: "monospace", fontSize: "12px" }}
+              />
+              <small style={{ color: "#666", display: "block", marginTop: "4px" }}>
+                Variables: {"{{date}} {{time}} {{weekday}} {{timezone}} {{locale}} {{user_name}} "}
+                {"{{active_recording}} {{recording_count}} {{mcp_servers}}"}
+              </small>
+            </label>
+
+            <button
+              onClick={previewSystemPrompt}
+              style={{ padding: "4px 12px", background: "#e0e0e0", border: "none", borderRadius: "4px", cursor: "pointer" }}
+            >
+              Preview
+            </button>
+            {promptPreview !== null && (
+              <pre
+                style={{
+                  marginTop: "8px",
+                  padding: "8px",
+                  background: "#f5f5f5",
+                  borderRadius: "4px",
+                  fontSize: "12px",
+                  whiteSpace: "pre-wrap",
+                }}
+              >
+                {promptPreview}
+              </pre>
+            )}
+          </section>
+
           <section style={{ marginBottom: "24px" }}>
             <h2 style={{ fontSize: "18px", marginBottom: "12px" }}>Audio Devices</h2>
 
@@ -392,6 +603,84 @@ function SettingsPage() {
             <small>Press Cmd+Shift+E to start/stop recording</small>
           </div>
 
+          <div style={{ display: "flex", gap: "8px", marginBottom: "16px" }}>
+            <input
+              type="text"
+              value={searchQuery}
+              onChange={(e) => setSearchQuery(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") searchTranscripts();
+                if (e.key === "Escape") {
+                  setSearchQuery("");
+                  setSearchResults(null);
+                }
+              }}
+              placeholder='Search transcripts (e.g. budget freeze, "hiring plan")'
+              style={{ flex: 1, padding: "8px", borderRadius: "4px", border: "1px solid #ccc" }}
+            />
+            <button
+              onClick={searchTranscripts}
+              style={{ padding: "8px 16px", background: "#007aff", color: "white", border: "none", borderRadius: "4px", cursor: "pointer" }}
+            >
+              Search
+            </button>
+          </div>
+
+          {searchResults !== null && (
+            <div style={{ marginBottom: "24px" }}>
+              {searchResults.length === 0 ? (
+                <p style={{ color: "#666" }}>No transcript mentions "{searchQuery}".</p>
+              ) : (
+                <ul style={{ listStyle: "none", padding: 0 }}>
+                  {searchResults.map((m) => (
+                    <li
+                      key={m.transcription_id}
+                      style={{ padding: "8px 12px", background: "white", borderRadius: "6px", marginBottom: "6px", border: "1px solid #e0e0e0" }}
+                    >
+                      <div style={{ fontSize: "12px", color: "#666" }}>
+                        {m.recording_name} · {new Date(m.timestamp).toLocaleString()}
+                      </div>
+                      <div style={{ fontSize: "14px" }}>
+                        <Snippet text={m.snippet} />
+                      </div>
+                    </li>
+                  ))}
+                </ul>
+              )}
+            </div>
+          )}
+
+          <div style={{ display: "flex", gap: "8px", marginBottom: "8px" }}>
+            <select
+              value={exportFormat}
+              onChange={(e) => setExportFormat(e.target.value)}
+              style={{ padding: "8px", borderRadius: "4px", border: "1px solid #ccc" }}
+            >
+              <option value="markdown">Markdown</option>
+              <option value="srt">SRT</option>
+              <option value="vtt">WebVTT</option>
+              <option value="json">JSON</option>
+              <option value="text">Text</option>
+            </select>
+            <input
+              type="text"
+              value={exportFolder}
+              onChange={(e) => setExportFolder(e.target.value)}
+              placeholder="Export folder (default ~/Documents/Robert)"
+              style={{ flex: 1, padding: "8px", borderRadius: "4px", border: "1px solid #ccc" }}
+            />
+            <button
+              onClick={exportAllRecordings}
+              disabled={recordings.length === 0}
+              style={{ padding: "8px 16px", background: "#e0e0e0", border: "none", borderRadius: "4px", cursor: "pointer" }}
+            >
+              Export all
+            </button>
+          </div>
+          {exportStatus && (
+            <div style={{ marginBottom: "16px", fontSize: "12px", color: "#666" }}>{exportStatus}</div>
+          )}
+
           {recordings.length === 0 ? (
             <p style={{ color: "#666" }}>No recordings yet.</p>
           ) : (
@@ -458,6 +747,12 @@ function SettingsPage() {
                         >
                           Rename
                         </button>
+                        <button
+                          onClick={() => exportRecording(r.id)}
+                          style={{ padding: "4px 8px", background: "#e0e0e0", border: "none", borderRadius: "4px", cursor: "pointer", fontSize: "12px" }}
+                        >
+                          Export
+                        </button>
                         <button
                           onClick={() => deleteRecording(r.id)}
                           style={{ padding: "4px 8px", background: "#ff3b30", color: "white", border: "none", borderRadius: "4px", cursor: "pointer", fontSize: "12px" }}
@@ -475,6 +770,118 @@ function SettingsPage() {
         </section>
       )}
 
+      {activeTab === "notes" && (
+        <section>
+          <div style={{ marginBottom: "16px", color: "#666" }}>
+            <small>Say "Robert, add review PR 42 to my todo list" or "note that the deploy is Thursday"</small>
+          </div>
+
+          <h2 style={{ fontSize: "18px", marginBottom: "8px" }}>To-do</h2>
+          <div style={{ display: "flex", gap: "8px", marginBottom: "12px" }}>
+            <input
+              type="text"
+              value={newTodo}
+              onChange={(e) => setNewTodo(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") addTodo();
+              }}
+              placeholder="New to-do"
+              style={{ flex: 1, padding: "8px", borderRadius: "4px", border: "1px solid #ccc" }}
+            />
+            <button
+              onClick={addTodo}
+              style={{ padding: "8px 16px", background: "#007aff", color: "white", border: "none", borderRadius: "4px", cursor: "pointer" }}
+            >
+              Add
+            </button>
+          </div>
+          {todos.length === 0 ? (
+            <p style={{ color: "#666" }}>Nothing to do.</p>
+          ) : (
+            <ul style={{ listStyle: "none", padding: 0, marginBottom: "24px" }}>
+              {todos.map((t) => (
+                <li
+                  key={t.id}
+                  style={{
+                    display: "flex",
+                    alignItems: "center",
+                    gap: "8px",
+                    padding: "8px 12px",
+                    background: "white",
+                    borderRadius: "6px",
+                    marginBottom: "6px",
+                    border: "1px solid #e0e0e0",
+                  }}
+                >
+                  <input type="checkbox" checked={t.done} onChange={(e) => setTodoDone(t.id, e.target.checked)} />
+                  <span style={{ flex: 1, textDecoration: t.done ? "line-through" : "none", color: t.done ? "#999" : "inherit" }}>
+                    {t.title}
+                  </span>
+                  <button
+                    onClick={() => deleteTodo(t.id)}
+                    style={{ padding: "4px 8px", background: "#e0e0e0", border: "none", borderRadius: "4px", cursor: "pointer", fontSize: "12px" }}
+                  >
+                    Delete
+                  </button>
+                </li>
+              ))}
+            </ul>
+          )}
+
+          <h2 style={{ fontSize: "18px", marginBottom: "8px" }}>Notes</h2>
+          <div style={{ display: "flex", gap: "8px", marginBottom: "12px" }}>
+            <input
+              type="text"
+              value={newNote}
+              onChange={(e) => setNewNote(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") addNote();
+              }}
+              placeholder="New note"
+              style={{ flex: 1, padding: "8px", borderRadius: "4px", border: "1px solid #ccc" }}
+            />
+            <button
+              onClick={addNote}
+              style={{ padding: "8px 16px", background: "#007aff", color: "white", border: "none", borderRadius: "4px", cursor: "pointer" }}
+            >
+              Add
+            </button>
+          </div>
+          {notes.length === 0 ? (
+            <p style={{ color: "#666" }}>No notes yet.</p>
+          ) : (
+            <ul style={{ listStyle: "none", padding: 0 }}>
+              {notes.map((n) => (
+                <li
+                  key={n.id}
+                  style={{
+                    display: "flex",
+                    justifyContent: "space-between",
+                    alignItems: "center",
+                    padding: "8px 12px",
+                    background: "white",
+                    borderRadius: "6px",
+                    marginBottom: "6px",
+                    border: "1px solid #e0e0e0",
+                  }}
+                >
+                  <div>
+                    <div>{n.content}</div>
+                    <div style={{ fontSize: "12px", color: "#666" }}>{new Date(n.created_at).toLocaleString()}</div>
+                  </div>
+                  <button
+                    onClick={() => deleteNote(n.id)}
+                    style={{ padding: "4px 8px", background: "#e0e0e0", border: "none", borderRadius: "4px", cursor: "pointer", fontSize: "12px" }}
+                  >
+                    Delete
+                  </button>
+                </li>
+              ))}
+            </ul>
+          )}
+        </section>
+      )}
+
       {activeTab === "mcp" && (
         <section>
           <p style={{ color: "#666", marginBottom: "16px" }}>